@@ -0,0 +1,260 @@
+use http::StatusCode;
+use log::{debug, error};
+use serde_json::Value as JsonValue;
+
+use super::{ParamPosition, TestOperation, TestParam};
+use crate::{
+    spec::{is_json_media_type, Operation, SchemaContext},
+    validation::{Error as ValidationError, ValidationTree},
+    Schema, Spec,
+};
+
+/// A contract test built from the examples documented in a spec.
+///
+/// Pairs a request example with the schema of the operation's expected success response.
+#[derive(Debug)]
+pub struct TestCase {
+    /// Name of the case, e.g. `POST /pets (application/json: dog)`.
+    pub name: String,
+
+    pub operation: TestOperation,
+
+    /// Values for the operation's required parameters.
+    pub params: Vec<TestParam>,
+
+    /// Media type of the request body, if the operation has one.
+    pub media_type: Option<String>,
+
+    /// Example request body.
+    pub body: Option<JsonValue>,
+
+    /// Expected response status; the lowest explicitly documented 2xx status.
+    pub status: StatusCode,
+
+    /// Validator for the expected response body, if the response declares a JSON schema.
+    pub response_validator: Option<ValidationTree>,
+}
+
+impl TestCase {
+    /// Validates a live response body against the expected response schema.
+    pub fn check_response(&self, actual: &JsonValue) -> Result<(), ValidationError> {
+        match self.response_validator {
+            Some(ref validator) => validator.validate(actual),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Builds a test case for each request body example of each operation in `spec`.
+///
+/// Operations without a request body get a single case. Required parameter values are taken
+/// from their schemas' defaults or generated examples. Operations with no explicit 2xx response
+/// are skipped.
+pub fn cases_from_spec(spec: &Spec) -> Vec<TestCase> {
+    let mut cases = vec![];
+
+    for op in spec.operations() {
+        let (path, method) = (op.path_template(), op.method().clone());
+
+        let (status, response_schema) = match success_response(spec, &op) {
+            Some(res) => res,
+            None => {
+                debug!("no success response for {} {}, skipping", method, path);
+                continue;
+            }
+        };
+
+        let params = example_params(spec, &op);
+        let name = format!("{} {}", method, path);
+        let operation = TestOperation::new(method, path);
+
+        let response_validator = || {
+            response_schema.as_ref().and_then(|schema| {
+                ValidationTree::from_schema(schema, spec)
+                    // TODO: find better error solution
+                    .map_err(|err| error!("{}", err))
+                    .ok()
+            })
+        };
+
+        let examples = op.request_examples(spec);
+
+        if examples.is_empty() {
+            cases.push(TestCase {
+                name,
+                operation,
+                params,
+                media_type: None,
+                body: None,
+                status,
+                response_validator: response_validator(),
+            });
+            continue;
+        }
+
+        for (media_type, examples) in examples {
+            for (example_name, body) in examples {
+                cases.push(TestCase {
+                    name: format!("{} ({}: {})", name, media_type, example_name),
+                    operation: operation.clone(),
+                    params: params.clone(),
+                    media_type: Some(media_type.clone()),
+                    body: Some(body),
+                    status,
+                    response_validator: response_validator(),
+                });
+            }
+        }
+    }
+
+    cases
+}
+
+/// Returns the lowest documented 2xx status of `op` and its JSON response schema, if any.
+fn success_response(spec: &Spec, op: &Operation) -> Option<(StatusCode, Option<Schema>)> {
+    let (status, res) = op.responses(spec).into_iter().find_map(|(status, res)| {
+        status
+            .parse::<StatusCode>()
+            .ok()
+            .filter(StatusCode::is_success)
+            .map(|status| (status, res))
+    })?;
+
+    let schema = res
+        .content
+        .iter()
+        .find(|(media_type, _)| is_json_media_type(media_type))
+        .and_then(|(_, media_spec)| media_spec.schema.as_ref())
+        .and_then(|schema| {
+            schema
+                .resolve(spec)
+                .map(|schema| schema.for_context(spec, SchemaContext::Response))
+                .map_err(|err| error!("{}", err))
+                .ok()
+        });
+
+    Some((status, schema))
+}
+
+/// Returns example values for the required parameters of `op`.
+///
+/// A parameter's own `example`, or its first `examples` value, is preferred over its schema's
+/// default and a value generated from the schema.
+fn example_params(spec: &Spec, op: &Operation) -> Vec<TestParam> {
+    op.parameters(spec)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|param| param.required == Some(true) || param.location == "path")
+        .filter_map(|param| {
+            let position = match param.location.as_str() {
+                "query" => ParamPosition::Query,
+                "header" => ParamPosition::Header,
+                "path" => ParamPosition::Path,
+                "cookie" => ParamPosition::Cookie,
+                _ => return None,
+            };
+
+            let example = param.examples.as_ref().and_then(|examples| {
+                examples
+                    .resolve_all(spec)
+                    .into_values()
+                    .find_map(|example| example.value)
+            });
+
+            let value = match example.or_else(|| param.default_value(spec)) {
+                Some(val) => val,
+                None => param.effective_schema(spec).ok()??.generate_example(spec),
+            };
+
+            let value = match value {
+                JsonValue::String(val) => val,
+                val => val.to_string(),
+            };
+
+            Some(TestParam::new(param.name, value, position))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn cases_from_examples() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /pets/{id}:
+    get:
+      parameters:
+        - { name: id, in: path, required: true, schema: { type: integer } }
+        - name: fields
+          in: query
+          required: true
+          schema: { type: string, default: all }
+          examples:
+            short: { value: name }
+      responses:
+        "200":
+          description: pet
+          content:
+            application/json:
+              schema: { $ref: "#/components/schemas/Pet" }
+  /pets:
+    post:
+      requestBody:
+        content:
+          application/json:
+            examples:
+              dog: { value: { name: Rex } }
+              cat: { value: { name: Tom } }
+      responses:
+        "201":
+          description: created
+          content:
+            application/json:
+              schema: { $ref: "#/components/schemas/Pet" }
+        default:
+          description: error
+components:
+  schemas:
+    Pet:
+      type: object
+      required: [name]
+      properties:
+        name: { type: string }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let cases = cases_from_spec(&spec);
+        let names = cases.iter().map(|case| &case.name).collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "POST /pets (application/json: cat)",
+                "POST /pets (application/json: dog)",
+                "GET /pets/{id}",
+            ]
+        );
+
+        let post = &cases[1];
+        assert_eq!(post.status, StatusCode::CREATED);
+        assert_eq!(post.body, Some(json!({ "name": "Rex" })));
+
+        let get = &cases[2];
+        assert_eq!(get.params.len(), 2);
+        assert_eq!(get.params[0].value, "0");
+        assert_eq!(get.params[0].position, ParamPosition::Path);
+        assert_eq!(get.params[1].value, "name");
+
+        get.check_response(&json!({ "name": "Rex" })).unwrap();
+        assert!(get.check_response(&json!({ "age": 3 })).is_err());
+    }
+}
@@ -0,0 +1,596 @@
+use std::collections::BTreeMap;
+
+use serde_json::Value as JsonValue;
+
+use crate::{
+    json_pointer::pointer,
+    spec::{
+        Callback, Error as SpecError, FromRef, Header, MediaType, MediaTypeExamples,
+        ObjectOrReference, Parameter, PathItem, RefError, Response, SchemaContext,
+    },
+    validation::{Error as ValidationError, ValidationOptions},
+    Schema, Spec,
+};
+
+/// The result of [`verify_spec_examples`].
+#[derive(Debug, Default)]
+pub struct ConformanceReport {
+    /// Number of examples checked, including those that failed.
+    pub checked: usize,
+
+    /// Examples that are not valid, in document order.
+    pub failures: Vec<ExampleFailure>,
+}
+
+impl ConformanceReport {
+    /// Returns true if every example checked is valid.
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// An example that does not conform to its schema, or that could not be checked.
+#[derive(Debug)]
+pub struct ExampleFailure {
+    /// JSON pointer to the example, e.g.
+    /// `#/paths/~1pets/post/requestBody/content/application~1json/examples/dog`.
+    pub location: String,
+
+    /// All errors found in the example.
+    pub errors: Vec<ValidationError>,
+}
+
+/// Validates every example declared in `spec` against its schema.
+///
+/// Checks the examples of request bodies, responses and response headers of all operations,
+/// including webhooks and callbacks, the examples of parameters and of their `content`, and the
+/// `example` and `examples` of all schemas, inline or in `components`. Responses, request
+/// bodies, parameters, headers and callbacks in `components` are checked once, where they are
+/// declared, whether or not they are used. Request body and parameter examples are validated as
+/// requests and response and header examples as responses, so `readOnly` properties are not
+/// required in requests and `writeOnly` properties are not required in responses, also within
+/// referenced schemas; see [`Schema::for_context`]. Parameter examples must also be
+/// serializable with the parameter's style; see [`Parameter::validate_examples`].
+///
+/// Example `$ref`s are followed, and ones that can not be resolved are reported as failures, as
+/// are schemas, request bodies, responses and callbacks that can not be resolved. Examples with
+/// only an `externalValue` and media types without a schema are skipped.
+///
+/// Failures are listed in document order: paths, then components, then webhooks. Within a
+/// media type or parameter, failures of its schema come before those of its examples, and a
+/// schema's own examples come before those of its subschemas.
+pub fn verify_spec_examples(spec: &Spec) -> ConformanceReport {
+    let mut report = ConformanceReport::default();
+
+    check_path_items(spec, &["paths"], &spec.paths, &mut report);
+
+    if let Some(ref components) = spec.components {
+        for (name, schema) in &components.schemas {
+            if let ObjectOrReference::Object(schema) = schema {
+                check_schema(spec, schema, &["components", "schemas", name], &mut report);
+            }
+        }
+
+        for (name, res) in &components.responses {
+            if let ObjectOrReference::Object(res) = res {
+                check_response(spec, res, &["components", "responses", name], &mut report);
+            }
+        }
+
+        for (name, param) in &components.parameters {
+            if let ObjectOrReference::Object(param) = param {
+                check_parameter(
+                    spec,
+                    param,
+                    &["components", "parameters", name],
+                    &mut report,
+                );
+            }
+        }
+
+        for (name, body) in &components.request_bodies {
+            if let ObjectOrReference::Object(body) = body {
+                let parent = ["components", "requestBodies", name];
+                check_content(
+                    spec,
+                    &body.content,
+                    SchemaContext::Request,
+                    &parent,
+                    &mut report,
+                );
+            }
+        }
+
+        for (name, header) in &components.headers {
+            if let ObjectOrReference::Object(header) = header {
+                check_header(spec, header, &["components", "headers", name], &mut report);
+            }
+        }
+
+        for (name, callback) in &components.callbacks {
+            if let ObjectOrReference::Object(callback) = callback {
+                check_callback(
+                    spec,
+                    callback,
+                    &["components", "callbacks", name],
+                    &mut report,
+                );
+            }
+        }
+    }
+
+    check_path_items(spec, &["webhooks"], &spec.webhooks, &mut report);
+
+    report
+}
+
+fn options() -> ValidationOptions {
+    ValidationOptions {
+        collect_all: true,
+        ..ValidationOptions::default()
+    }
+}
+
+/// Records a single example, or the object at `location`, as failed with a spec error.
+fn fail(location: String, err: SpecError, report: &mut ConformanceReport) {
+    report.failures.push(ExampleFailure {
+        location,
+        errors: vec![ValidationError::Schema(err)],
+    });
+}
+
+/// Returns `parent` extended by `segments`.
+fn join<'a>(parent: &[&'a str], segments: &[&'a str]) -> Vec<&'a str> {
+    parent.iter().chain(segments).copied().collect()
+}
+
+/// Reports a reference that can not be resolved, as [`ObjectOrReference`]s in `components` are
+/// checked where they are declared.
+fn check_resolvable<T: FromRef>(
+    spec: &Spec,
+    oor: &ObjectOrReference<T>,
+    parent: &[&str],
+    report: &mut ConformanceReport,
+) {
+    if let Err(err) = oor.resolve(spec) {
+        fail(pointer(parent), SpecError::Ref(err), report);
+    }
+}
+
+fn check_path_items(
+    spec: &Spec,
+    parent: &[&str],
+    path_items: &BTreeMap<String, PathItem>,
+    report: &mut ConformanceReport,
+) {
+    for (path, path_item) in path_items {
+        for (idx, param) in path_item.parameters.iter().enumerate() {
+            if let ObjectOrReference::Object(param) = param {
+                let idx = idx.to_string();
+                let parent = join(parent, &[path, "parameters", &idx]);
+                check_parameter(spec, param, &parent, report);
+            }
+        }
+
+        for (method, op) in path_item.methods() {
+            let method = method.as_str().to_ascii_lowercase();
+            let op_parent = join(parent, &[path, method.as_str()]);
+
+            for (idx, param) in op.parameters.iter().enumerate() {
+                if let ObjectOrReference::Object(param) = param {
+                    let idx = idx.to_string();
+                    let parent = join(&op_parent, &["parameters", &idx]);
+                    check_parameter(spec, param, &parent, report);
+                }
+            }
+
+            if let Some(ref body) = op.request_body {
+                let parent = join(&op_parent, &["requestBody"]);
+
+                match body {
+                    ObjectOrReference::Object(body) => {
+                        check_content(spec, &body.content, SchemaContext::Request, &parent, report)
+                    }
+                    ObjectOrReference::Ref { .. } => check_resolvable(spec, body, &parent, report),
+                }
+            }
+
+            for (status, res) in &op.responses {
+                let parent = join(&op_parent, &["responses", status]);
+
+                match res {
+                    ObjectOrReference::Object(res) => check_response(spec, res, &parent, report),
+                    ObjectOrReference::Ref { .. } => check_resolvable(spec, res, &parent, report),
+                }
+            }
+
+            for (name, callback) in &op.callbacks {
+                let parent = join(&op_parent, &["callbacks", name]);
+                check_callback(spec, callback, &parent, report);
+            }
+        }
+    }
+}
+
+fn check_response(spec: &Spec, res: &Response, parent: &[&str], report: &mut ConformanceReport) {
+    for (name, header) in &res.headers {
+        let parent = join(parent, &["headers", name]);
+
+        match header {
+            ObjectOrReference::Object(header) => check_header(spec, header, &parent, report),
+            ObjectOrReference::Ref { .. } => check_resolvable(spec, header, &parent, report),
+        }
+    }
+
+    check_content(spec, &res.content, SchemaContext::Response, parent, report);
+}
+
+fn check_header(spec: &Spec, header: &Header, parent: &[&str], report: &mut ConformanceReport) {
+    let schema = match header.schema {
+        Some(ref schema) => schema,
+        None => return,
+    };
+
+    check_schema(spec, schema, &join(parent, &["schema"]), report);
+
+    let examples = match header.examples {
+        Some(ref examples) => example_values(spec, examples, parent),
+        None => return,
+    };
+
+    let schema = schema.for_context(spec, SchemaContext::Response);
+
+    for (location, example) in examples {
+        match example {
+            Ok(example) => check_value(spec, &schema, &example, location, report),
+            Err(err) => {
+                report.checked += 1;
+                fail(location, SpecError::Ref(err), report);
+            }
+        }
+    }
+}
+
+/// Checks the operations of a callback, which is resolved first if it is a reference to a
+/// callback in `components` and otherwise checked where it is declared.
+fn check_callback(
+    spec: &Spec,
+    callback: &Callback,
+    parent: &[&str],
+    report: &mut ConformanceReport,
+) {
+    match callback.path_items(spec) {
+        Ok(_) if callback.is_ref() => {}
+        Ok(path_items) => check_path_items(spec, parent, &path_items, report),
+        Err(err) => fail(pointer(parent), err, report),
+    }
+}
+
+fn check_value(
+    spec: &Spec,
+    schema: &Schema,
+    val: &JsonValue,
+    location: String,
+    report: &mut ConformanceReport,
+) {
+    report.checked += 1;
+
+    if let Err(errors) = schema.validate_with(spec, val, &options()) {
+        report.failures.push(ExampleFailure {
+            location,
+            errors: errors.errors,
+        });
+    }
+}
+
+/// Checks the examples of `schema` and of its inline subschemas.
+fn check_schema(spec: &Spec, schema: &Schema, parent: &[&str], report: &mut ConformanceReport) {
+    let location = |segments: &[&str]| pointer(parent.iter().chain(segments));
+
+    if let Some(ref example) = schema.example {
+        check_value(spec, schema, example, location(&["example"]), report);
+    }
+
+    for (idx, example) in schema.examples.iter().enumerate() {
+        let idx = idx.to_string();
+        check_value(spec, schema, example, location(&["examples", &idx]), report);
+    }
+
+    for (segments, subschema) in schema.inline_subschemas() {
+        let parent = parent
+            .iter()
+            .copied()
+            .chain(segments.iter().map(String::as_str))
+            .collect::<Vec<_>>();
+
+        check_schema(spec, subschema, &parent, report);
+    }
+}
+
+/// Returns the values of `examples` with their locations, or the errors resolving them.
+///
+/// Examples with only an `externalValue` are left out.
+fn example_values(
+    spec: &Spec,
+    examples: &MediaTypeExamples,
+    parent: &[&str],
+) -> Vec<(String, Result<JsonValue, RefError>)> {
+    let location = |segments: &[&str]| pointer(parent.iter().chain(segments));
+
+    match examples {
+        MediaTypeExamples::Example { example } => {
+            vec![(location(&["example"]), Ok(example.clone()))]
+        }
+
+        MediaTypeExamples::Examples { examples } => examples
+            .iter()
+            .filter_map(|(name, example)| {
+                let example = match example.resolve(spec) {
+                    Ok(example) => Ok(example.value?),
+                    Err(err) => Err(err),
+                };
+
+                Some((location(&["examples", name]), example))
+            })
+            .collect(),
+    }
+}
+
+fn check_content(
+    spec: &Spec,
+    content: &BTreeMap<String, MediaType>,
+    context: SchemaContext,
+    parent: &[&str],
+    report: &mut ConformanceReport,
+) {
+    for (media_type, media_spec) in content {
+        let parent = parent
+            .iter()
+            .copied()
+            .chain(["content", media_type])
+            .collect::<Vec<_>>();
+        let schema_location = parent.iter().copied().chain(["schema"]).collect::<Vec<_>>();
+
+        if let Some(ObjectOrReference::Object(ref schema)) = media_spec.schema {
+            check_schema(spec, schema, &schema_location, report);
+        }
+
+        let examples = match media_spec.examples {
+            Some(ref examples) => example_values(spec, examples, &parent),
+            None => continue,
+        };
+
+        let schema = match media_spec.schema {
+            Some(ref schema) => schema.resolve(spec),
+            None => continue,
+        };
+
+        let schema = match schema {
+            Ok(schema) => Some(schema.for_context(spec, context)),
+            Err(err) => {
+                fail(pointer(&schema_location), SpecError::Ref(err), report);
+                None
+            }
+        };
+
+        for (location, example) in examples {
+            match (example, &schema) {
+                (Ok(example), Some(schema)) => {
+                    check_value(spec, schema, &example, location, report)
+                }
+                (Ok(_), None) => {}
+                (Err(err), _) => {
+                    report.checked += 1;
+                    fail(location, SpecError::Ref(err), report);
+                }
+            }
+        }
+    }
+}
+
+fn check_parameter(
+    spec: &Spec,
+    param: &Parameter,
+    parent: &[&str],
+    report: &mut ConformanceReport,
+) {
+    if let Some(ObjectOrReference::Object(ref schema)) = param.schema {
+        let schema_location = parent.iter().copied().chain(["schema"]);
+        check_schema(spec, schema, &schema_location.collect::<Vec<_>>(), report);
+    }
+
+    check_content(spec, &param.content, SchemaContext::Request, parent, report);
+
+    let examples = match param.examples {
+        Some(ref examples) => example_values(spec, examples, parent),
+        None => return,
+    };
+
+    report.checked += examples.len();
+
+    let mut by_location = BTreeMap::<String, Vec<ValidationError>>::new();
+
+    for err in param.validate_examples(spec).err().into_iter().flatten() {
+        // the key is the example name, `example` for a single example, or the parameter name
+        // if its schema is invalid
+        let location = match (&param.examples, err.key.as_str()) {
+            (Some(MediaTypeExamples::Example { .. }), _) => {
+                pointer(parent.iter().chain(&["example"]))
+            }
+            (Some(MediaTypeExamples::Examples { examples }), key) if examples.contains_key(key) => {
+                pointer(parent.iter().chain(&["examples", key]))
+            }
+            _ => pointer(parent),
+        };
+
+        by_location.entry(location).or_default().push(err.error);
+    }
+
+    if let Some(errors) = by_location.remove(&pointer(parent)) {
+        report.failures.push(ExampleFailure {
+            location: pointer(parent),
+            errors,
+        });
+    }
+
+    for (location, example) in examples {
+        match example {
+            Ok(_) => {
+                if let Some(errors) = by_location.remove(&location) {
+                    report.failures.push(ExampleFailure { location, errors });
+                }
+            }
+            Err(err) => fail(location, SpecError::Ref(err), report),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_examples() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /batch:
+    post:
+      parameters:
+        - name: filter
+          in: query
+          content:
+            application/json:
+              schema: { type: object, required: [q] }
+              example: {}
+      requestBody:
+        content:
+          application/json:
+            schema:
+              type: array
+              items: { $ref: "#/components/schemas/Pet" }
+            example: [{ name: Rex }]
+      responses:
+        "200":
+          description: ok
+          headers:
+            X-Rate-Limit:
+              schema: { type: integer }
+              example: many
+      callbacks:
+        done:
+          "{$request.body#/url}":
+            post:
+              requestBody:
+                content:
+                  application/json:
+                    schema: { type: integer }
+                    example: late
+              responses:
+                "200": { description: ok }
+  /pets:
+    get:
+      parameters:
+        - name: limit
+          in: query
+          schema: { type: integer }
+          examples:
+            ten: { value: 10 }
+            many: { value: many }
+      responses:
+        "200":
+          $ref: "#/components/responses/Pets"
+    post:
+      requestBody:
+        content:
+          application/json:
+            schema: { $ref: "#/components/schemas/Pet" }
+            examples:
+              dog: { value: { name: Rex } }
+              cat: { $ref: "#/components/examples/Cat" }
+              bird: { $ref: "#/components/examples/Bird" }
+              missing: { $ref: "#/components/examples/Missing" }
+      responses:
+        "201":
+          description: created
+          content:
+            application/json:
+              schema: { $ref: "#/components/schemas/Pet" }
+              example: { id: 1, name: Rex }
+components:
+  schemas:
+    Pet:
+      type: object
+      required: [id, name]
+      properties:
+        id: { type: integer, readOnly: true }
+        name: { type: string, example: 1 }
+      examples:
+        - { id: 1, name: Rex }
+        - { id: one, name: Rex }
+    Node:
+      type: object
+      properties:
+        child: { $ref: "#/components/schemas/Node" }
+      example: { child: { child: {} } }
+  requestBodies:
+    Unused:
+      content:
+        application/json:
+          schema: { type: integer }
+          example: one
+  headers:
+    Unused:
+      schema: { type: integer }
+      examples:
+        bad: { value: two }
+  examples:
+    Cat: { value: { name: Tom } }
+    Bird: { value: { name: 1 } }
+  responses:
+    Pets:
+      description: pets
+      content:
+        application/json:
+          schema:
+            type: array
+            items: { $ref: "#/components/schemas/Pet" }
+            example: [1]
+          example: [{ name: Rex }]
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let report = verify_spec_examples(&spec);
+        assert!(!report.is_ok());
+        assert_eq!(report.checked, 19);
+
+        let locations = report
+            .failures
+            .iter()
+            .map(|failure| failure.location.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            locations,
+            [
+                "#/paths/~1batch/post/parameters/0/content/application~1json/example",
+                "#/paths/~1batch/post/responses/200/headers/X-Rate-Limit/example",
+                "#/paths/~1batch/post/callbacks/done/{$request.body#~1url}/post/requestBody\
+                 /content/application~1json/example",
+                "#/paths/~1pets/get/parameters/0/examples/many",
+                "#/paths/~1pets/post/requestBody/content/application~1json/examples/bird",
+                "#/paths/~1pets/post/requestBody/content/application~1json/examples/missing",
+                "#/components/schemas/Pet/examples/1",
+                "#/components/schemas/Pet/properties/name/example",
+                "#/components/responses/Pets/content/application~1json/schema/example",
+                "#/components/responses/Pets/content/application~1json/example",
+                "#/components/requestBodies/Unused/content/application~1json/example",
+                "#/components/headers/Unused/examples/bad",
+            ]
+        );
+    }
+}
@@ -1,4 +1,6 @@
 mod auth;
+mod case;
+mod examples;
 mod operation;
 mod param;
 mod request;
@@ -7,6 +9,8 @@ mod runner;
 mod test;
 
 pub use auth::*;
+pub use case::*;
+pub use examples::*;
 pub use operation::*;
 pub use param::*;
 pub use request::*;
@@ -85,15 +85,31 @@ impl TestRunner {
 
         // TODO: add other param types to request
 
-        let res = client
-            .request(method, &url.to_string())
-            .headers(req.headers.clone())
+        // reqwest uses a newer version of the `http` crate so headers and status are converted
+        let res = req
+            .headers
+            .iter()
+            .fold(
+                client.request(method, url.to_string()),
+                |builder, (name, val)| builder.header(name.as_str(), val.as_bytes()),
+            )
             .body(req.body.to_vec())
             .send()
             .await?;
 
-        let status = res.status();
-        let headers = res.headers().clone();
+        let status =
+            http::StatusCode::from_u16(res.status().as_u16()).map_err(http::Error::from)?;
+        let headers = res
+            .headers()
+            .iter()
+            .map(|(name, val)| {
+                Ok((
+                    http::HeaderName::from_bytes(name.as_str().as_bytes())
+                        .map_err(http::Error::from)?,
+                    http::HeaderValue::from_bytes(val.as_bytes()).map_err(http::Error::from)?,
+                ))
+            })
+            .collect::<Result<_, Error>>()?;
 
         let body_is_empty = res.content_length().map(|x| x == 0).unwrap_or(false);
         let body = if body_is_empty {
@@ -3,7 +3,7 @@ use http::HeaderMap;
 use log::{debug, trace};
 
 use crate::{
-    spec::{Error as SpecError, RefError},
+    spec::{is_json_media_type, Error as SpecError, RefError},
     validation::{Error as ValidationError, ValidationTree},
     Error, Spec,
 };
@@ -82,16 +82,8 @@ impl ConformanceTestSpec {
             }
 
             OperationSpec::OperationId(op_id) => spec
-                .operations()
-                .find(|(path, method, op)| {
-                    trace!("checking {} {} ({:?})", &method, &path, &op);
-
-                    op.operation_id
-                        .as_ref()
-                        .map(|id| id == op_id)
-                        .unwrap_or(false)
-                })
-                .map(|(path, method, _op)| TestOperation::new(method.clone(), path.clone()))
+                .operation_by_id(op_id)
+                .map(|op| TestOperation::new(op.method().clone(), op.path_template()))
                 .ok_or_else(|| ValidationError::OperationIdNotFound(op_id.clone()))?,
         };
 
@@ -183,7 +175,12 @@ impl ConformanceTestSpec {
                         &name
                     ))))?;
 
-                if let Some(ref ex) = example.value {
+                if !is_json_media_type(media_type) {
+                    debug!(
+                        "not validating example of non-JSON media type: {}",
+                        media_type
+                    );
+                } else if let Some(ref ex) = example.value {
                     // check example validity
                     let validator = ValidationTree::from_schema(&schema, spec)?;
 
@@ -202,7 +199,7 @@ impl ConformanceTestSpec {
                     operation: test_op.clone(),
                     headers: hdrs,
                     params: self.resolve_params(spec)?,
-                    body: example.as_bytes().into(),
+                    body: example.body_bytes(media_type).into(),
                 }
             }
         };
@@ -277,6 +274,20 @@ impl ConformanceTestSpec {
                                 &name
                             ))))?;
 
+                    // bodies of other media types can not be validated against the schema
+                    if !is_json_media_type(media_type) {
+                        debug!(
+                            "not validating example of non-JSON media type: {}",
+                            media_type
+                        );
+
+                        return Ok(TestResponseSpec {
+                            operation: test_op.clone(),
+                            status: *status,
+                            body_validator: None,
+                        });
+                    }
+
                     // create validator
                     let validator = ValidationTree::from_schema(&schema, spec)?;
 
@@ -341,4 +352,44 @@ mod tests {
             ResponseSpec::from_schema(401, "application/json"),
         );
     }
+
+    #[test]
+    fn xml_examples_are_not_validated() {
+        let spec = crate::from_reader(
+            r#"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /pets:
+    post:
+      requestBody:
+        content:
+          application/xml:
+            schema: { type: object }
+            examples:
+              rex: { value: "<pet><name>Rex</name></pet>" }
+      responses:
+        "200":
+          description: pet
+          content:
+            application/xml:
+              schema: { type: object }
+              examples:
+                rex: { value: "<pet><name>Rex</name></pet>" }
+"#
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let test = ConformanceTestSpec::named(
+            "xml",
+            OperationSpec::post("/pets"),
+            RequestSpec::from_example("application/xml", "rex"),
+            ResponseSpec::from_example(200, "application/xml", "rex"),
+        );
+
+        let resolved = test.resolve(&spec).unwrap();
+        assert_eq!(&resolved.request.body[..], b"<pet><name>Rex</name></pet>");
+        assert!(resolved.response.body_validator.is_none());
+    }
 }
@@ -0,0 +1,241 @@
+//! Human readable summaries of specs.
+
+use std::{collections::BTreeMap, fmt::Write as _};
+
+use http::Method;
+
+use crate::{
+    spec::{ObjectOrReference, Operation},
+    Spec,
+};
+
+/// Renders a Markdown overview of a spec.
+///
+/// The overview has the spec's title, version and description, its operations grouped by their
+/// first tag, with summaries, and a table of the properties of each component schema. Tags
+/// declared in the spec's `tags` come first, in declaration order, followed by other tags in
+/// alphabetical order and then untagged operations. Webhooks are not included.
+pub fn to_markdown(spec: &Spec) -> String {
+    let mut out = String::new();
+
+    let _ = writeln!(out, "# {} {}", spec.info.title, spec.info.version);
+
+    if let Some(ref description) = spec.info.description {
+        let _ = write!(out, "\n{}\n", description.trim());
+    }
+
+    let mut groups = BTreeMap::<Option<&str>, Vec<(&str, Method, &Operation)>>::new();
+
+    for (path, path_item) in &spec.paths {
+        for (method, op) in path_item.methods() {
+            let tag = op.tags.first().map(String::as_str);
+            groups.entry(tag).or_default().push((path, method, op));
+        }
+    }
+
+    if !groups.is_empty() {
+        out.push_str("\n## Operations\n");
+    }
+
+    let declared = spec
+        .tags
+        .iter()
+        .map(|tag| tag.name.as_str())
+        .collect::<Vec<_>>();
+    let undeclared = groups
+        .keys()
+        .flatten()
+        .filter(|tag| !declared.contains(tag))
+        .copied()
+        .collect::<Vec<_>>();
+
+    let tags = declared.iter().chain(&undeclared).copied().map(Some);
+
+    for tag in tags.chain([None]) {
+        let ops = match groups.get(&tag) {
+            Some(ops) => ops,
+            None => continue,
+        };
+
+        let _ = write!(out, "\n### {}\n\n", tag.unwrap_or("Other"));
+
+        let description = spec
+            .tags
+            .iter()
+            .find(|declared| Some(declared.name.as_str()) == tag)
+            .and_then(|declared| declared.description.as_deref());
+
+        if let Some(description) = description {
+            let _ = write!(out, "{}\n\n", description.trim());
+        }
+
+        for (path, method, op) in ops {
+            let _ = write!(out, "- `{} {}`", method, path);
+
+            if let Some(summary) = op.summary.as_deref().or(op.operation_id.as_deref()) {
+                let _ = write!(out, ": {}", summary);
+            }
+
+            if op.deprecated == Some(true) {
+                out.push_str(" *(deprecated)*");
+            }
+
+            out.push('\n');
+        }
+    }
+
+    let schemas = spec
+        .components
+        .as_ref()
+        .map(|components| &components.schemas)
+        .filter(|schemas| !schemas.is_empty());
+
+    if let Some(schemas) = schemas {
+        out.push_str("\n## Schemas\n");
+
+        for (name, schema) in schemas {
+            let _ = write!(out, "\n### {}\n\n", name);
+
+            let schema = match schema {
+                ObjectOrReference::Object(schema) => schema,
+                ObjectOrReference::Ref { ref_path, .. } => {
+                    let _ = writeln!(out, "See `{}`.", ref_path);
+                    continue;
+                }
+            };
+
+            if let Some(ref description) = schema.description {
+                let _ = write!(out, "{}\n\n", description.trim());
+            }
+
+            if schema.properties.is_empty() {
+                let _ = writeln!(out, "Type: {}", schema.display_name());
+                continue;
+            }
+
+            out.push_str("| Property | Type | Required | Description |\n");
+            out.push_str("| --- | --- | --- | --- |\n");
+
+            for (prop_name, prop) in &schema.properties {
+                let (type_name, description) = match prop {
+                    ObjectOrReference::Object(prop) => {
+                        (prop.display_name(), prop.description.as_deref())
+                    }
+                    ObjectOrReference::Ref { ref_path, .. } => (
+                        ref_path.rsplit('/').next().unwrap_or_default().to_owned(),
+                        None,
+                    ),
+                };
+
+                let required = if schema.required.contains(prop_name) {
+                    "yes"
+                } else {
+                    "no"
+                };
+
+                let _ = writeln!(
+                    out,
+                    "| `{}` | {} | {} | {} |",
+                    prop_name,
+                    table_cell(&type_name),
+                    required,
+                    table_cell(description.unwrap_or_default()),
+                );
+            }
+        }
+    }
+
+    out
+}
+
+/// Escapes text for use in a Markdown table cell.
+fn table_cell(text: &str) -> String {
+    text.trim().replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdown_overview() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info:
+  title: Petstore
+  version: 1.0.0
+  description: A pet store.
+tags:
+  - { name: pets, description: Everything about pets. }
+paths:
+  /pets:
+    get:
+      tags: [pets]
+      summary: List pets
+      responses: {}
+  /health:
+    get:
+      operationId: health
+      deprecated: true
+      responses: {}
+  /users:
+    get:
+      tags: [users]
+      responses: {}
+components:
+  schemas:
+    Pet:
+      description: A pet.
+      required: [name]
+      properties:
+        name: { type: string, description: "Name | nickname" }
+        owner: { $ref: "#/components/schemas/Person" }
+        tags: { type: array, items: { type: string } }
+    Id: { type: integer }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            to_markdown(&spec),
+            r#"# Petstore 1.0.0
+
+A pet store.
+
+## Operations
+
+### pets
+
+Everything about pets.
+
+- `GET /pets`: List pets
+
+### users
+
+- `GET /users`
+
+### Other
+
+- `GET /health`: health *(deprecated)*
+
+## Schemas
+
+### Id
+
+Type: integer
+
+### Pet
+
+A pet.
+
+| Property | Type | Required | Description |
+| --- | --- | --- | --- |
+| `name` | string | yes | Name \| nickname |
+| `owner` | Person | no |  |
+| `tags` | array of string | no |  |
+"#
+        );
+    }
+}
@@ -14,6 +14,11 @@ pub enum Error {
     #[display(fmt = "I/O error")]
     Io(io::Error),
 
+    #[cfg(feature = "gzip")]
+    #[display(fmt = "Corrupt gzip stream")]
+    #[from(ignore)]
+    Gzip(io::Error),
+
     #[display(fmt = "Yaml error")]
     Yaml(serde_yaml::Error),
 
@@ -30,4 +35,8 @@ pub enum Error {
     #[cfg(feature = "conformance")]
     #[display(fmt = "Reqwest error")]
     Reqwest(reqwest::Error),
+
+    #[cfg(feature = "conformance")]
+    #[display(fmt = "HTTP error")]
+    Http(http::Error),
 }
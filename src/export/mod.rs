@@ -0,0 +1,5 @@
+//! Conversion of specs into the formats of other API tools.
+
+mod postman;
+
+pub use postman::*;
@@ -0,0 +1,411 @@
+//! Export to [Postman Collection v2.1] format.
+//!
+//! [Postman Collection v2.1]: https://schema.postman.com/collection/json/v2.1.0/draft-07/docs/index.html
+
+use std::collections::BTreeMap;
+
+use log::error;
+use serde_json::{json, Value as JsonValue};
+
+use crate::{
+    spec::{
+        is_json_media_type, MediaTypeExamples, Operation, Parameter, PathItem, SecurityRequirement,
+        SecurityScheme,
+    },
+    Spec,
+};
+
+const POSTMAN_SCHEMA: &str = "https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
+
+/// Converts a spec into a Postman Collection (v2.1).
+///
+/// Each operation becomes a request, grouped into folders by its first tag. Requests use a
+/// `{{baseUrl}}` collection variable, set to the URL of the first server. Path, query and header
+/// parameters are filled in with their example, default or a generated value, serialized as
+/// their style describes and percent-encoded; see [`Parameter::serialize_value`]. Request bodies
+/// get the first example of the first JSON media type (or the first media type), or a value
+/// generated from its schema if it has no examples.
+///
+/// The first security requirement is mapped to Postman auth settings, with credentials left as
+/// collection variables such as `{{bearerToken}}`. OpenID Connect is mapped to OAuth 2.0.
+///
+/// Not exported: cookie parameters, response examples, callbacks and webhooks, alternative
+/// security requirements and requirements combining several schemes (only the first scheme is
+/// used).
+pub fn to_postman(spec: &Spec) -> JsonValue {
+    let mut folders = BTreeMap::<&str, Vec<JsonValue>>::new();
+    let mut items = vec![];
+
+    for (path, path_item) in &spec.paths {
+        for (method, op) in path_item.methods() {
+            let item = request_item(spec, path, &method, op, path_item);
+
+            match op.tags.first() {
+                Some(tag) => folders.entry(tag).or_default().push(item),
+                None => items.push(item),
+            }
+        }
+    }
+
+    let folders = folders
+        .into_iter()
+        .map(|(tag, items)| json!({ "name": tag, "item": items }));
+
+    let mut info = json!({
+        "name": spec.info.title,
+        "version": spec.info.version,
+        "schema": POSTMAN_SCHEMA,
+    });
+
+    if let Some(ref description) = spec.info.description {
+        info["description"] = json!(description);
+    }
+
+    let base_url = spec
+        .primary_server()
+        .map(|server| server.url_with_defaults().trim_end_matches('/').to_owned())
+        .unwrap_or_default();
+
+    let mut collection = json!({
+        "info": info,
+        "item": folders.chain(items).collect::<Vec<_>>(),
+        "variable": [{ "key": "baseUrl", "value": base_url }],
+    });
+
+    if let Some(auth) = auth(spec, &spec.security) {
+        collection["auth"] = auth;
+    }
+
+    collection
+}
+
+/// Builds the collection item for an operation.
+fn request_item(
+    spec: &Spec,
+    path: &str,
+    method: &http::Method,
+    op: &Operation,
+    path_item: &PathItem,
+) -> JsonValue {
+    let params = op.effective_parameters(path_item, spec);
+
+    let value_of = |location: &str| {
+        params
+            .iter()
+            .filter(|param| param.location == location)
+            .map(|param| (param, param.serialize_value(&example_value(spec, param))))
+            .collect::<Vec<_>>()
+    };
+
+    let segments = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(
+            |segment| match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                Some(name) => format!(":{}", name),
+                None => segment.to_owned(),
+            },
+        )
+        .collect::<Vec<_>>();
+
+    // a serialized query parameter may consist of several pairs, e.g. `tag=a&tag=b`
+    let query = value_of("query")
+        .into_iter()
+        .flat_map(|(param, serialized)| {
+            serialized
+                .split('&')
+                .map(|pair| {
+                    let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                    let mut query = json!({ "key": key, "value": value });
+
+                    if param.required != Some(true) {
+                        query["disabled"] = json!(true);
+                    }
+
+                    if let Some(ref description) = param.description {
+                        query["description"] = json!(description);
+                    }
+
+                    query
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let enabled_query = query
+        .iter()
+        .filter(|query| query.get("disabled").is_none())
+        .map(|query| {
+            format!(
+                "{}={}",
+                query["key"].as_str().unwrap(),
+                query["value"].as_str().unwrap()
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut raw = format!("{{{{baseUrl}}}}/{}", segments.join("/"));
+
+    if !enabled_query.is_empty() {
+        raw.push('?');
+        raw.push_str(&enabled_query.join("&"));
+    }
+
+    let variables = value_of("path")
+        .into_iter()
+        .map(|(param, value)| json!({ "key": param.name, "value": value }))
+        .collect::<Vec<_>>();
+
+    let mut headers = value_of("header")
+        .into_iter()
+        .map(|(param, value)| json!({ "key": param.name, "value": value }))
+        .collect::<Vec<_>>();
+
+    let mut request = json!({
+        "method": method.as_str(),
+        "url": {
+            "raw": raw,
+            "host": ["{{baseUrl}}"],
+            "path": segments,
+            "query": query,
+            "variable": variables,
+        },
+    });
+
+    if let Some(ref description) = op.description {
+        request["description"] = json!(description);
+    }
+
+    // request examples fall back to a value generated from the schema
+    let examples = op.request_examples(spec);
+    let body = examples
+        .iter()
+        .filter(|(_, examples)| !examples.is_empty())
+        .find(|(media_type, _)| is_json_media_type(media_type))
+        .or_else(|| examples.iter().find(|(_, examples)| !examples.is_empty()))
+        .and_then(|(media_type, examples)| Some((media_type, &examples.first()?.1)));
+
+    if let Some((media_type, example)) = body {
+        headers.push(json!({ "key": "Content-Type", "value": media_type }));
+
+        request["body"] = match example {
+            JsonValue::String(raw) if !is_json_media_type(media_type) => json!({
+                "mode": "raw",
+                "raw": raw,
+            }),
+            example => json!({
+                "mode": "raw",
+                "raw": serde_json::to_string_pretty(example).unwrap(),
+                "options": { "raw": { "language": "json" } },
+            }),
+        };
+    }
+
+    request["header"] = json!(headers);
+
+    if let Some(ref security) = op.security {
+        request["auth"] = auth(spec, security).unwrap_or_else(|| json!({ "type": "noauth" }));
+    }
+
+    json!({
+        "name": op.display_title(method, path),
+        "request": request,
+    })
+}
+
+/// Returns an example value for a parameter, or an empty string if none can be found.
+fn example_value(spec: &Spec, param: &Parameter) -> JsonValue {
+    let declared = match param.examples {
+        Some(MediaTypeExamples::Example { ref example }) => Some(example.clone()),
+        Some(ref examples) => examples
+            .resolve_all(spec)
+            .into_values()
+            .find_map(|example| example.value),
+        None => None,
+    };
+
+    let value = declared.or_else(|| param.default_value(spec)).or_else(|| {
+        param
+            .effective_schema(spec)
+            .map_err(|err| error!("{}", err))
+            .ok()?
+            .map(|schema| schema.generate_example(spec))
+    });
+
+    value.unwrap_or_else(|| JsonValue::String(String::new()))
+}
+
+/// Maps the first scheme of the first security requirement to Postman auth settings.
+///
+/// Returns `None` if there are no requirements or the scheme can not be resolved.
+fn auth(spec: &Spec, security: &[SecurityRequirement]) -> Option<JsonValue> {
+    let name = security.first()?.keys().next()?;
+
+    let scheme = spec
+        .components
+        .as_ref()?
+        .security_schemes
+        .get(name)
+        .map(|oor| oor.resolve(spec))?
+        .map_err(|err| error!("{}", err))
+        .ok()?;
+
+    let attrs = |attrs: &[(&str, &str)]| {
+        attrs
+            .iter()
+            .map(|(key, value)| json!({ "key": key, "value": value, "type": "string" }))
+            .collect::<Vec<_>>()
+    };
+
+    let auth = match scheme {
+        SecurityScheme::ApiKey { name, location } => json!({
+            "type": "apikey",
+            "apikey": attrs(&[("key", &name), ("value", "{{apiKey}}"), ("in", &location)]),
+        }),
+
+        SecurityScheme::Http { scheme, .. } if scheme.eq_ignore_ascii_case("basic") => json!({
+            "type": "basic",
+            "basic": attrs(&[("username", "{{username}}"), ("password", "{{password}}")]),
+        }),
+
+        SecurityScheme::Http { .. } => json!({
+            "type": "bearer",
+            "bearer": attrs(&[("token", "{{bearerToken}}")]),
+        }),
+
+        SecurityScheme::OAuth2 { .. } | SecurityScheme::OpenIdConnect { .. } => json!({
+            "type": "oauth2",
+            "oauth2": attrs(&[("accessToken", "{{accessToken}}")]),
+        }),
+    };
+
+    Some(auth)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn postman_collection() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Pet Store, version: "1.0" }
+servers:
+  - url: https://{env}.example.com/v1/
+    variables:
+      env: { default: api }
+security:
+  - token: []
+paths:
+  /pets/{id}:
+    parameters:
+      - { name: id, in: path, required: true, schema: { type: integer }, example: 7 }
+    get:
+      tags: [pets]
+      summary: Get a pet
+      parameters:
+        - { name: fields, in: query, schema: { type: string } }
+        - { name: verbose, in: query, required: true, schema: { type: boolean, default: false } }
+        - name: q
+          in: query
+          required: true
+          schema: { type: string }
+          example: "a b&c"
+        - name: tag
+          in: query
+          required: true
+          schema: { type: array, items: { type: string } }
+          example: [x, y]
+      responses: {}
+  /health:
+    get:
+      security: []
+      responses: {}
+    post:
+      operationId: report
+      security:
+        - key: []
+      requestBody:
+        content:
+          application/json:
+            example: { status: ok }
+      responses: {}
+  /pets:
+    post:
+      tags: [pets]
+      requestBody:
+        content:
+          application/xml: {}
+          application/json:
+            schema:
+              type: object
+              properties:
+                name: { type: string }
+      responses: {}
+components:
+  securitySchemes:
+    token: { type: http, scheme: bearer }
+    key: { type: apiKey, name: X-Key, in: header }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let collection = to_postman(&spec);
+
+        assert_eq!(collection["info"]["name"], "Pet Store");
+        assert_eq!(collection["info"]["schema"], POSTMAN_SCHEMA);
+        assert_eq!(
+            collection["variable"],
+            json!([{ "key": "baseUrl", "value": "https://api.example.com/v1" }])
+        );
+        assert_eq!(collection["auth"]["type"], "bearer");
+
+        let folder = &collection["item"][0];
+        assert_eq!(folder["name"], "pets");
+
+        let get_pet = &folder["item"][1];
+        assert_eq!(get_pet["name"], "Get a pet");
+        assert_eq!(
+            get_pet["request"]["url"],
+            json!({
+                "raw": "{{baseUrl}}/pets/:id?verbose=false&q=a%20b%26c&tag=x&tag=y",
+                "host": ["{{baseUrl}}"],
+                "path": ["pets", ":id"],
+                "query": [
+                    { "key": "fields", "value": "string", "disabled": true },
+                    { "key": "verbose", "value": "false" },
+                    { "key": "q", "value": "a%20b%26c" },
+                    { "key": "tag", "value": "x" },
+                    { "key": "tag", "value": "y" },
+                ],
+                "variable": [{ "key": "id", "value": "7" }],
+            })
+        );
+        assert!(get_pet["request"].get("auth").is_none());
+
+        let add_pet = &folder["item"][0];
+        assert_eq!(
+            add_pet["request"]["body"]["raw"],
+            "{\n  \"name\": \"string\"\n}"
+        );
+
+        let health = &collection["item"][1];
+        assert_eq!(health["name"], "GET /health");
+        assert_eq!(health["request"]["auth"], json!({ "type": "noauth" }));
+
+        let report = &collection["item"][2];
+        assert_eq!(report["name"], "report");
+        assert_eq!(report["request"]["auth"]["type"], "apikey");
+        assert_eq!(
+            report["request"]["header"],
+            json!([{ "key": "Content-Type", "value": "application/json" }])
+        );
+        assert_eq!(
+            report["request"]["body"]["raw"],
+            "{\n  \"status\": \"ok\"\n}"
+        );
+    }
+}
@@ -0,0 +1,46 @@
+//! JSON pointer helpers for reporting locations within a spec.
+
+/// Escapes a single JSON pointer reference token.
+pub(crate) fn escape(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+/// Unescapes a single JSON pointer reference token.
+pub(crate) fn unescape(segment: &str) -> String {
+    segment.replace("~1", "/").replace("~0", "~")
+}
+
+/// Builds a JSON pointer fragment (e.g. `#/paths/~1pets`) from unescaped segments.
+pub(crate) fn pointer<I, S>(segments: I) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    segments
+        .into_iter()
+        .fold("#".to_owned(), |mut ptr, segment| {
+            ptr.push('/');
+            ptr.push_str(&escape(segment.as_ref()));
+            ptr
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pointer_escaping() {
+        assert_eq!(pointer(Vec::<&str>::new()), "#");
+        assert_eq!(
+            pointer(["paths", "/pets/{id}", "get"]),
+            "#/paths/~1pets~1{id}/get"
+        );
+        assert_eq!(
+            pointer(["components", "schemas", "a~b"]),
+            "#/components/schemas/a~0b"
+        );
+        assert_eq!(unescape("~1pets~1{id}"), "/pets/{id}");
+        assert_eq!(unescape("a~01"), "a~1");
+    }
+}
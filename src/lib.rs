@@ -17,6 +17,8 @@
 use std::{fs::File, io::Read, path::Path};
 
 mod error;
+mod json_pointer;
+pub mod lint;
 pub mod spec;
 
 pub use error::Error;
@@ -28,6 +30,12 @@ pub mod validation;
 #[cfg(feature = "conformance")]
 pub mod conformance;
 
+#[cfg(feature = "docs")]
+pub mod docs;
+
+#[cfg(feature = "export")]
+pub mod export;
+
 /// Version 3.1.0 of the OpenAPI specification.
 ///
 /// Refer to the official [specification] for more information.
@@ -44,11 +52,37 @@ where
 }
 
 /// Try deserializing an OpenAPI spec (YAML or JSON) from a [`Read`] type.
+///
+/// With the `gzip` feature enabled, gzip-compressed input is detected by its magic bytes and
+/// decompressed before parsing.
 pub fn from_reader<R>(read: R) -> Result<OpenApiV3Spec, Error>
 where
     R: Read,
 {
-    Ok(serde_yaml::from_reader::<R, OpenApiV3Spec>(read)?)
+    #[cfg(feature = "gzip")]
+    let read = decompress_gzip(read)?;
+
+    Ok(serde_yaml::from_reader(read)?)
+}
+
+/// Reads all of `read`, decompressing it if it starts with the gzip magic bytes.
+#[cfg(feature = "gzip")]
+fn decompress_gzip<R: Read>(mut read: R) -> Result<std::io::Cursor<Vec<u8>>, Error> {
+    const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+    let mut buf = vec![];
+    read.read_to_end(&mut buf)?;
+
+    if !buf.starts_with(&GZIP_MAGIC) {
+        return Ok(std::io::Cursor::new(buf));
+    }
+
+    let mut decompressed = vec![];
+    flate2::read::MultiGzDecoder::new(&buf[..])
+        .read_to_end(&mut decompressed)
+        .map_err(Error::Gzip)?;
+
+    Ok(std::io::Cursor::new(decompressed))
 }
 
 /// Try serializing to a YAML string.
@@ -61,6 +95,16 @@ pub fn to_json(spec: &OpenApiV3Spec) -> Result<String, Error> {
     Ok(serde_json::to_string_pretty(spec)?)
 }
 
+/// Try serializing to a JSON string that conforms to the given OpenAPI version.
+///
+/// See [`Spec::to_version`] for how the spec is converted.
+pub fn to_json_versioned(
+    spec: &OpenApiV3Spec,
+    version: spec::OpenApiVersion,
+) -> Result<String, Error> {
+    to_json(&spec.to_version(version)?)
+}
+
 #[cfg(test)]
 mod tests {
     use std::{
@@ -73,6 +117,58 @@ mod tests {
 
     use super::*;
 
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn from_reader_gzip() {
+        let yaml = "openapi: 3.1.0\ninfo: { title: Test, version: \"1\" }\npaths: {}\n";
+
+        let mut encoder = flate2::write::GzEncoder::new(vec![], flate2::Compression::default());
+        encoder.write_all(yaml.as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let spec = from_reader(&compressed[..]).unwrap();
+        assert_eq!(spec.info.title, "Test");
+
+        let spec = from_reader(yaml.as_bytes()).unwrap();
+        assert_eq!(spec.info.title, "Test");
+
+        let err = from_reader(&compressed[..compressed.len() / 2]).unwrap_err();
+        assert!(matches!(err, Error::Gzip(_)));
+    }
+
+    #[test]
+    fn openapi_version_round_trip() {
+        for version in ["3.1.0", "3.0.3", "3.1.1-rc1", "3.10"] {
+            let yaml = format!(
+                "openapi: {}\ninfo: {{ title: Test, version: \"1\" }}\npaths: {{}}\n",
+                version
+            );
+
+            let spec = from_reader(yaml.as_bytes()).unwrap();
+            assert_eq!(spec.openapi, version);
+
+            let json = to_json(&spec).unwrap();
+            let doc = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+            assert_eq!(doc["openapi"], version);
+
+            let spec = from_reader(json.as_bytes()).unwrap();
+            assert_eq!(spec.openapi, version);
+            assert_eq!(
+                from_reader(to_yaml(&spec).unwrap().as_bytes()).unwrap(),
+                spec
+            );
+        }
+
+        let spec = from_reader(
+            "openapi: 3.0.3\ninfo: { title: Test, version: \"1\" }\npaths: {}\n".as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(
+            spec.validate_version().unwrap(),
+            semver::Version::new(3, 0, 3)
+        );
+    }
+
     /// Helper function to write string to file.
     fn write_to_file<P>(path: P, filename: &str, data: &str)
     where
@@ -0,0 +1,173 @@
+//! Lints for `allOf` compositions.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::Warning;
+use crate::{
+    json_pointer::{pointer, unescape},
+    spec::{ObjectOrReference, Schema},
+    Spec,
+};
+
+/// Reports component schemas that inherit from themselves through `allOf`, e.g. `A` with
+/// `allOf: [$ref: B]` and `B` with `allOf: [$ref: A]`.
+///
+/// Such a chain can never be merged, so [`Schema::merge_all_of`] rejects it. Only references
+/// that are `allOf` members, directly or within inline `allOf` members, are followed; other
+/// reference cycles, such as a `Node` whose property refers back to `Node`, describe recursive
+/// data and are not reported. Each cycle is reported once, at the component it is listed from.
+pub fn all_of_cycles(spec: &Spec) -> Vec<Warning> {
+    let schemas = match spec.components.as_ref() {
+        Some(components) => &components.schemas,
+        None => return vec![],
+    };
+
+    let graph = schemas
+        .iter()
+        .map(|(name, schema)| {
+            let mut parents = vec![];
+
+            if let ObjectOrReference::Object(schema) = schema {
+                all_of_parents(schema, &mut parents);
+            }
+
+            (name.clone(), parents)
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    let mut cycles = BTreeSet::new();
+    let mut done = BTreeSet::new();
+
+    for name in graph.keys() {
+        find_cycles(name, &graph, &mut vec![], &mut done, &mut cycles);
+    }
+
+    cycles
+        .into_iter()
+        .map(|cycle| {
+            let chain = cycle
+                .iter()
+                .chain(cycle.first())
+                .map(|name| name.as_str())
+                .collect::<Vec<_>>()
+                .join(" -> ");
+
+            Warning::new(
+                "all-of-cycle",
+                pointer(["components", "schemas", &cycle[0]]),
+                format!("`allOf` inheritance cycle: {}", chain),
+            )
+        })
+        .collect()
+}
+
+/// Collects the names of the component schemas that `schema` lists as `allOf` members.
+fn all_of_parents(schema: &Schema, parents: &mut Vec<String>) {
+    for member in &schema.all_of {
+        match member {
+            ObjectOrReference::Ref { ref_path, .. } => {
+                if let Some(name) = ref_path.strip_prefix("#/components/schemas/") {
+                    parents.push(unescape(name));
+                }
+            }
+            ObjectOrReference::Object(member) => all_of_parents(member, parents),
+        }
+    }
+}
+
+fn find_cycles<'a>(
+    name: &'a str,
+    graph: &'a BTreeMap<String, Vec<String>>,
+    stack: &mut Vec<&'a str>,
+    done: &mut BTreeSet<&'a str>,
+    cycles: &mut BTreeSet<Vec<String>>,
+) {
+    if let Some(pos) = stack.iter().position(|ancestor| *ancestor == name) {
+        // rotate so that each cycle is listed from its smallest name
+        let mut cycle = stack[pos..]
+            .iter()
+            .map(|name| name.to_string())
+            .collect::<Vec<_>>();
+        let min = (0..cycle.len()).min_by_key(|&idx| &cycle[idx]).unwrap();
+        cycle.rotate_left(min);
+        cycles.insert(cycle);
+        return;
+    }
+
+    if done.contains(name) {
+        return;
+    }
+
+    stack.push(name);
+
+    for parent in graph.get(name).into_iter().flatten() {
+        find_cycles(parent, graph, stack, done, cycles);
+    }
+
+    stack.pop();
+    done.insert(name);
+}
+
+impl Spec {
+    /// Returns warnings for component schemas that inherit from themselves through `allOf`.
+    ///
+    /// See [`all_of_cycles`].
+    pub fn validate_all_of_cycles(&self) -> Vec<Warning> {
+        all_of_cycles(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_of_cycles() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths: {}
+components:
+  schemas:
+    Dog:
+      allOf:
+        - $ref: "#/components/schemas/Pet"
+    Pet:
+      allOf:
+        - $ref: "#/components/schemas/Animal"
+    Animal:
+      allOf:
+        - properties:
+            kind: { type: string }
+        - allOf:
+            - $ref: "#/components/schemas/Dog"
+    Self:
+      allOf:
+        - $ref: "#/components/schemas/Self"
+    Node:
+      properties:
+        children:
+          items: { $ref: "#/components/schemas/Node" }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            super::all_of_cycles(&spec),
+            [
+                Warning::new(
+                    "all-of-cycle",
+                    "#/components/schemas/Animal",
+                    "`allOf` inheritance cycle: Animal -> Dog -> Pet -> Animal",
+                ),
+                Warning::new(
+                    "all-of-cycle",
+                    "#/components/schemas/Self",
+                    "`allOf` inheritance cycle: Self -> Self",
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,111 @@
+//! Lints for callback objects.
+
+use super::Warning;
+use crate::{
+    json_pointer::pointer,
+    spec::{invalid_template_expressions, Callback, ObjectOrReference},
+    Spec,
+};
+
+/// Reports callback keys containing syntactically invalid runtime expressions.
+///
+/// Checks the callbacks of operations in paths and webhooks, and component callbacks.
+/// Referenced callbacks are checked at their definition.
+pub fn invalid_callback_expressions(spec: &Spec) -> Vec<Warning> {
+    let mut warnings = vec![];
+
+    for (section, path_items) in [("paths", &spec.paths), ("webhooks", &spec.webhooks)] {
+        for (path, path_item) in path_items {
+            for (method, op) in path_item.methods() {
+                let method = method.as_str().to_ascii_lowercase();
+
+                for (name, callback) in &op.callbacks {
+                    check_callback(
+                        &[section, path, &method, "callbacks", name],
+                        callback,
+                        &mut warnings,
+                    );
+                }
+            }
+        }
+    }
+
+    for (name, callback) in spec
+        .components
+        .iter()
+        .flat_map(|components| &components.callbacks)
+    {
+        if let ObjectOrReference::Object(callback) = callback {
+            check_callback(&["components", "callbacks", name], callback, &mut warnings);
+        }
+    }
+
+    warnings
+}
+
+fn check_callback(segments: &[&str], callback: &Callback, warnings: &mut Vec<Warning>) {
+    let callback = serde_json::to_value(callback).unwrap();
+
+    let expressions = callback
+        .as_object()
+        .into_iter()
+        .flat_map(|obj| obj.keys())
+        .filter(|key| *key != "$ref" && !key.starts_with("x-"));
+
+    for key in expressions {
+        for expr in invalid_template_expressions(key) {
+            warnings.push(Warning::new(
+                "invalid-callback-expression",
+                pointer(segments.iter().copied().chain([key.as_str()])),
+                format!("`{}` is not a valid runtime expression", expr),
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_expressions() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /subscribe:
+    post:
+      callbacks:
+        onEvent:
+          "{$request.body#/callbackUrl}": {}
+          "http://example.com/{$request.id}": {}
+      responses:
+        "201": { description: subscribed }
+components:
+  callbacks:
+    Removed:
+      "{$response.header.Location": {}
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let warnings = invalid_callback_expressions(&spec);
+        assert_eq!(
+            warnings,
+            [
+                Warning::new(
+                    "invalid-callback-expression",
+                    "#/paths/~1subscribe/post/callbacks/onEvent/http:~1~1example.com~1{$request.id}",
+                    "`$request.id` is not a valid runtime expression",
+                ),
+                Warning::new(
+                    "invalid-callback-expression",
+                    "#/components/callbacks/Removed/{$response.header.Location",
+                    "`{$response.header.Location` is not a valid runtime expression",
+                ),
+            ]
+        );
+    }
+}
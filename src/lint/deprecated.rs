@@ -0,0 +1,221 @@
+//! Lints for usage of deprecated definitions.
+
+use std::collections::BTreeSet;
+
+use super::Warning;
+use crate::{
+    json_pointer::pointer,
+    spec::{containing_node, find_refs_at, find_schemas_at, ObjectOrReference, Parameter},
+    Spec,
+};
+
+/// Reports operations that are not deprecated but use deprecated parameters or components.
+///
+/// Components are followed through `$ref`s transitively, and deprecated schemas within the
+/// operation and within the components it uses are included, so an operation using a schema
+/// that has a deprecated property schema, inline or referenced, is reported too. Each warning
+/// names the deprecated definition.
+pub fn deprecated_usage(spec: &Spec) -> Vec<Warning> {
+    let doc = serde_json::to_value(spec).unwrap();
+    let mut warnings = vec![];
+
+    for (section, path_items) in [("paths", &spec.paths), ("webhooks", &spec.webhooks)] {
+        for (path, path_item) in path_items {
+            let path_item_val = serde_json::to_value(path_item).unwrap();
+
+            for (method, op) in path_item.methods() {
+                if op.deprecated == Some(true) {
+                    continue;
+                }
+
+                let method = method.as_str().to_ascii_lowercase();
+                let location = pointer([section, path, &method]);
+
+                for name in deprecated_parameters(path_item.parameters.iter().chain(&op.parameters))
+                {
+                    warnings.push(Warning::new(
+                        "deprecated-usage",
+                        location.clone(),
+                        format!("operation uses deprecated parameter `{}`", name),
+                    ));
+                }
+
+                let op_val = serde_json::to_value(op).unwrap();
+                let params_location = pointer([section, path, "parameters"]);
+                let params_val = &path_item_val["parameters"];
+
+                let mut refs = used_components(&doc, &op_val, &location);
+                refs.extend(used_components(&doc, params_val, &params_location));
+
+                let mut deprecated = vec![];
+
+                for node in refs {
+                    let component = match doc.pointer(&node[1..]) {
+                        Some(component) => component,
+                        None => continue,
+                    };
+
+                    if component.get("deprecated") == Some(&serde_json::Value::Bool(true)) {
+                        deprecated.push(node.clone());
+                    }
+
+                    // the component itself is checked above, whether or not it is a schema
+                    deprecated.extend(
+                        deprecated_schemas(component, &node)
+                            .into_iter()
+                            .filter(|schema| *schema != node),
+                    );
+                }
+
+                deprecated.extend(deprecated_schemas(params_val, &params_location));
+                deprecated.extend(deprecated_schemas(&op_val, &location));
+
+                for definition in deprecated {
+                    warnings.push(Warning::new(
+                        "deprecated-usage",
+                        location.clone(),
+                        format!("operation uses deprecated `{}`", definition),
+                    ));
+                }
+            }
+        }
+    }
+
+    warnings
+}
+
+impl Spec {
+    /// Returns warnings for non-deprecated operations that use deprecated definitions.
+    ///
+    /// See [`deprecated_usage`].
+    pub fn deprecated_usage_warnings(&self) -> Vec<Warning> {
+        deprecated_usage(self)
+    }
+}
+
+/// Returns the names of inline parameters that are deprecated.
+fn deprecated_parameters<'a>(
+    params: impl Iterator<Item = &'a ObjectOrReference<Parameter>>,
+) -> Vec<&'a str> {
+    params
+        .filter_map(|param| match param {
+            ObjectOrReference::Object(param) if param.deprecated == Some(true) => {
+                Some(param.name.as_str())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns the locations of the deprecated schemas within `val`, the part of a serialized spec
+/// at `location`.
+fn deprecated_schemas(val: &serde_json::Value, location: &str) -> Vec<String> {
+    find_schemas_at(val, location)
+        .into_iter()
+        .filter(|(_, schema)| schema.get("deprecated") == Some(&serde_json::Value::Bool(true)))
+        .map(|(location, _)| location)
+        .collect()
+}
+
+/// Returns the components referenced from `val`, the part of `doc` at `location`, directly or
+/// through other components.
+fn used_components(
+    doc: &serde_json::Value,
+    val: &serde_json::Value,
+    location: &str,
+) -> BTreeSet<String> {
+    let mut found = BTreeSet::new();
+    let mut queue = find_refs_at(val, location)
+        .into_iter()
+        .map(|(_, ref_path)| ref_path)
+        .collect::<Vec<_>>();
+
+    while let Some(ref_path) = queue.pop() {
+        let node = match containing_node(&ref_path) {
+            Some(node) if node.starts_with("#/components/") => node,
+            _ => continue,
+        };
+
+        if !found.insert(node.clone()) {
+            continue;
+        }
+
+        if let Some(component) = doc.pointer(&node[1..]) {
+            queue.extend(
+                find_refs_at(component, &node)
+                    .into_iter()
+                    .map(|(_, ref_path)| ref_path),
+            );
+        }
+    }
+
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn deprecated_dependencies() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /pets:
+    parameters:
+      - { name: legacy, in: query, deprecated: true, schema: { type: string } }
+    get:
+      parameters:
+        - { $ref: "#/components/parameters/Limit" }
+      responses:
+        "200":
+          description: pets
+          content:
+            application/json:
+              schema: { $ref: "#/components/schemas/Pet" }
+            text/plain:
+              schema:
+                properties:
+                  name: { type: string, deprecated: true }
+    delete:
+      deprecated: true
+      parameters:
+        - { $ref: "#/components/parameters/Limit" }
+      responses:
+        "204": { description: deleted }
+components:
+  parameters:
+    Limit: { name: limit, in: query, deprecated: true, schema: { type: integer } }
+  schemas:
+    Pet:
+      properties:
+        tag: { $ref: "#/components/schemas/OldTag" }
+        age: { type: integer, deprecated: true }
+    OldTag: { type: string, deprecated: true }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let messages = spec
+            .deprecated_usage_warnings()
+            .into_iter()
+            .map(|warning| {
+                assert_eq!(warning.location, "#/paths/~1pets/get");
+                warning.message
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            messages,
+            [
+                "operation uses deprecated parameter `legacy`",
+                "operation uses deprecated `#/components/parameters/Limit`",
+                "operation uses deprecated `#/components/schemas/OldTag`",
+                "operation uses deprecated `#/components/schemas/Pet/properties/age`",
+                "operation uses deprecated \
+                 `#/paths/~1pets/get/responses/200/content/text~1plain/schema/properties/name`",
+            ]
+        );
+    }
+}
@@ -0,0 +1,268 @@
+//! Lints for documentation completeness.
+
+use std::collections::BTreeMap;
+
+use super::Warning;
+use crate::{
+    json_pointer::pointer,
+    spec::{MediaType, ObjectOrReference, Parameter},
+    Spec,
+};
+
+/// Parts of a spec that must be described, checked by [`missing_descriptions`].
+#[derive(Debug, Clone)]
+pub struct DescriptionTargets {
+    /// Operations must have a `summary` or `description`.
+    pub operations: bool,
+
+    /// Path item and operation parameters must have a `description`.
+    pub parameters: bool,
+
+    /// Request body and response schemas must have a `description`.
+    pub schemas: bool,
+}
+
+impl DescriptionTargets {
+    /// Requires descriptions on all supported targets.
+    pub fn all() -> Self {
+        Self {
+            operations: true,
+            parameters: true,
+            schemas: true,
+        }
+    }
+}
+
+impl Default for DescriptionTargets {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+/// Reports operations, parameters and request or response schemas selected by `targets` that
+/// lack a non-blank description.
+///
+/// Referenced parameters and schemas are resolved, but warnings are located where they are
+/// used; those that can not be resolved are skipped. Schemas are described as by
+/// [`ObjectOrReference::resolved_description`], so a `description` next to a `$ref` counts.
+///
+/// This lint is configurable, so it is not part of [`lint`](super::lint).
+pub fn missing_descriptions(spec: &Spec, targets: &DescriptionTargets) -> Vec<Warning> {
+    let mut warnings = vec![];
+
+    let mut warn = |segments: &[&str], message: String| {
+        warnings.push(Warning::new(
+            "require-descriptions",
+            pointer(segments),
+            message,
+        ));
+    };
+
+    for (section, path_items) in [("paths", &spec.paths), ("webhooks", &spec.webhooks)] {
+        for (path, path_item) in path_items {
+            if targets.parameters {
+                for (idx, param) in path_item.parameters.iter().enumerate() {
+                    if let Some(name) = undescribed_parameter(param, spec) {
+                        warn(
+                            &[section, path, "parameters", &idx.to_string()],
+                            format!("parameter `{}` of `{}` has no description", name, path),
+                        );
+                    }
+                }
+            }
+
+            for (method, op) in path_item.methods() {
+                let subject = format!("`{} {}`", method, path);
+                let method = method.as_str().to_ascii_lowercase();
+
+                if targets.operations
+                    && is_blank(op.summary.as_deref())
+                    && is_blank(op.description.as_deref())
+                {
+                    warn(
+                        &[section, path, &method],
+                        format!("operation {} has no summary or description", subject),
+                    );
+                }
+
+                if targets.parameters {
+                    for (idx, param) in op.parameters.iter().enumerate() {
+                        if let Some(name) = undescribed_parameter(param, spec) {
+                            warn(
+                                &[section, path, &method, "parameters", &idx.to_string()],
+                                format!("parameter `{}` of {} has no description", name, subject),
+                            );
+                        }
+                    }
+                }
+
+                if !targets.schemas {
+                    continue;
+                }
+
+                if let Some(Ok(body)) = op.request_body.as_ref().map(|body| body.resolve(spec)) {
+                    for media_type in undescribed_schemas(&body.content, spec) {
+                        warn(
+                            &[
+                                section,
+                                path,
+                                &method,
+                                "requestBody",
+                                "content",
+                                media_type,
+                                "schema",
+                            ],
+                            format!(
+                                "request body schema of {} (`{}`) has no description",
+                                subject, media_type
+                            ),
+                        );
+                    }
+                }
+
+                for (status, res) in &op.responses {
+                    let res = match res.resolve(spec) {
+                        Ok(res) => res,
+                        Err(_) => continue,
+                    };
+
+                    for media_type in undescribed_schemas(&res.content, spec) {
+                        warn(
+                            &[
+                                section,
+                                path,
+                                &method,
+                                "responses",
+                                status,
+                                "content",
+                                media_type,
+                                "schema",
+                            ],
+                            format!(
+                                "{} response schema of {} (`{}`) has no description",
+                                status, subject, media_type
+                            ),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    warnings
+}
+
+impl Spec {
+    /// Returns warnings for parts of the spec selected by `targets` that are not described.
+    ///
+    /// See [`missing_descriptions`].
+    pub fn validate_descriptions(&self, targets: &DescriptionTargets) -> Vec<Warning> {
+        missing_descriptions(self, targets)
+    }
+}
+
+fn is_blank(text: Option<&str>) -> bool {
+    text.map(str::trim).unwrap_or_default().is_empty()
+}
+
+/// Returns the name of `param` if it resolves and has no description.
+fn undescribed_parameter(param: &ObjectOrReference<Parameter>, spec: &Spec) -> Option<String> {
+    let param = param.resolve(spec).ok()?;
+    is_blank(param.description.as_deref()).then_some(param.name)
+}
+
+/// Returns the media types whose schemas resolve and have no description.
+fn undescribed_schemas<'a>(content: &'a BTreeMap<String, MediaType>, spec: &Spec) -> Vec<&'a str> {
+    content
+        .iter()
+        .filter(|(_, media_type)| {
+            matches!(
+                media_type.schema,
+                Some(ref schema) if schema.resolve(spec).is_ok()
+                    && is_blank(schema.resolved_description(spec).as_deref())
+            )
+        })
+        .map(|(media_type, _)| media_type.as_str())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_descriptions() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /pets:
+    get:
+      summary: List pets.
+      parameters:
+        - { name: limit, in: query, schema: { type: integer } }
+        - { name: offset, in: query, description: " ", schema: { type: integer } }
+        - $ref: "#/components/parameters/Sort"
+      responses:
+        "200":
+          description: pets
+          content:
+            application/json:
+              schema: { $ref: "#/components/schemas/Pets" }
+        "206":
+          description: some pets
+          content:
+            application/json:
+              schema: { $ref: "#/components/schemas/Pets", description: A page of pets. }
+    post:
+      requestBody:
+        content:
+          application/json:
+            schema: { type: object }
+      responses:
+        "201":
+          description: created
+          content:
+            application/json:
+              schema: { $ref: "#/components/schemas/Pet" }
+components:
+  parameters:
+    Sort: { name: sort, in: query, description: Sort order., schema: { type: string } }
+  schemas:
+    Pet: { type: object, description: A pet. }
+    Pets: { type: array, items: { $ref: "#/components/schemas/Pet" } }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let messages = |targets: &DescriptionTargets| {
+            super::missing_descriptions(&spec, targets)
+                .into_iter()
+                .map(|warning| format!("{}: {}", warning.location, warning.message))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            messages(&DescriptionTargets::all()),
+            [
+                "#/paths/~1pets/get/parameters/0: parameter `limit` of `GET /pets` has no description",
+                "#/paths/~1pets/get/parameters/1: parameter `offset` of `GET /pets` has no description",
+                "#/paths/~1pets/get/responses/200/content/application~1json/schema: 200 response schema of `GET /pets` (`application/json`) has no description",
+                "#/paths/~1pets/post: operation `POST /pets` has no summary or description",
+                "#/paths/~1pets/post/requestBody/content/application~1json/schema: request body schema of `POST /pets` (`application/json`) has no description",
+            ]
+        );
+
+        let operations_only = DescriptionTargets {
+            operations: true,
+            parameters: false,
+            schemas: false,
+        };
+        assert_eq!(
+            messages(&operations_only),
+            ["#/paths/~1pets/post: operation `POST /pets` has no summary or description"]
+        );
+    }
+}
@@ -0,0 +1,209 @@
+//! Lints for `enum` and `const` values.
+
+use serde_json::Value;
+
+use super::Warning;
+use crate::{
+    spec::{visit, SchemaType},
+    Spec,
+};
+
+/// Reports `enum` and `const` values that match none of their schema's types.
+///
+/// Every type of an OpenAPI 3.1 `type` array is considered, so `null` values are allowed if the
+/// array includes `"null"`, as they are by OpenAPI 3.0 `nullable`. Integers also match `number`,
+/// and numbers with no fractional part match `integer`. Schemas without a `type` are skipped.
+pub fn enum_type_mismatch(spec: &Spec) -> Vec<Warning> {
+    let mut warnings = vec![];
+
+    for (location, schema) in visit::schemas(spec) {
+        let types = match schema.schema_type {
+            Some(ref types) => types.types(),
+            None => continue,
+        };
+
+        let nullable = schema.nullable == Some(true);
+
+        let values = schema
+            .enum_values
+            .iter()
+            .flatten()
+            .map(|val| ("enum", val))
+            .chain(schema.const_value.iter().map(|val| ("const", val)));
+
+        for (keyword, val) in values {
+            if (nullable && val.is_null()) || types.iter().any(|type_| matches_type(val, *type_)) {
+                continue;
+            }
+
+            let types = types
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(" | ");
+
+            warnings.push(Warning::new(
+                "enum-type",
+                location.clone(),
+                format!("`{}` value {} is not of type {}", keyword, val, types),
+            ));
+        }
+    }
+
+    warnings
+}
+
+/// Reports values that appear more than once in a schema's `enum`, and empty `enum`s, which no
+/// value can match.
+///
+/// Values are compared for JSON equality, so `1` and `1.0` are considered different. Each
+/// repeated value is reported once.
+pub fn duplicate_enum_values(spec: &Spec) -> Vec<Warning> {
+    let mut warnings = vec![];
+
+    for (location, schema) in visit::schemas(spec) {
+        let values = match schema.enum_values {
+            Some(ref values) if values.is_empty() => {
+                warnings.push(Warning::new(
+                    "enum-values",
+                    location,
+                    "`enum` must not be empty",
+                ));
+                continue;
+            }
+            Some(ref values) => values,
+            None => continue,
+        };
+
+        let mut reported = Vec::<&Value>::new();
+
+        for (idx, val) in values.iter().enumerate() {
+            if reported.contains(&val) || !values[..idx].contains(val) {
+                continue;
+            }
+
+            reported.push(val);
+            warnings.push(Warning::new(
+                "enum-values",
+                location.clone(),
+                format!("`enum` value {} is repeated", val),
+            ));
+        }
+    }
+
+    warnings
+}
+
+fn matches_type(val: &Value, type_: SchemaType) -> bool {
+    match (type_, val) {
+        (SchemaType::Boolean, Value::Bool(_))
+        | (SchemaType::Number, Value::Number(_))
+        | (SchemaType::String, Value::String(_))
+        | (SchemaType::Array, Value::Array(_))
+        | (SchemaType::Object, Value::Object(_))
+        | (SchemaType::Null, Value::Null) => true,
+
+        (SchemaType::Integer, Value::Number(num)) => {
+            num.is_i64() || num.is_u64() || matches!(num.as_f64(), Some(num) if num.fract() == 0.0)
+        }
+
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enum_type_mismatch() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths: {}
+components:
+  schemas:
+    Size:
+      type: [integer, "null"]
+      enum: [1, 2.0, null, large]
+    Name:
+      type: [string, number]
+      const: true
+    Legacy:
+      type: string
+      nullable: true
+      enum: [a, null]
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            super::enum_type_mismatch(&spec),
+            [
+                Warning::new(
+                    "enum-type",
+                    "#/components/schemas/Name",
+                    "`const` value true is not of type string | number",
+                ),
+                Warning::new(
+                    "enum-type",
+                    "#/components/schemas/Size",
+                    "`enum` value \"large\" is not of type integer | null",
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn duplicate_enum_values() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /pets:
+    get:
+      parameters:
+        - { name: size, in: query, schema: { type: string, enum: [] } }
+      responses: {}
+components:
+  schemas:
+    Size:
+      enum: [small, large, small, { a: 1 }, { a: 1 }, small, 1, 1.0]
+    Pet:
+      properties:
+        enum: { type: string, enum: [] }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            super::duplicate_enum_values(&spec),
+            [
+                Warning::new(
+                    "enum-values",
+                    "#/paths/~1pets/get/parameters/0/schema",
+                    "`enum` must not be empty",
+                ),
+                Warning::new(
+                    "enum-values",
+                    "#/components/schemas/Pet/properties/enum",
+                    "`enum` must not be empty",
+                ),
+                Warning::new(
+                    "enum-values",
+                    "#/components/schemas/Size",
+                    "`enum` value \"small\" is repeated",
+                ),
+                Warning::new(
+                    "enum-values",
+                    "#/components/schemas/Size",
+                    "`enum` value {\"a\":1} is repeated",
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,225 @@
+//! Lints for the shape of error responses.
+
+use super::Warning;
+use crate::{
+    json_pointer::{escape, pointer},
+    spec::{media_type_essence, ObjectOrReference},
+    Spec,
+};
+
+/// Contract that error responses must follow, checked by [`error_responses`].
+#[derive(Debug, Clone, Default)]
+pub struct ErrorResponseStyle {
+    /// Name of the component schema that error response bodies must reference, e.g. `Problem`.
+    pub schema: Option<String>,
+
+    /// Media type that error responses must provide, e.g. `application/problem+json`.
+    pub media_type: Option<String>,
+}
+
+impl ErrorResponseStyle {
+    /// Creates a style requiring [RFC 7807](https://www.rfc-editor.org/rfc/rfc7807) problem
+    /// details, using the `application/problem+json` media type.
+    pub fn problem_details() -> Self {
+        Self {
+            schema: None,
+            media_type: Some("application/problem+json".to_owned()),
+        }
+    }
+
+    /// Sets the component schema that error response bodies must reference.
+    pub fn with_schema(mut self, schema: impl Into<String>) -> Self {
+        self.schema = Some(schema.into());
+        self
+    }
+}
+
+/// Reports 4XX and 5XX responses that do not follow `style`.
+///
+/// A response deviates if it has no content, if it lacks the required media type, or if the
+/// schema of any of its media types (only the required one, if set) is neither a `$ref` to the
+/// required component schema nor an `allOf` including one. Media types are compared by their
+/// essence, so parameters such as `charset` are ignored. Responses that can not be resolved are
+/// skipped. Warnings are located at the response.
+///
+/// This lint is configurable, so it is not part of [`lint`](super::lint).
+pub fn error_responses(spec: &Spec, style: &ErrorResponseStyle) -> Vec<Warning> {
+    let mut warnings = vec![];
+
+    if style.schema.is_none() && style.media_type.is_none() {
+        return warnings;
+    }
+
+    let schema_ref = style
+        .schema
+        .as_ref()
+        .map(|name| format!("#/components/schemas/{}", escape(name)));
+
+    let media_type_required = style.media_type.as_deref().map(media_type_essence);
+
+    for (section, path_items) in [("paths", &spec.paths), ("webhooks", &spec.webhooks)] {
+        for (path, path_item) in path_items {
+            for (method, op) in path_item.methods() {
+                let method_name = method.as_str().to_ascii_lowercase();
+
+                for (status, res) in op.responses(spec) {
+                    if !status.starts_with(['4', '5']) {
+                        continue;
+                    }
+
+                    let location = pointer([section, path, &method_name, "responses", &status]);
+                    let subject = format!("{} response of `{} {}`", status, method, path);
+                    let mut warn = |message: String| {
+                        warnings.push(Warning::new("error-responses", location.clone(), message));
+                    };
+
+                    if res.content.is_empty() {
+                        warn(format!("{} has no content", subject));
+                        continue;
+                    }
+
+                    let media_types = match media_type_required {
+                        Some(ref essence) => {
+                            let media_types = res
+                                .content
+                                .iter()
+                                .filter(|(media_type, _)| {
+                                    media_type_essence(media_type) == *essence
+                                })
+                                .collect::<Vec<_>>();
+
+                            if media_types.is_empty() {
+                                warn(format!(
+                                    "{} does not provide `{}`",
+                                    subject,
+                                    style.media_type.as_deref().unwrap_or_default()
+                                ));
+                                continue;
+                            }
+
+                            media_types
+                        }
+                        None => res.content.iter().collect(),
+                    };
+
+                    let schema_ref = match schema_ref {
+                        Some(ref schema_ref) => schema_ref,
+                        None => continue,
+                    };
+
+                    let is_schema_ref = |schema: &ObjectOrReference<_>| match schema {
+                        ObjectOrReference::Ref { ref_path, .. } => ref_path == schema_ref,
+                        ObjectOrReference::Object(_) => false,
+                    };
+
+                    for (media_type, media_spec) in media_types {
+                        match media_spec.schema {
+                            Some(ref schema) if is_schema_ref(schema) => {}
+                            // extensions of the schema, e.g. with additional members
+                            Some(ObjectOrReference::Object(ref schema))
+                                if schema.all_of.iter().any(is_schema_ref) => {}
+                            _ => warn(format!(
+                                "{} (`{}`) does not use schema `{}`",
+                                subject,
+                                media_type,
+                                style.schema.as_deref().unwrap_or_default(),
+                            )),
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    warnings
+}
+
+impl Spec {
+    /// Returns warnings for error responses that do not follow `style`.
+    ///
+    /// See [`error_responses`].
+    pub fn validate_error_responses(&self, style: &ErrorResponseStyle) -> Vec<Warning> {
+        error_responses(self, style)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_responses() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /pets:
+    get:
+      responses:
+        "200":
+          description: pets
+        "400":
+          description: bad request
+          content:
+            application/problem+json; charset=utf-8:
+              schema: { $ref: "#/components/schemas/Problem" }
+        "422":
+          description: invalid
+          content:
+            application/problem+json:
+              schema:
+                allOf:
+                  - $ref: "#/components/schemas/Problem"
+                  - { type: object, properties: { errors: { type: array } } }
+        "404":
+          description: not found
+          content:
+            application/json:
+              schema: { $ref: "#/components/schemas/Problem" }
+        5XX:
+          description: server error
+          content:
+            application/problem+json:
+              schema: { type: object }
+    delete:
+      responses:
+        "409":
+          description: conflict
+components:
+  schemas:
+    Problem: { type: object }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let style = ErrorResponseStyle::problem_details().with_schema("Problem");
+
+        assert_eq!(
+            super::error_responses(&spec, &style),
+            [
+                Warning::new(
+                    "error-responses",
+                    "#/paths/~1pets/get/responses/404",
+                    "404 response of `GET /pets` does not provide `application/problem+json`",
+                ),
+                Warning::new(
+                    "error-responses",
+                    "#/paths/~1pets/get/responses/5XX",
+                    "5XX response of `GET /pets` (`application/problem+json`) does not use schema `Problem`",
+                ),
+                Warning::new(
+                    "error-responses",
+                    "#/paths/~1pets/delete/responses/409",
+                    "409 response of `DELETE /pets` has no content",
+                ),
+            ]
+        );
+
+        let style = ErrorResponseStyle::default().with_schema("Problem");
+        assert_eq!(super::error_responses(&spec, &style).len(), 2);
+
+        assert!(super::error_responses(&spec, &ErrorResponseStyle::default()).is_empty());
+    }
+}
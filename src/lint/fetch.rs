@@ -0,0 +1,324 @@
+//! Lints that perform network requests.
+//!
+//! These are never run as part of parsing and must be explicitly awaited.
+
+use std::time::Duration;
+
+use derive_more::Display;
+use futures_util::{stream, StreamExt as _};
+use log::{debug, error};
+
+use super::Warning;
+use crate::{json_pointer::pointer, Spec};
+
+/// Options for lints that perform network requests.
+#[derive(Debug, Clone)]
+pub struct FetchOptions {
+    /// Timeout for each individual request.
+    pub timeout: Duration,
+
+    /// Maximum number of requests in flight at once.
+    pub concurrency: usize,
+
+    /// Whether responses with a 4xx or 5xx status count as unreachable servers.
+    ///
+    /// Many servers respond with an error to a `HEAD` request for their base URL while still
+    /// being perfectly reachable, so by default only network-level failures are reported.
+    pub server_error_status_is_unreachable: bool,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(10),
+            concurrency: 8,
+            server_error_status_is_unreachable: false,
+        }
+    }
+}
+
+/// Reason a URL could not be fetched successfully.
+#[derive(Debug, Clone, PartialEq, Eq, Display)]
+pub enum FetchFailure {
+    #[display(fmt = "request timed out")]
+    Timeout,
+
+    #[display(fmt = "connection failed")]
+    Connect,
+
+    #[display(fmt = "invalid URL")]
+    InvalidUrl,
+
+    #[display(fmt = "responded with status {}", _0)]
+    Status(u16),
+
+    #[display(fmt = "request failed: {}", _0)]
+    Other(String),
+}
+
+impl From<reqwest::Error> for FetchFailure {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            Self::Timeout
+        } else if err.is_connect() {
+            Self::Connect
+        } else if err.is_builder() {
+            Self::InvalidUrl
+        } else {
+            Self::Other(err.to_string())
+        }
+    }
+}
+
+/// Sends a `HEAD` request to each URL, returning the failures alongside their original index.
+///
+/// Servers that reject `HEAD` requests with a 403, 405 or 501 status are sent a `GET` request
+/// instead, whose response decides the result. Returns an error, and fetches nothing, if the HTTP client can not be built.
+pub(crate) async fn fetch_all(
+    urls: Vec<String>,
+    opts: &FetchOptions,
+    error_status_is_failure: bool,
+) -> Result<Vec<(usize, String, FetchFailure)>, reqwest::Error> {
+    let client = reqwest::Client::builder().timeout(opts.timeout).build()?;
+
+    let failures = stream::iter(urls.into_iter().enumerate())
+        .map(|(idx, url)| {
+            let client = &client;
+
+            async move {
+                debug!("fetching {}", &url);
+
+                let response = match client.head(&url).send().await {
+                    Ok(res) if matches!(res.status().as_u16(), 403 | 405 | 501) => {
+                        debug!(
+                            "{} rejected HEAD with {}, retrying with GET",
+                            &url,
+                            res.status()
+                        );
+                        client.get(&url).send().await
+                    }
+                    response => response,
+                };
+
+                let result = match response {
+                    Ok(res)
+                        if error_status_is_failure
+                            && (res.status().is_client_error()
+                                || res.status().is_server_error()) =>
+                    {
+                        Err(FetchFailure::Status(res.status().as_u16()))
+                    }
+                    Ok(_) => Ok(()),
+                    Err(err) => Err(FetchFailure::from(err)),
+                };
+
+                result.err().map(|failure| (idx, url, failure))
+            }
+        })
+        .buffer_unordered(opts.concurrency.max(1))
+        .filter_map(|failure| async move { failure })
+        .collect()
+        .await;
+
+    Ok(failures)
+}
+
+/// Returns the single warning reported by a network lint whose HTTP client can not be built,
+/// so that a failed check is not mistaken for a clean result.
+fn client_failure(rule: &'static str, err: reqwest::Error) -> Vec<Warning> {
+    error!("failed to build HTTP client: {}", err);
+
+    vec![Warning::new(
+        rule,
+        "#",
+        format!("could not be checked, failed to build HTTP client: {}", err),
+    )]
+}
+
+/// Sends a `HEAD` request to each server URL, reporting unreachable servers.
+///
+/// Server variables are substituted with their default values. Relative server URLs can not be
+/// checked and are skipped. If the HTTP client can not be built, a single warning for the
+/// whole document is returned instead.
+pub async fn check_server_reachability(spec: &Spec, opts: &FetchOptions) -> Vec<Warning> {
+    let urls = spec
+        .servers
+        .iter()
+        .map(|server| server.url_with_defaults())
+        .collect::<Vec<_>>();
+
+    let (idxs, urls): (Vec<_>, Vec<_>) = urls
+        .into_iter()
+        .enumerate()
+        .filter(|(_, url)| url::Url::parse(url).is_ok())
+        .unzip();
+
+    let mut failures = match fetch_all(urls, opts, opts.server_error_status_is_unreachable).await {
+        Ok(failures) => failures,
+        Err(err) => return client_failure("server-reachable", err),
+    };
+    failures.sort_by_key(|(idx, _, _)| *idx);
+
+    failures
+        .into_iter()
+        .map(|(idx, url, failure)| {
+            Warning::new(
+                "server-reachable",
+                pointer(["servers", &idxs[idx].to_string()]),
+                format!("server {} is unreachable: {}", url, failure),
+            )
+        })
+        .collect()
+}
+
+/// Sends a `HEAD` request to each documentation URL, or a `GET` request if `HEAD` is rejected,
+/// reporting broken links.
+///
+/// See [`Spec::external_docs_urls`] for the URLs that are checked. Responses with a 4xx or 5xx
+/// status count as broken links. If the HTTP client can not be built, a single warning for the
+/// whole document is returned instead.
+pub async fn check_documentation_links(spec: &Spec, opts: &FetchOptions) -> Vec<Warning> {
+    let (locations, urls): (Vec<_>, Vec<_>) = spec
+        .external_docs_urls()
+        .into_iter()
+        .map(|(location, url)| (location, url.to_string()))
+        .unzip();
+
+    let mut failures = match fetch_all(urls, opts, true).await {
+        Ok(failures) => failures,
+        Err(err) => return client_failure("docs-link", err),
+    };
+    failures.sort_by_key(|(idx, _, _)| *idx);
+
+    failures
+        .into_iter()
+        .map(|(idx, url, failure)| {
+            Warning::new(
+                "docs-link",
+                locations[idx].clone(),
+                format!("documentation link {} is broken: {}", url, failure),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn check_server_reachability() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+servers:
+  - url: /v1
+  - url: http://127.0.0.1:1
+paths: {}
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            super::check_server_reachability(&spec, &FetchOptions::default()).await,
+            [Warning::new(
+                "server-reachable",
+                "#/servers/1",
+                "server http://127.0.0.1:1 is unreachable: connection failed",
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn check_documentation_links() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /pets:
+    get:
+      externalDocs: { url: "http://127.0.0.1:1/docs" }
+      responses: {}
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            super::check_documentation_links(&spec, &FetchOptions::default()).await,
+            [Warning::new(
+                "docs-link",
+                "#/paths/~1pets/get/externalDocs/url",
+                "documentation link http://127.0.0.1:1/docs is broken: connection failed",
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn documentation_links_without_head() {
+        use tokio::{
+            io::{AsyncReadExt as _, AsyncWriteExt as _},
+            net::TcpListener,
+        };
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        // rejects `HEAD` requests and serves `GET` requests for `/docs` only
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = [0; 1024];
+                let len = socket.read(&mut buf).await.unwrap();
+                let request = String::from_utf8_lossy(&buf[..len]);
+
+                let status = if request.starts_with("HEAD") {
+                    "405 Method Not Allowed"
+                } else if request.starts_with("GET /docs ") {
+                    "200 OK"
+                } else {
+                    "404 Not Found"
+                };
+
+                let response = format!(
+                    "HTTP/1.1 {}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+                    status
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        let spec = crate::from_reader(
+            format!(
+                r##"
+openapi: 3.1.0
+info: {{ title: Test, version: "1" }}
+externalDocs: {{ url: "http://{addr}/docs" }}
+paths:
+  /pets:
+    get:
+      externalDocs: {{ url: "http://{addr}/missing" }}
+      responses: {{}}
+"##,
+                addr = addr
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            super::check_documentation_links(&spec, &FetchOptions::default()).await,
+            [Warning::new(
+                "docs-link",
+                "#/paths/~1pets/get/externalDocs/url",
+                format!(
+                    "documentation link http://{}/missing is broken: responded with status 404",
+                    addr
+                ),
+            )]
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! Lints for header names.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::Warning;
+use crate::{
+    json_pointer::pointer,
+    spec::{Header, ObjectOrReference, Parameter},
+    Spec,
+};
+
+/// Header parameter names that are ignored, since they are described by other spec fields.
+const RESERVED_PARAMETER_HEADERS: &[&str] = &["accept", "authorization", "content-type"];
+
+/// Response header names that are ignored, since they are described by the response's content.
+const RESERVED_RESPONSE_HEADERS: &[&str] = &["content-type"];
+
+/// Reports header definitions that are ignored or ambiguous.
+///
+/// `Content-Type` response headers and `Accept`, `Content-Type` or `Authorization` header
+/// parameters are ignored by tooling, since media types and security schemes describe them.
+/// Header names are case-insensitive, so response headers that only differ in case are reported
+/// as duplicates.
+pub fn invalid_header_names(spec: &Spec) -> Vec<Warning> {
+    let mut warnings = vec![];
+
+    if let Some(ref components) = spec.components {
+        for (name, param) in &components.parameters {
+            check_parameter(
+                param,
+                pointer(["components", "parameters", name]),
+                &mut warnings,
+            );
+        }
+
+        for (name, res) in &components.responses {
+            if let ObjectOrReference::Object(res) = res {
+                check_response_headers(
+                    &res.headers,
+                    &["components", "responses", name],
+                    &mut warnings,
+                );
+            }
+        }
+    }
+
+    for (section, path_items) in [("paths", &spec.paths), ("webhooks", &spec.webhooks)] {
+        for (path, path_item) in path_items {
+            for (idx, param) in path_item.parameters.iter().enumerate() {
+                let location = pointer([section, path, "parameters", &idx.to_string()]);
+                check_parameter(param, location, &mut warnings);
+            }
+
+            for (method, op) in path_item.methods() {
+                let method = method.as_str().to_ascii_lowercase();
+
+                for (idx, param) in op.parameters.iter().enumerate() {
+                    let location =
+                        pointer([section, path, &method, "parameters", &idx.to_string()]);
+                    check_parameter(param, location, &mut warnings);
+                }
+
+                for (status, res) in &op.responses {
+                    if let ObjectOrReference::Object(res) = res {
+                        check_response_headers(
+                            &res.headers,
+                            &[section, path, &method, "responses", status],
+                            &mut warnings,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    warnings
+}
+
+impl Spec {
+    /// Returns warnings for reserved and duplicate header names.
+    ///
+    /// See [`invalid_header_names`].
+    pub fn validate_header_names(&self) -> Vec<Warning> {
+        invalid_header_names(self)
+    }
+}
+
+fn check_parameter(
+    param: &ObjectOrReference<Parameter>,
+    location: String,
+    warnings: &mut Vec<Warning>,
+) {
+    if let ObjectOrReference::Object(param) = param {
+        if param.location == "header"
+            && RESERVED_PARAMETER_HEADERS.contains(&param.name.to_ascii_lowercase().as_str())
+        {
+            warnings.push(Warning::new(
+                "header-names",
+                location,
+                format!("header parameter `{}` is ignored", param.name),
+            ));
+        }
+    }
+}
+
+fn check_response_headers(
+    headers: &BTreeMap<String, ObjectOrReference<Header>>,
+    response_segments: &[&str],
+    warnings: &mut Vec<Warning>,
+) {
+    let mut seen = BTreeSet::new();
+
+    for name in headers.keys() {
+        let location = pointer(
+            response_segments
+                .iter()
+                .copied()
+                .chain(["headers", name.as_str()]),
+        );
+        let lower = name.to_ascii_lowercase();
+
+        if RESERVED_RESPONSE_HEADERS.contains(&lower.as_str()) {
+            warnings.push(Warning::new(
+                "header-names",
+                location.clone(),
+                format!("response header `{}` is ignored", name),
+            ));
+        }
+
+        if !seen.insert(lower) {
+            warnings.push(Warning::new(
+                "header-names",
+                location,
+                format!("response header `{}` is defined more than once", name),
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn header_names() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /pets:
+    get:
+      parameters:
+        - { name: Accept, in: header, schema: { type: string } }
+        - { name: accept, in: query, schema: { type: string } }
+        - { name: X-Request-Id, in: header, schema: { type: string } }
+      responses:
+        "200":
+          description: pets
+          headers:
+            Content-Type: { schema: { type: string } }
+            X-Rate-Limit: { schema: { type: integer } }
+            x-rate-limit: { schema: { type: integer } }
+components:
+  parameters:
+    Auth: { name: Authorization, in: header, schema: { type: string } }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let warnings = spec.validate_header_names();
+        let messages = warnings
+            .iter()
+            .map(|warning| (warning.location.as_str(), warning.message.as_str()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            messages,
+            [
+                (
+                    "#/components/parameters/Auth",
+                    "header parameter `Authorization` is ignored"
+                ),
+                (
+                    "#/paths/~1pets/get/parameters/0",
+                    "header parameter `Accept` is ignored"
+                ),
+                (
+                    "#/paths/~1pets/get/responses/200/headers/Content-Type",
+                    "response header `Content-Type` is ignored"
+                ),
+                (
+                    "#/paths/~1pets/get/responses/200/headers/x-rate-limit",
+                    "response header `x-rate-limit` is defined more than once"
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,138 @@
+//! Lints for gateway limits on paths and operations.
+
+use super::Warning;
+use crate::{json_pointer::pointer, Spec};
+
+/// Limits checked by [`path_limits`].
+#[derive(Debug, Clone)]
+pub struct PathLimits {
+    /// Maximum number of segments in a path, e.g. 3 for `/pets/{id}/owner`.
+    pub max_path_segments: usize,
+
+    /// Maximum number of parameters of an operation, including path item parameters that it
+    /// does not override.
+    pub max_operation_parameters: usize,
+}
+
+impl Default for PathLimits {
+    /// Returns limits that only very unusual APIs exceed: 16 path segments and 32 parameters.
+    fn default() -> Self {
+        Self {
+            max_path_segments: 16,
+            max_operation_parameters: 32,
+        }
+    }
+}
+
+/// Reports paths with more segments than allowed by `limits` (`max-path-segments`) and
+/// operations with more parameters (`max-operation-parameters`).
+///
+/// Path warnings are located at the path item and operation warnings at the operation. Both
+/// paths and webhooks are checked, although webhook names only count towards the segment limit
+/// if they look like paths.
+///
+/// This lint is configurable, so it is not part of [`lint`](super::lint).
+pub fn path_limits(spec: &Spec, limits: &PathLimits) -> Vec<Warning> {
+    let mut warnings = vec![];
+
+    for (section, path_items) in [("paths", &spec.paths), ("webhooks", &spec.webhooks)] {
+        for (path, path_item) in path_items {
+            let segments = path
+                .split('/')
+                .filter(|segment| !segment.is_empty())
+                .count();
+
+            if path.starts_with('/') && segments > limits.max_path_segments {
+                warnings.push(Warning::new(
+                    "max-path-segments",
+                    pointer([section, path]),
+                    format!(
+                        "path `{}` has {} segments, more than the maximum of {}",
+                        path, segments, limits.max_path_segments
+                    ),
+                ));
+            }
+
+            for (method, op) in path_item.methods() {
+                let params = op.effective_parameters(path_item, spec).len();
+
+                if params > limits.max_operation_parameters {
+                    warnings.push(Warning::new(
+                        "max-operation-parameters",
+                        pointer([section, path, &method.as_str().to_ascii_lowercase()]),
+                        format!(
+                            "operation `{} {}` has {} parameters, more than the maximum of {}",
+                            method, path, params, limits.max_operation_parameters
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    warnings
+}
+
+impl Spec {
+    /// Returns warnings for paths and operations that exceed `limits`.
+    ///
+    /// See [`path_limits`].
+    pub fn validate_path_limits(&self, limits: &PathLimits) -> Vec<Warning> {
+        path_limits(self, limits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_limits() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /pets/{id}/owner:
+    parameters:
+      - { name: id, in: path, required: true, schema: { type: integer } }
+      - { name: trace, in: header, schema: { type: string } }
+    get:
+      parameters:
+        - { name: trace, in: header, schema: { type: string } }
+        - { name: fields, in: query, schema: { type: string } }
+      responses: {}
+    delete:
+      responses: {}
+  /pets:
+    get:
+      responses: {}
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let limits = PathLimits {
+            max_path_segments: 2,
+            max_operation_parameters: 2,
+        };
+
+        assert_eq!(
+            super::path_limits(&spec, &limits),
+            [
+                Warning::new(
+                    "max-path-segments",
+                    "#/paths/~1pets~1{id}~1owner",
+                    "path `/pets/{id}/owner` has 3 segments, more than the maximum of 2",
+                ),
+                Warning::new(
+                    "max-operation-parameters",
+                    "#/paths/~1pets~1{id}~1owner/get",
+                    "operation `GET /pets/{id}/owner` has 3 parameters, more than the maximum of 2",
+                ),
+            ]
+        );
+
+        assert!(super::path_limits(&spec, &PathLimits::default()).is_empty());
+    }
+}
@@ -0,0 +1,196 @@
+//! Lints for media types and the schemas describing them.
+
+use std::collections::BTreeMap;
+
+use super::Warning;
+use crate::{
+    json_pointer::pointer,
+    spec::{is_json_media_type, media_type_essence, MediaType, SchemaType},
+    Spec,
+};
+
+/// Reports request and response schemas whose type does not fit their media type.
+///
+/// Flags binary strings (`format: binary`) under JSON media types, which cannot carry raw
+/// bytes, and object or array schemas under `text/*` or binary media types, such as
+/// `application/octet-stream` and `image/*`, which have no structure to map them to. Array
+/// schemas are allowed for `text/csv`, whose rows they commonly describe. Referenced schemas are
+/// resolved; schemas without a single non-null type are skipped.
+pub fn media_type_schema_mismatch(spec: &Spec) -> Vec<Warning> {
+    let mut warnings = vec![];
+
+    for (section, path_items) in [("paths", &spec.paths), ("webhooks", &spec.webhooks)] {
+        for (path, path_item) in path_items {
+            for (method, op) in path_item.methods() {
+                let method_name = method.as_str().to_ascii_lowercase();
+
+                if let Ok(body) = op.request_body(spec) {
+                    check_content(
+                        spec,
+                        &body.content,
+                        &[section, path, &method_name, "requestBody"],
+                        &format!("request body of `{} {}`", method, path),
+                        &mut warnings,
+                    );
+                }
+
+                for (status, res) in op.responses(spec) {
+                    check_content(
+                        spec,
+                        &res.content,
+                        &[section, path, &method_name, "responses", &status],
+                        &format!("{} response of `{} {}`", status, method, path),
+                        &mut warnings,
+                    );
+                }
+            }
+        }
+    }
+
+    warnings
+}
+
+impl Spec {
+    /// Returns warnings for request and response schemas that do not fit their media type.
+    ///
+    /// See [`media_type_schema_mismatch`].
+    pub fn validate_media_type_schemas(&self) -> Vec<Warning> {
+        media_type_schema_mismatch(self)
+    }
+}
+
+fn check_content(
+    spec: &Spec,
+    content: &BTreeMap<String, MediaType>,
+    parent: &[&str],
+    subject: &str,
+    warnings: &mut Vec<Warning>,
+) {
+    for (media_type, media_spec) in content {
+        let schema = match media_spec
+            .schema
+            .as_ref()
+            .map(|schema| schema.resolve(spec))
+        {
+            Some(Ok(schema)) => schema,
+            _ => continue,
+        };
+
+        let schema_type = match schema
+            .schema_type
+            .as_ref()
+            .and_then(|types| types.single_non_null())
+        {
+            Some(schema_type) => schema_type,
+            None => continue,
+        };
+
+        let essence = media_type_essence(media_type);
+
+        let message = if is_json_media_type(&essence)
+            && schema_type == SchemaType::String
+            && schema.format.as_deref() == Some("binary")
+        {
+            format!(
+                "{} declares a binary string for JSON media type `{}`, \
+                 which can not carry raw bytes",
+                subject, media_type
+            )
+        } else if matches!(schema_type, SchemaType::Object | SchemaType::Array)
+            && (essence.starts_with("text/") || is_binary_media_type(&essence))
+            // CSV rows are commonly described as an array
+            && !(schema_type == SchemaType::Array && essence == "text/csv")
+        {
+            format!(
+                "{} declares an {} schema for `{}`, which is not a structured media type",
+                subject, schema_type, media_type
+            )
+        } else {
+            continue;
+        };
+
+        warnings.push(Warning::new(
+            "media-type-schema",
+            pointer(
+                parent
+                    .iter()
+                    .copied()
+                    .chain(["content", media_type, "schema"]),
+            ),
+            message,
+        ));
+    }
+}
+
+/// Returns true for media types of opaque binary data.
+fn is_binary_media_type(essence: &str) -> bool {
+    essence == "application/octet-stream"
+        || ["image/", "audio/", "video/"]
+            .iter()
+            .any(|prefix| essence.starts_with(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn media_type_schema_mismatch() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /pets:
+    post:
+      requestBody:
+        content:
+          application/octet-stream:
+            schema: { type: string, format: binary }
+          text/csv:
+            schema: { type: array, items: { type: string } }
+          text/plain:
+            schema: { type: array, items: { type: string } }
+      responses:
+        "200":
+          description: pet
+          content:
+            application/json:
+              schema: { type: string, format: binary }
+            text/plain; charset=utf-8:
+              schema: { $ref: "#/components/schemas/Pet" }
+            image/png:
+              schema: { type: string, format: binary }
+components:
+  schemas:
+    Pet: { type: object }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            super::media_type_schema_mismatch(&spec),
+            [
+                Warning::new(
+                    "media-type-schema",
+                    "#/paths/~1pets/post/requestBody/content/text~1plain/schema",
+                    "request body of `POST /pets` declares an array schema for `text/plain`, \
+                     which is not a structured media type",
+                ),
+                Warning::new(
+                    "media-type-schema",
+                    "#/paths/~1pets/post/responses/200/content/application~1json/schema",
+                    "200 response of `POST /pets` declares a binary string for JSON media type \
+                     `application/json`, which can not carry raw bytes",
+                ),
+                Warning::new(
+                    "media-type-schema",
+                    "#/paths/~1pets/post/responses/200/content/text~1plain; charset=utf-8/schema",
+                    "200 response of `POST /pets` declares an object schema for \
+                     `text/plain; charset=utf-8`, which is not a structured media type",
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,106 @@
+//! Static analysis of OpenAPI specifications.
+//!
+//! Lints report problems that a spec may have even though it deserializes successfully, such as
+//! spec rules that cannot be expressed in the type system, or common authoring mistakes.
+
+use derive_more::Display;
+
+use crate::Spec;
+
+mod all_of;
+mod callbacks;
+mod deprecated;
+mod descriptions;
+mod enums;
+mod error_responses;
+#[cfg(feature = "fetch")]
+mod fetch;
+mod headers;
+mod limits;
+mod media_types;
+mod naming;
+mod operation_id;
+mod parameters;
+mod path_style;
+mod ref_siblings;
+mod required;
+mod security;
+mod servers;
+mod unused;
+
+pub use all_of::*;
+pub use callbacks::*;
+pub use deprecated::*;
+pub use descriptions::*;
+pub use enums::*;
+pub use error_responses::*;
+#[cfg(feature = "fetch")]
+pub use fetch::*;
+pub use headers::*;
+pub use limits::*;
+pub use media_types::*;
+pub use naming::*;
+pub use operation_id::*;
+pub use parameters::*;
+pub use path_style::*;
+pub use ref_siblings::*;
+pub use required::*;
+pub use security::*;
+pub use servers::*;
+pub use unused::*;
+
+/// Lint rules that run without network access, in reporting order.
+const RULES: &[fn(&Spec) -> Vec<Warning>] = &[
+    required_not_in_properties,
+    invalid_callback_expressions,
+    deprecated_usage,
+    invalid_header_names,
+    invalid_server_variables,
+    enum_type_mismatch,
+    invalid_security_references,
+    unused_components,
+    invalid_parameter_schemas,
+    duplicate_enum_values,
+    media_type_schema_mismatch,
+    missing_servers,
+    all_of_cycles,
+];
+
+/// Runs all lints that do not require network access.
+///
+/// Lints that perform network requests, such as [`check_server_reachability`], configurable
+/// lints, such as [`operation_id_format`], [`path_style`], [`error_responses`],
+/// [`missing_descriptions`] and [`path_limits`], opt-in lints, such as
+/// [`parameter_name_collisions`], [`read_only_required`] and [`write_only_required`], and lints
+/// of the source document, such as [`ref_siblings`], must be run separately.
+pub fn lint(spec: &Spec) -> Vec<Warning> {
+    RULES.iter().flat_map(|rule| rule(spec)).collect()
+}
+
+/// A problem found in a spec by a lint rule.
+#[derive(Debug, Clone, PartialEq, Eq, Display)]
+#[display(fmt = "{}: {} [{}]", location, message, rule)]
+pub struct Warning {
+    /// Name of the rule that produced this warning.
+    pub rule: &'static str,
+
+    /// JSON pointer to the offending location in the spec, e.g. `#/paths/~1pets/get`.
+    pub location: String,
+
+    /// Explanation of the problem.
+    pub message: String,
+}
+
+impl Warning {
+    pub fn new(
+        rule: &'static str,
+        location: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            rule,
+            location: location.into(),
+            message: message.into(),
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! Naming conventions shared by the configurable naming lints.
+
+use derive_more::Display;
+use regex::Regex;
+
+/// A naming convention, e.g. for `operationId`s, path segments or parameter names.
+#[derive(Debug, Clone, Display)]
+pub enum NamingStyle {
+    /// Lowercase first letter and no separators, e.g. `getPetById`.
+    #[display(fmt = "camelCase")]
+    CamelCase,
+
+    /// Uppercase first letter and no separators, e.g. `GetPetById`.
+    #[display(fmt = "PascalCase")]
+    PascalCase,
+
+    /// Lowercase words separated by underscores, e.g. `get_pet_by_id`.
+    #[display(fmt = "snake_case")]
+    SnakeCase,
+
+    /// Lowercase words separated by hyphens, e.g. `get-pet-by-id`.
+    #[display(fmt = "kebab-case")]
+    KebabCase,
+
+    /// Capitalized words separated by hyphens, e.g. `X-Request-Id`.
+    #[display(fmt = "Train-Case")]
+    TrainCase,
+
+    /// A custom pattern that must match the whole name.
+    #[display(fmt = "`{}`", _0)]
+    Pattern(Regex),
+}
+
+impl NamingStyle {
+    /// Returns true if `name` follows this convention.
+    pub fn matches(&self, name: &str) -> bool {
+        let mut chars = name.chars();
+        let first = chars.next();
+        let rest = chars.as_str();
+
+        let is_words = |sep: char| {
+            matches!(first, Some(c) if c.is_ascii_lowercase())
+                && name.split(sep).all(|word| {
+                    !word.is_empty()
+                        && word
+                            .chars()
+                            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+                })
+        };
+
+        match self {
+            NamingStyle::CamelCase => {
+                matches!(first, Some(c) if c.is_ascii_lowercase())
+                    && rest.chars().all(|c| c.is_ascii_alphanumeric())
+            }
+            NamingStyle::PascalCase => {
+                matches!(first, Some(c) if c.is_ascii_uppercase())
+                    && rest.chars().all(|c| c.is_ascii_alphanumeric())
+            }
+            NamingStyle::SnakeCase => is_words('_'),
+            NamingStyle::KebabCase => is_words('-'),
+            NamingStyle::TrainCase => name.split('-').all(|word| {
+                let mut chars = word.chars();
+
+                matches!(chars.next(), Some(c) if c.is_ascii_uppercase() || c.is_ascii_digit())
+                    && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+            }),
+            // anchoring the whole pattern lets alternations match any of their branches fully
+            NamingStyle::Pattern(pattern) => matches!(
+                Regex::new(&format!("^(?:{})$", pattern.as_str())),
+                Ok(anchored) if anchored.is_match(name)
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn styles() {
+        assert!(NamingStyle::CamelCase.matches("getPet2"));
+        assert!(!NamingStyle::CamelCase.matches("GetPet"));
+        assert!(!NamingStyle::CamelCase.matches("get_pet"));
+        assert!(NamingStyle::PascalCase.matches("GetPet"));
+        assert!(NamingStyle::SnakeCase.matches("get_pet_2"));
+        assert!(!NamingStyle::SnakeCase.matches("get__pet"));
+        assert!(!NamingStyle::SnakeCase.matches("getPet"));
+        assert!(NamingStyle::KebabCase.matches("get-pet"));
+        assert!(!NamingStyle::KebabCase.matches(""));
+        assert!(NamingStyle::TrainCase.matches("X-Request-Id"));
+        assert!(!NamingStyle::TrainCase.matches("X-Request-ID"));
+        assert!(!NamingStyle::TrainCase.matches("x-request-id"));
+        assert!(!NamingStyle::TrainCase.matches("X--Id"));
+
+        let pattern = NamingStyle::Pattern(Regex::new("(get|list)[A-Z]\\w*").unwrap());
+        assert!(pattern.matches("listPets"));
+        assert!(!pattern.matches("deletePet"));
+        assert!(!pattern.matches("xgetPet"));
+
+        let pattern = NamingStyle::Pattern(Regex::new("get|getPets").unwrap());
+        assert!(pattern.matches("get"));
+        assert!(pattern.matches("getPets"));
+        assert!(!pattern.matches("getPet"));
+    }
+}
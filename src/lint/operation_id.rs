@@ -0,0 +1,79 @@
+//! Lints for `operationId` naming conventions.
+
+use super::{NamingStyle, Warning};
+use crate::{json_pointer::pointer, Spec};
+
+/// Reports `operationId`s that do not follow `style`.
+///
+/// This lint is configurable, so it is not part of [`lint`](super::lint).
+pub fn operation_id_format(spec: &Spec, style: &NamingStyle) -> Vec<Warning> {
+    let mut warnings = vec![];
+
+    for (section, path_items) in [("paths", &spec.paths), ("webhooks", &spec.webhooks)] {
+        for (path, path_item) in path_items {
+            for (method, op) in path_item.methods() {
+                let operation_id = match op.operation_id {
+                    Some(ref operation_id) => operation_id,
+                    None => continue,
+                };
+
+                if !style.matches(operation_id) {
+                    let method = method.as_str().to_ascii_lowercase();
+
+                    warnings.push(Warning::new(
+                        "operation-id-format",
+                        pointer([section, path, &method, "operationId"]),
+                        format!("operationId `{}` is not {}", operation_id, style),
+                    ));
+                }
+            }
+        }
+    }
+
+    warnings
+}
+
+impl Spec {
+    /// Returns warnings for `operationId`s that do not follow `style`.
+    ///
+    /// See [`operation_id_format`].
+    pub fn validate_operation_id_format(&self, style: &NamingStyle) -> Vec<Warning> {
+        operation_id_format(self, style)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operation_id_format() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      responses: {}
+    post:
+      operationId: create_pet
+      responses: {}
+    delete:
+      responses: {}
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            spec.validate_operation_id_format(&NamingStyle::CamelCase),
+            [Warning::new(
+                "operation-id-format",
+                "#/paths/~1pets/post/operationId",
+                "operationId `create_pet` is not camelCase",
+            )]
+        );
+    }
+}
@@ -0,0 +1,248 @@
+//! Lints for parameters.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::Warning;
+use crate::{
+    json_pointer::pointer,
+    spec::{ObjectOrReference, Parameter},
+    Spec,
+};
+
+/// Reports parameters that do not describe their value with exactly one of `schema` or
+/// `content`, or whose `content` does not have exactly one media type.
+///
+/// Such parameters have no well-defined schema; see
+/// [`Parameter::effective_schema`](crate::spec::Parameter::effective_schema). Parameters of
+/// components, path items and operations are checked; referenced parameters are checked as
+/// components.
+pub fn invalid_parameter_schemas(spec: &Spec) -> Vec<Warning> {
+    let mut warnings = vec![];
+
+    if let Some(ref components) = spec.components {
+        for (name, param) in &components.parameters {
+            check_parameter(
+                param,
+                pointer(["components", "parameters", name]),
+                &mut warnings,
+            );
+        }
+    }
+
+    for (section, path_items) in [("paths", &spec.paths), ("webhooks", &spec.webhooks)] {
+        for (path, path_item) in path_items {
+            for (idx, param) in path_item.parameters.iter().enumerate() {
+                let location = pointer([section, path, "parameters", &idx.to_string()]);
+                check_parameter(param, location, &mut warnings);
+            }
+
+            for (method, op) in path_item.methods() {
+                let method = method.as_str().to_ascii_lowercase();
+
+                for (idx, param) in op.parameters.iter().enumerate() {
+                    let location =
+                        pointer([section, path, &method, "parameters", &idx.to_string()]);
+                    check_parameter(param, location, &mut warnings);
+                }
+            }
+        }
+    }
+
+    warnings
+}
+
+impl Spec {
+    /// Returns warnings for parameters without exactly one of `schema` or `content`.
+    ///
+    /// See [`invalid_parameter_schemas`].
+    pub fn validate_parameter_schemas(&self) -> Vec<Warning> {
+        invalid_parameter_schemas(self)
+    }
+
+    /// Returns warnings for operations using a parameter name in more than one location.
+    ///
+    /// See [`parameter_name_collisions`].
+    pub fn validate_parameter_name_collisions(&self) -> Vec<Warning> {
+        parameter_name_collisions(self)
+    }
+}
+
+/// Reports operations that use the same parameter name in more than one location, e.g. both a
+/// path parameter and a query parameter named `id`.
+///
+/// The spec allows this, since parameters are identified by name and location, but it can be
+/// confusing for clients, and generated code often has to rename one of the parameters. Path
+/// item parameters that an operation does not override are included, and referenced parameters
+/// are resolved. Warnings are located at the operation and list the locations involved.
+///
+/// This lint is opt-in, since some style guides allow such names, so it is not part of
+/// [`lint`](super::lint).
+pub fn parameter_name_collisions(spec: &Spec) -> Vec<Warning> {
+    let mut warnings = vec![];
+
+    for (section, path_items) in [("paths", &spec.paths), ("webhooks", &spec.webhooks)] {
+        for (path, path_item) in path_items {
+            for (method, op) in path_item.methods() {
+                let mut locations = BTreeMap::<String, BTreeSet<String>>::new();
+
+                for param in op.effective_parameters(path_item, spec) {
+                    locations
+                        .entry(param.name)
+                        .or_default()
+                        .insert(param.location);
+                }
+
+                let method = method.as_str().to_ascii_lowercase();
+
+                for (name, locations) in locations {
+                    if locations.len() < 2 {
+                        continue;
+                    }
+
+                    warnings.push(Warning::new(
+                        "param-name-collision",
+                        pointer([section, path, &method]),
+                        format!(
+                            "parameter name `{}` is used in multiple locations: {}",
+                            name,
+                            locations.into_iter().collect::<Vec<_>>().join(", ")
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    warnings
+}
+
+fn check_parameter(
+    param: &ObjectOrReference<Parameter>,
+    location: String,
+    warnings: &mut Vec<Warning>,
+) {
+    let param = match param {
+        ObjectOrReference::Object(param) => param,
+        ObjectOrReference::Ref { .. } => return,
+    };
+
+    let message = match (param.schema.is_some(), param.content.len()) {
+        (true, 0) | (false, 1) => return,
+        (true, _) => format!(
+            "parameter `{}` sets both `schema` and `content`",
+            param.name
+        ),
+        (false, 0) => format!(
+            "parameter `{}` sets neither `schema` nor `content`",
+            param.name
+        ),
+        (false, count) => format!(
+            "parameter `{}` has {} `content` media types instead of one",
+            param.name, count
+        ),
+    };
+
+    warnings.push(Warning::new("parameter-schema", location, message));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_parameter_schemas() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /pets:
+    parameters:
+      - { name: trace, in: header }
+    get:
+      parameters:
+        - { name: limit, in: query, schema: { type: integer } }
+        - name: filter
+          in: query
+          content:
+            application/json: { schema: { type: object } }
+        - name: sort
+          in: query
+          schema: { type: string }
+          content:
+            application/json: { schema: { type: string } }
+        - $ref: "#/components/parameters/Format"
+      responses: {}
+components:
+  parameters:
+    Format:
+      name: format
+      in: query
+      content:
+        application/json: {}
+        text/plain: {}
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            super::invalid_parameter_schemas(&spec),
+            [
+                Warning::new(
+                    "parameter-schema",
+                    "#/components/parameters/Format",
+                    "parameter `format` has 2 `content` media types instead of one",
+                ),
+                Warning::new(
+                    "parameter-schema",
+                    "#/paths/~1pets/parameters/0",
+                    "parameter `trace` sets neither `schema` nor `content`",
+                ),
+                Warning::new(
+                    "parameter-schema",
+                    "#/paths/~1pets/get/parameters/2",
+                    "parameter `sort` sets both `schema` and `content`",
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn parameter_name_collisions() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /pets/{id}:
+    parameters:
+      - { name: id, in: path, required: true }
+    get:
+      parameters:
+        - { name: id, in: query }
+        - $ref: "#/components/parameters/IdHeader"
+        - { name: limit, in: query }
+      responses: {}
+    put:
+      parameters:
+        - { name: limit, in: query }
+      responses: {}
+components:
+  parameters:
+    IdHeader: { name: id, in: header }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            super::parameter_name_collisions(&spec),
+            [Warning::new(
+                "param-name-collision",
+                "#/paths/~1pets~1{id}/get",
+                "parameter name `id` is used in multiple locations: header, path, query",
+            )]
+        );
+    }
+}
@@ -0,0 +1,278 @@
+//! Lints for path segment and parameter naming conventions.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::{NamingStyle, Warning};
+use crate::{
+    json_pointer::pointer,
+    spec::{ObjectOrReference, Parameter},
+    Spec,
+};
+
+/// Naming conventions for paths and parameters, checked by [`path_style`].
+#[derive(Debug, Clone)]
+pub struct PathStyle {
+    /// Convention for literal path segments, e.g. `pet-owners` in `/pet-owners/{ownerId}`.
+    pub segments: NamingStyle,
+
+    /// Convention for path template parameters, e.g. `ownerId` in `/pet-owners/{ownerId}`.
+    pub parameters: NamingStyle,
+
+    /// Convention for query parameter names, e.g. `pageSize`, if they are checked.
+    pub query_parameters: Option<NamingStyle>,
+
+    /// Convention for header parameter names, e.g. `X-Request-Id`, if they are checked.
+    pub header_parameters: Option<NamingStyle>,
+
+    /// Segments and parameter names that are exempt from the conventions, e.g. `OAuth2`.
+    pub exceptions: BTreeSet<String>,
+}
+
+impl PathStyle {
+    /// Creates a path style with the given conventions for paths and no exceptions.
+    ///
+    /// Query and header parameter names are not checked.
+    pub fn new(segments: NamingStyle, parameters: NamingStyle) -> Self {
+        Self {
+            segments,
+            parameters,
+            query_parameters: None,
+            header_parameters: None,
+            exceptions: BTreeSet::new(),
+        }
+    }
+}
+
+/// Reports path segments, path template parameters and query and header parameters that do not
+/// follow `style`.
+///
+/// Also reports path parameters spelled differently from a previous path's parameter that
+/// differs only in casing or separators, such as `{pet_id}` after `{petId}`, even if both are
+/// exceptions. Warnings for paths are located at the path item, and those for query and header
+/// parameters at the parameter. Parameters of path items, of operations and in `components` are
+/// checked, but references are not followed, so each parameter is reported once.
+///
+/// This lint is configurable, so it is not part of [`lint`](super::lint).
+pub fn path_style(spec: &Spec, style: &PathStyle) -> Vec<Warning> {
+    let mut warnings = vec![];
+    let mut spellings = BTreeMap::<String, &str>::new();
+
+    for (path, path_item) in &spec.paths {
+        let location = pointer(["paths", path]);
+        let mut warn = |message: String| {
+            warnings.push(Warning::new("path-style", location.clone(), message));
+        };
+
+        for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+            if segment.contains('{') {
+                let params = segment
+                    .split('{')
+                    .skip(1)
+                    .filter_map(|part| part.split_once('}'))
+                    .map(|(name, _)| name);
+
+                for name in params {
+                    let key = name.replace(['_', '-'], "").to_ascii_lowercase();
+
+                    match spellings.get(&key) {
+                        Some(first) if *first != name => warn(format!(
+                            "path parameter `{}` is spelled `{}` elsewhere",
+                            name, first
+                        )),
+                        Some(_) => {}
+                        None => {
+                            spellings.insert(key, name);
+                        }
+                    }
+
+                    if !style.exceptions.contains(name) && !style.parameters.matches(name) {
+                        warn(format!(
+                            "path parameter `{}` is not {}",
+                            name, style.parameters
+                        ));
+                    }
+                }
+            } else if !style.exceptions.contains(segment) && !style.segments.matches(segment) {
+                warn(format!(
+                    "path segment `{}` is not {}",
+                    segment, style.segments
+                ));
+            }
+        }
+
+        check_parameters(
+            &path_item.parameters,
+            &["paths", path],
+            style,
+            &mut warnings,
+        );
+
+        for (method, op) in path_item.methods() {
+            let method = method.as_str().to_ascii_lowercase();
+            let parent = ["paths", path, &method];
+            check_parameters(&op.parameters, &parent, style, &mut warnings);
+        }
+    }
+
+    if let Some(ref components) = spec.components {
+        for (name, param) in &components.parameters {
+            if let ObjectOrReference::Object(param) = param {
+                let location = pointer(["components", "parameters", name]);
+                check_parameter(param, location, style, &mut warnings);
+            }
+        }
+    }
+
+    warnings
+}
+
+fn check_parameters(
+    params: &[ObjectOrReference<Parameter>],
+    parent: &[&str],
+    style: &PathStyle,
+    warnings: &mut Vec<Warning>,
+) {
+    for (idx, param) in params.iter().enumerate() {
+        if let ObjectOrReference::Object(param) = param {
+            let idx = idx.to_string();
+            let location = pointer(parent.iter().copied().chain(["parameters", &idx]));
+            check_parameter(param, location, style, warnings);
+        }
+    }
+}
+
+fn check_parameter(
+    param: &Parameter,
+    location: String,
+    style: &PathStyle,
+    warnings: &mut Vec<Warning>,
+) {
+    let naming = match param.location.as_str() {
+        "query" => &style.query_parameters,
+        "header" => &style.header_parameters,
+        _ => return,
+    };
+
+    match naming {
+        Some(naming) if !style.exceptions.contains(&param.name) && !naming.matches(&param.name) => {
+            warnings.push(Warning::new(
+                "path-style",
+                location,
+                format!(
+                    "{} parameter `{}` is not {}",
+                    param.location, param.name, naming
+                ),
+            ))
+        }
+        _ => {}
+    }
+}
+
+impl Spec {
+    /// Returns warnings for path segments and parameters that do not follow `style`.
+    ///
+    /// See [`path_style`].
+    pub fn validate_path_style(&self, style: &PathStyle) -> Vec<Warning> {
+        path_style(self, style)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_style() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /OAuth2/token: {}
+  /pet-owners/{ownerId}: {}
+  /pet-owners/{owner_id}/petList: {}
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let mut style = PathStyle::new(NamingStyle::KebabCase, NamingStyle::CamelCase);
+        style.exceptions.insert("OAuth2".to_owned());
+
+        assert_eq!(
+            spec.validate_path_style(&style),
+            [
+                Warning::new(
+                    "path-style",
+                    "#/paths/~1pet-owners~1{owner_id}~1petList",
+                    "path parameter `owner_id` is spelled `ownerId` elsewhere",
+                ),
+                Warning::new(
+                    "path-style",
+                    "#/paths/~1pet-owners~1{owner_id}~1petList",
+                    "path parameter `owner_id` is not camelCase",
+                ),
+                Warning::new(
+                    "path-style",
+                    "#/paths/~1pet-owners~1{owner_id}~1petList",
+                    "path segment `petList` is not kebab-case",
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn parameter_style() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /pets:
+    parameters:
+      - { name: X-Request-Id, in: header }
+      - { name: ETag, in: header }
+    get:
+      parameters:
+        - { name: page_size, in: query }
+        - { name: x-trace, in: header }
+        - $ref: "#/components/parameters/Sort"
+      responses: {}
+components:
+  parameters:
+    Sort: { name: SortBy, in: query }
+    Session: { name: session, in: cookie }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let mut style = PathStyle::new(NamingStyle::KebabCase, NamingStyle::CamelCase);
+        assert!(spec.validate_path_style(&style).is_empty());
+
+        style.query_parameters = Some(NamingStyle::CamelCase);
+        style.header_parameters = Some(NamingStyle::TrainCase);
+        style.exceptions.insert("ETag".to_owned());
+
+        assert_eq!(
+            spec.validate_path_style(&style),
+            [
+                Warning::new(
+                    "path-style",
+                    "#/paths/~1pets/get/parameters/0",
+                    "query parameter `page_size` is not camelCase",
+                ),
+                Warning::new(
+                    "path-style",
+                    "#/paths/~1pets/get/parameters/1",
+                    "header parameter `x-trace` is not Train-Case",
+                ),
+                Warning::new(
+                    "path-style",
+                    "#/components/parameters/Sort",
+                    "query parameter `SortBy` is not camelCase",
+                ),
+            ]
+        );
+    }
+}
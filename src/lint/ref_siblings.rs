@@ -0,0 +1,89 @@
+//! Lints for keywords placed alongside `$ref`.
+
+use super::Warning;
+use crate::spec::find_refs;
+
+/// Reports `$ref` objects with sibling keywords in OpenAPI 3.0 documents.
+///
+/// OpenAPI 3.0 ignores all siblings of `$ref`, whereas 3.1 allows them, so documents of other
+/// versions produce no warnings. Since siblings are discarded when a [`Spec`](crate::Spec) is
+/// deserialized, this lint runs on the source document, e.g. as parsed by
+/// `serde_yaml::from_reader`.
+pub fn ref_siblings(source: &serde_json::Value) -> Vec<Warning> {
+    let is_3_0 = matches!(
+        source.get("openapi").and_then(|val| val.as_str()),
+        Some(version) if version.starts_with("3.0")
+    );
+
+    if !is_3_0 {
+        return vec![];
+    }
+
+    find_refs(source)
+        .into_iter()
+        .filter_map(|(location, _)| {
+            let location = location.strip_suffix("/$ref")?.to_owned();
+
+            let siblings = source
+                .pointer(&location[1..])?
+                .as_object()?
+                .keys()
+                .filter(|key| *key != "$ref")
+                .map(|key| format!("`{}`", key))
+                .collect::<Vec<_>>();
+
+            if siblings.is_empty() {
+                return None;
+            }
+
+            Some(Warning::new(
+                "ref-siblings",
+                location,
+                format!(
+                    "siblings of `$ref` are not allowed in OpenAPI 3.0: {}",
+                    siblings.join(", ")
+                ),
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(openapi: &str) -> serde_json::Value {
+        serde_yaml::from_str(&format!(
+            r##"
+openapi: {}
+info: {{ title: Test, version: "1" }}
+paths: {{}}
+components:
+  schemas:
+    Pet:
+      properties:
+        tag:
+          $ref: "#/components/schemas/Tag"
+          description: the tag
+          nullable: true
+        owner: {{ $ref: "#/components/schemas/Person" }}
+"##,
+            openapi
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn siblings_in_3_0() {
+        assert_eq!(
+            ref_siblings(&source("3.0.3")),
+            [Warning::new(
+                "ref-siblings",
+                "#/components/schemas/Pet/properties/tag",
+                "siblings of `$ref` are not allowed in OpenAPI 3.0: `description`, `nullable`",
+            )]
+        );
+
+        assert!(ref_siblings(&source("3.1.0")).is_empty());
+    }
+}
@@ -0,0 +1,201 @@
+//! Lints for the `required` keyword of object schemas.
+
+use super::Warning;
+use crate::{spec::visit, Schema, Spec};
+
+/// Reports names listed in a schema's `required` that are not declared as properties.
+///
+/// See [`Schema::required_but_not_in_properties`](crate::Schema::required_but_not_in_properties)
+/// for which definitions are considered.
+pub fn required_not_in_properties(spec: &Spec) -> Vec<Warning> {
+    visit::schemas(spec)
+        .into_iter()
+        .flat_map(|(location, schema)| {
+            schema
+                .required_but_not_in_properties(spec)
+                .into_iter()
+                .map(move |name| {
+                    Warning::new(
+                        "required-not-in-properties",
+                        location.clone(),
+                        format!(
+                            "required property `{}` is not declared in `properties`",
+                            name
+                        ),
+                    )
+                })
+        })
+        .collect()
+}
+
+/// Reports required properties that are `readOnly`, which are only required in responses.
+///
+/// The spec allows this, and [`Schema::for_context`](crate::Schema::for_context) drops such
+/// properties from `required` for requests, but some style guides prefer separate request and
+/// response schemas. Only a schema's own `properties` are considered; properties that can not be
+/// resolved are skipped. Warnings are located at the schema.
+///
+/// This lint is opt-in, so it is not part of [`lint`](super::lint).
+pub fn read_only_required(spec: &Spec) -> Vec<Warning> {
+    required_props(spec, Schema::is_read_only)
+        .into_iter()
+        .map(|(location, name)| {
+            Warning::new(
+                "readonly-required",
+                location,
+                format!(
+                    "required property `{}` is `readOnly`, so it is only required in responses",
+                    name
+                ),
+            )
+        })
+        .collect()
+}
+
+/// Reports required properties that are `writeOnly`, which are only required in requests.
+///
+/// The counterpart of [`read_only_required`], also opt-in.
+pub fn write_only_required(spec: &Spec) -> Vec<Warning> {
+    required_props(spec, Schema::is_write_only)
+        .into_iter()
+        .map(|(location, name)| {
+            Warning::new(
+                "writeonly-required",
+                location,
+                format!(
+                    "required property `{}` is `writeOnly`, so it is only required in requests",
+                    name
+                ),
+            )
+        })
+        .collect()
+}
+
+/// Returns the locations of schemas and names of their required properties for which
+/// `is_flagged` is true.
+fn required_props(spec: &Spec, is_flagged: fn(&Schema) -> bool) -> Vec<(String, String)> {
+    let mut props = vec![];
+
+    for (location, schema) in visit::schemas(spec) {
+        for name in &schema.required {
+            match schema.properties.get(name).map(|prop| prop.resolve(spec)) {
+                Some(Ok(prop)) if is_flagged(&prop) => props.push((location.clone(), name.clone())),
+                _ => {}
+            }
+        }
+    }
+
+    props
+}
+
+impl Spec {
+    /// Returns warnings for required properties that are `readOnly`.
+    ///
+    /// See [`read_only_required`].
+    pub fn validate_read_only_required(&self) -> Vec<Warning> {
+        read_only_required(self)
+    }
+
+    /// Returns warnings for required properties that are `writeOnly`.
+    ///
+    /// See [`write_only_required`].
+    pub fn validate_write_only_required(&self) -> Vec<Warning> {
+        write_only_required(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orphan_required_names() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /pets:
+    post:
+      requestBody:
+        content:
+          application/json:
+            schema:
+              type: object
+              required: [name, tag]
+              properties:
+                name: { type: string }
+      responses: {}
+components:
+  schemas:
+    Base:
+      properties:
+        id: { type: integer }
+    Pet:
+      allOf:
+        - $ref: "#/components/schemas/Base"
+        - properties:
+            name: { type: string }
+      required: [id, name]
+    Map:
+      type: object
+      required: [anything]
+      additionalProperties: { type: string }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let warnings = required_not_in_properties(&spec);
+
+        assert_eq!(
+            warnings,
+            vec![Warning::new(
+                "required-not-in-properties",
+                "#/paths/~1pets/post/requestBody/content/application~1json/schema",
+                "required property `tag` is not declared in `properties`",
+            )]
+        );
+    }
+
+    #[test]
+    fn read_write_only_required() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths: {}
+components:
+  schemas:
+    Id: { type: integer, readOnly: true }
+    User:
+      type: object
+      required: [id, password, name]
+      properties:
+        id: { $ref: "#/components/schemas/Id" }
+        password: { type: string, writeOnly: true }
+        name: { type: string }
+        created: { type: string, readOnly: true }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            read_only_required(&spec),
+            [Warning::new(
+                "readonly-required",
+                "#/components/schemas/User",
+                "required property `id` is `readOnly`, so it is only required in responses",
+            )]
+        );
+        assert_eq!(
+            write_only_required(&spec),
+            [Warning::new(
+                "writeonly-required",
+                "#/components/schemas/User",
+                "required property `password` is `writeOnly`, so it is only required in requests",
+            )]
+        );
+    }
+}
@@ -0,0 +1,172 @@
+//! Lints for security requirements.
+
+use super::Warning;
+use crate::{
+    json_pointer::pointer,
+    spec::{SecurityRequirement, SecurityScheme},
+    Spec,
+};
+
+/// Reports security requirements that name undeclared security schemes, or that request OAuth2
+/// scopes not declared by any flow of the scheme.
+///
+/// The requirements of the spec and of each operation are checked. Schemes that can not be
+/// resolved count as undeclared.
+pub fn invalid_security_references(spec: &Spec) -> Vec<Warning> {
+    let mut warnings = vec![];
+
+    check_requirements(
+        spec,
+        &spec.security,
+        &[],
+        "top-level security",
+        &mut warnings,
+    );
+
+    for (section, path_items) in [("paths", &spec.paths), ("webhooks", &spec.webhooks)] {
+        for (path, path_item) in path_items {
+            for (method, op) in path_item.methods() {
+                let requirements = match op.security {
+                    Some(ref requirements) => requirements,
+                    None => continue,
+                };
+
+                let subject = format!("operation `{} {}`", method, path);
+                let method = method.as_str().to_ascii_lowercase();
+
+                check_requirements(
+                    spec,
+                    requirements,
+                    &[section, path, &method],
+                    &subject,
+                    &mut warnings,
+                );
+            }
+        }
+    }
+
+    warnings
+}
+
+impl Spec {
+    /// Returns warnings for security requirements that reference undeclared schemes or scopes.
+    ///
+    /// See [`invalid_security_references`].
+    pub fn validate_security_references(&self) -> Vec<Warning> {
+        invalid_security_references(self)
+    }
+}
+
+fn check_requirements(
+    spec: &Spec,
+    requirements: &[SecurityRequirement],
+    parent: &[&str],
+    subject: &str,
+    warnings: &mut Vec<Warning>,
+) {
+    for (idx, requirement) in requirements.iter().enumerate() {
+        for (name, scopes) in requirement {
+            let idx = idx.to_string();
+            let location = pointer(parent.iter().copied().chain(["security", &idx, name]));
+
+            let scheme = spec
+                .components
+                .as_ref()
+                .and_then(|components| components.security_schemes.get(name))
+                .and_then(|scheme| scheme.resolve(spec).ok());
+
+            let flows = match scheme {
+                Some(SecurityScheme::OAuth2 { flows }) => flows,
+                Some(_) => continue,
+                None => {
+                    warnings.push(Warning::new(
+                        "security-references",
+                        location,
+                        format!("{} requires undeclared security scheme `{}`", subject, name),
+                    ));
+                    continue;
+                }
+            };
+
+            let declared = [
+                flows.implicit.as_ref().map(|flow| &flow.scopes),
+                flows.password.as_ref().map(|flow| &flow.scopes),
+                flows.client_credentials.as_ref().map(|flow| &flow.scopes),
+                flows.authorization_code.as_ref().map(|flow| &flow.scopes),
+            ];
+
+            for scope in scopes {
+                if !declared
+                    .iter()
+                    .flatten()
+                    .any(|flow| flow.contains_key(scope))
+                {
+                    warnings.push(Warning::new(
+                        "security-references",
+                        location.clone(),
+                        format!(
+                            "{} requests scope `{}`, which OAuth2 scheme `{}` does not declare",
+                            subject, scope, name
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn security_references() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+security:
+  - { apiKey: [] }
+paths:
+  /pets:
+    post:
+      security:
+        - { petstore: ["write:pets", "admin"], missing: [] }
+      responses: {}
+components:
+  securitySchemes:
+    apiKey: { type: apiKey, name: X-Key, in: header }
+    petstore:
+      type: oauth2
+      flows:
+        implicit:
+          authorizationUrl: https://example.com/auth
+          scopes:
+            "read:pets": read pets
+        clientCredentials:
+          tokenUrl: https://example.com/token
+          scopes:
+            "write:pets": modify pets
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            spec.validate_security_references(),
+            [
+                Warning::new(
+                    "security-references",
+                    "#/paths/~1pets/post/security/0/missing",
+                    "operation `POST /pets` requires undeclared security scheme `missing`",
+                ),
+                Warning::new(
+                    "security-references",
+                    "#/paths/~1pets/post/security/0/petstore",
+                    "operation `POST /pets` requests scope `admin`, which OAuth2 scheme \
+                     `petstore` does not declare",
+                ),
+            ]
+        );
+    }
+}
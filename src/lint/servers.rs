@@ -0,0 +1,178 @@
+//! Lints for servers and server variables.
+
+use super::Warning;
+use crate::{json_pointer::pointer, spec::Server, Spec};
+
+/// Reports server variables with an empty `enum`, or with a `default` that is not one of its
+/// `enum` values.
+///
+/// Either makes the server URL impossible to expand as intended. Servers of the spec, of path
+/// items and of operations are checked.
+pub fn invalid_server_variables(spec: &Spec) -> Vec<Warning> {
+    let mut warnings = vec![];
+
+    check_servers(&spec.servers, &[], &mut warnings);
+
+    for (section, path_items) in [("paths", &spec.paths), ("webhooks", &spec.webhooks)] {
+        for (path, path_item) in path_items {
+            check_servers(&path_item.servers, &[section, path], &mut warnings);
+
+            for (method, op) in path_item.methods() {
+                let method = method.as_str().to_ascii_lowercase();
+                check_servers(&op.servers, &[section, path, &method], &mut warnings);
+            }
+        }
+    }
+
+    warnings
+}
+
+/// Reports a spec that declares no `servers` while some of its operations do not declare any
+/// either, on the path item or the operation.
+///
+/// Such operations have no base URL, so clients can not be generated for them. Webhooks are not
+/// checked, since they are called by the API rather than by its clients.
+pub fn missing_servers(spec: &Spec) -> Vec<Warning> {
+    if !spec.servers.is_empty() {
+        return vec![];
+    }
+
+    let unreachable = spec
+        .paths
+        .values()
+        .filter(|path_item| path_item.servers.is_empty())
+        .flat_map(|path_item| path_item.methods())
+        .filter(|(_, op)| op.servers.is_empty())
+        .count();
+
+    if unreachable == 0 {
+        return vec![];
+    }
+
+    vec![Warning::new(
+        "no-servers",
+        pointer(Vec::<&str>::new()),
+        format!(
+            "no servers are declared, so {} operation(s) have no base URL",
+            unreachable
+        ),
+    )]
+}
+
+fn check_servers(servers: &[Server], parent: &[&str], warnings: &mut Vec<Warning>) {
+    for (idx, server) in servers.iter().enumerate() {
+        for (name, var) in &server.variables {
+            let idx = idx.to_string();
+            let location =
+                pointer(
+                    parent
+                        .iter()
+                        .copied()
+                        .chain(["servers", &idx, "variables", name]),
+                );
+
+            match var.substitutions_enum {
+                Some(ref values) if values.is_empty() => warnings.push(Warning::new(
+                    "server-variables",
+                    location,
+                    format!("`enum` of server {} variable `{}` is empty", idx, name),
+                )),
+                Some(ref values) if !values.contains(&var.default) => warnings.push(Warning::new(
+                    "server-variables",
+                    location,
+                    format!(
+                        "default `{}` of server {} variable `{}` is not one of its `enum` \
+                             values",
+                        var.default, idx, name
+                    ),
+                )),
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+servers:
+  - url: "https://{region}.example.com/{version}"
+    variables:
+      region: { default: eu, enum: [us, ap] }
+      version: { default: v1, enum: [] }
+paths:
+  /pets:
+    get:
+      servers:
+        - url: "https://{env}.example.com"
+          variables:
+            env: { default: prod, enum: [prod, staging] }
+      responses: {}
+"##;
+
+    #[test]
+    fn server_variables() {
+        let spec = crate::from_reader(SPEC.as_bytes()).unwrap();
+
+        assert_eq!(
+            invalid_server_variables(&spec),
+            [
+                Warning::new(
+                    "server-variables",
+                    "#/servers/0/variables/region",
+                    "default `eu` of server 0 variable `region` is not one of its `enum` values",
+                ),
+                Warning::new(
+                    "server-variables",
+                    "#/servers/0/variables/version",
+                    "`enum` of server 0 variable `version` is empty",
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn no_servers() {
+        let mut spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /pets:
+    servers:
+      - url: https://pets.example.com
+    get: { responses: {} }
+  /owners:
+    get:
+      servers:
+        - url: https://owners.example.com
+      responses: {}
+    post: { responses: {} }
+webhooks:
+  newPet:
+    post: { responses: {} }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            missing_servers(&spec),
+            [Warning::new(
+                "no-servers",
+                "#",
+                "no servers are declared, so 1 operation(s) have no base URL",
+            )]
+        );
+
+        spec.paths.remove("/owners");
+        assert!(missing_servers(&spec).is_empty());
+
+        let spec_with_servers = crate::from_reader(SPEC.as_bytes()).unwrap();
+        assert!(missing_servers(&spec_with_servers).is_empty());
+    }
+}
@@ -0,0 +1,215 @@
+//! Lints for components that are never used.
+
+use std::collections::BTreeSet;
+
+use log::warn;
+
+use super::Warning;
+use crate::{
+    json_pointer::{escape, unescape},
+    spec::{containing_node, find_refs, ObjectOrReference, Schema},
+    Spec,
+};
+
+/// Component sections whose components can only be used through `$ref`s.
+///
+/// Security schemes are excluded, since security requirements refer to them by name.
+const REFERENCEABLE_SECTIONS: &[&str] = &[
+    "schemas",
+    "responses",
+    "parameters",
+    "examples",
+    "requestBodies",
+    "headers",
+    "links",
+    "callbacks",
+    "pathItems",
+];
+
+/// Reports components that are not used, directly or through other components, by any path,
+/// webhook or other part of the spec outside of `components`.
+///
+/// Components that are only referenced by other unused components are reported too. Schemas
+/// named in a used schema's discriminator `mapping` count as used, as do schemas that extend
+/// a used schema with a discriminator through `allOf`, since they are variants selected by the
+/// discriminator value. Unlike a prune, this lint only reports and does not modify the spec.
+pub fn unused_components(spec: &Spec) -> Vec<Warning> {
+    let doc = match serde_json::to_value(spec) {
+        Ok(doc) => doc,
+        Err(err) => {
+            warn!(
+                "skipping unused component lint, spec can not be serialized: {}",
+                err
+            );
+            return vec![];
+        }
+    };
+
+    let graph = spec.component_dependency_graph();
+    let schemas = spec
+        .components
+        .as_ref()
+        .map(|components| &components.schemas);
+
+    let schema_node = |name: &str| format!("#/components/schemas/{}", escape(name));
+
+    // schemas selected by a discriminator: its mapping targets and the schemas extending it
+    let variants = |node: &str| {
+        let name = unescape(node.strip_prefix("#/components/schemas/")?);
+        let schema = match schemas?.get(&name)? {
+            ObjectOrReference::Object(schema) => schema,
+            ObjectOrReference::Ref { .. } => return None,
+        };
+        let discriminator = schema.discriminator.as_ref()?;
+
+        let mapped = discriminator.mapping.values().map(|target| {
+            // mapping values may be bare schema names instead of references
+            if target.contains('/') || target.starts_with('#') {
+                target.clone()
+            } else {
+                schema_node(target)
+            }
+        });
+
+        let extending = schemas?
+            .iter()
+            .filter(|(_, oor)| extends(oor, node))
+            .map(|(other, _)| schema_node(other));
+
+        Some(mapped.chain(extending).collect::<Vec<_>>())
+    };
+
+    let mut queue = find_refs(&doc)
+        .into_iter()
+        .filter(|(location, _)| !location.starts_with("#/components/"))
+        .filter_map(|(_, ref_path)| containing_node(&ref_path))
+        .collect::<Vec<_>>();
+
+    let mut used = BTreeSet::new();
+
+    while let Some(node) = queue.pop() {
+        if !used.insert(node.clone()) {
+            continue;
+        }
+
+        queue.extend(graph.dependencies(&node).map(str::to_owned));
+        queue.extend(variants(&node).into_iter().flatten());
+    }
+
+    graph
+        .nodes()
+        .filter(|node| !used.contains(*node))
+        .filter_map(|node| {
+            let rest = node.strip_prefix("#/components/")?;
+            let (section, name) = rest.split_once('/')?;
+
+            if !REFERENCEABLE_SECTIONS.contains(&section) {
+                return None;
+            }
+
+            Some(Warning::new(
+                "unused-component",
+                node,
+                format!("component `{}` is never used", unescape(name)),
+            ))
+        })
+        .collect()
+}
+
+/// Returns true if `oor` is a schema with an `allOf` member referring to `node`.
+fn extends(oor: &ObjectOrReference<Schema>, node: &str) -> bool {
+    match oor {
+        ObjectOrReference::Object(schema) => schema.all_of.iter().any(
+            |member| matches!(member, ObjectOrReference::Ref { ref_path, .. } if ref_path == node),
+        ),
+        ObjectOrReference::Ref { .. } => false,
+    }
+}
+
+impl Spec {
+    /// Returns warnings for components that are never used.
+    ///
+    /// See [`unused_components`].
+    pub fn validate_unused_components(&self) -> Vec<Warning> {
+        unused_components(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unused_components() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /pets:
+    get:
+      parameters:
+        - $ref: "#/components/parameters/Limit"
+      responses:
+        "200":
+          description: pets
+          content:
+            application/json:
+              schema: { $ref: "#/components/schemas/Pet" }
+components:
+  parameters:
+    Limit: { name: limit, in: query }
+    Offset: { name: offset, in: query }
+  schemas:
+    Pet:
+      type: object
+      discriminator:
+        propertyName: kind
+        mapping:
+          fish: Fish
+      properties:
+        owner: { $ref: "#/components/schemas/Person" }
+        headers: { $ref: "#/components/schemas/Headers" }
+        content:
+          type: array
+          items: { $ref: "#/components/schemas/Content" }
+    Person: { type: object }
+    Headers: { type: object }
+    Content: { type: object }
+    Dog:
+      allOf:
+        - $ref: "#/components/schemas/Pet"
+    Fish: { type: object }
+    Orphan:
+      properties:
+        tag: { $ref: "#/components/schemas/Tag" }
+    Tag: { type: string }
+  securitySchemes:
+    apiKey: { type: apiKey, name: X-Key, in: header }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            super::unused_components(&spec),
+            [
+                Warning::new(
+                    "unused-component",
+                    "#/components/parameters/Offset",
+                    "component `Offset` is never used",
+                ),
+                Warning::new(
+                    "unused-component",
+                    "#/components/schemas/Orphan",
+                    "component `Orphan` is never used",
+                ),
+                Warning::new(
+                    "unused-component",
+                    "#/components/schemas/Tag",
+                    "component `Tag` is never used",
+                ),
+            ]
+        );
+    }
+}
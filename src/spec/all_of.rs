@@ -0,0 +1,598 @@
+//! Flattening of `allOf` compositions.
+
+use std::collections::BTreeMap;
+
+use serde_json::{Map, Value};
+
+use super::{Error, ObjectOrReference, Schema, Spec};
+
+/// How [`Schema::merge_all_of`] handles a keyword set to different values by two members.
+///
+/// `required` lists are always combined, and properties defined by only one member are always
+/// kept, regardless of policy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Reports any conflicting keyword or property as an [`Error::AllOfConflict`].
+    ///
+    /// Suited to linting, where a conflict is usually a mistake in the spec.
+    #[default]
+    Strict,
+
+    /// Keeps the value from the member merged last, replacing conflicting properties entirely.
+    TakeLast,
+
+    /// Keeps the combination accepted by both values, as a validator sees the `allOf`:
+    ///
+    /// - `maximum`, `maxLength`, `maxItems`, `maxProperties` and numeric `exclusiveMaximum`
+    ///   take the smallest value.
+    /// - `minimum`, `minLength`, `minItems`, `minProperties` and numeric `exclusiveMinimum`
+    ///   take the largest value.
+    /// - boolean `exclusiveMaximum` and `exclusiveMinimum` are taken along with the `maximum` or
+    ///   `minimum` they apply to, and are `true` if either value is when both bounds are equal.
+    /// - `uniqueItems`, `readOnly` and `writeOnly` are `true` if either value is.
+    /// - `nullable` is `true` only if both values are.
+    /// - `enum` takes the values present in both lists.
+    /// - properties defined by both members are merged keyword by keyword with these same
+    ///   rules, unless either is a `$ref`.
+    ///
+    /// Any other keyword, e.g. `type`, `format`, `pattern` or `multipleOf`, set to different
+    /// values, as well as an `enum` intersection that is empty, is an [`Error::AllOfConflict`].
+    TakeMostRestrictive,
+}
+
+impl Schema {
+    /// Merges this schema and its `allOf` members, recursively, into a single schema.
+    ///
+    /// Also returns pointers to every schema that contributed, in merge order: `#` for this
+    /// schema, `#/allOf/0` style pointers for inline members and the `$ref` path for referenced
+    /// members. Properties are merged by name and `required` lists are combined. Any other keyword
+    /// set to different values by two members, or a property defined differently by two members,
+    /// is reported as an [`Error::AllOfConflict`] naming both sources. A referenced member that
+    /// inherits from itself through `allOf` is reported as an [`Error::AllOfCycle`] listing the
+    /// chain, while a member referenced more than once otherwise is merged only once. Keywords
+    /// that can not be combined into a valid schema are reported as an
+    /// [`Error::InvalidMergedSchema`].
+    ///
+    /// Equivalent to [`merge_all_of`](Self::merge_all_of) with [`MergePolicy::Strict`].
+    pub fn flatten_all_of(&self, spec: &Spec) -> Result<(Schema, Vec<String>), Error> {
+        self.merge_all_of(spec, MergePolicy::Strict)
+    }
+
+    /// Merges this schema and its `allOf` members, recursively, into a single schema, resolving
+    /// conflicting keywords according to `policy`.
+    ///
+    /// Returns the same sources as [`flatten_all_of`](Self::flatten_all_of).
+    pub fn merge_all_of(
+        &self,
+        spec: &Spec,
+        policy: MergePolicy,
+    ) -> Result<(Schema, Vec<String>), Error> {
+        let mut merged = Merged {
+            policy,
+            ..Merged::default()
+        };
+        merged.add(self, "#".to_owned(), spec)?;
+
+        let schema = serde_json::from_value(Value::Object(merged.doc))
+            .map_err(Error::InvalidMergedSchema)?;
+
+        Ok((schema, merged.sources))
+    }
+}
+
+#[derive(Default)]
+struct Merged {
+    policy: MergePolicy,
+
+    doc: Map<String, Value>,
+
+    /// Source of each keyword (or `properties/{name}`) merged so far.
+    origins: BTreeMap<String, String>,
+
+    sources: Vec<String>,
+
+    /// References of the members currently being merged, outermost first.
+    chain: Vec<String>,
+}
+
+impl Merged {
+    fn add(&mut self, schema: &Schema, source: String, spec: &Spec) -> Result<(), Error> {
+        let mut doc = match serde_json::to_value(schema) {
+            Ok(Value::Object(doc)) => doc,
+            _ => Map::new(),
+        };
+
+        if self.policy == MergePolicy::TakeMostRestrictive {
+            for (bound, flag) in BOUNDS {
+                if merge_bound(&mut self.doc, &mut doc, bound, flag) {
+                    self.origins.insert(bound.to_owned(), source.clone());
+                    self.origins.insert(flag.to_owned(), source.clone());
+                }
+            }
+        }
+
+        for (keyword, val) in doc {
+            match (keyword.as_str(), val) {
+                ("allOf", _) => {}
+
+                ("properties", Value::Object(props)) => {
+                    for (name, prop) in props {
+                        let existing = self
+                            .doc
+                            .entry("properties")
+                            .or_insert_with(|| Value::Object(Map::new()))
+                            .as_object_mut()
+                            .unwrap()
+                            .get(&name)
+                            .cloned();
+
+                        self.merge_value(format!("properties/{}", name), existing, prop, &source)?;
+                    }
+                }
+
+                ("required", Value::Array(names)) => {
+                    let required = self
+                        .doc
+                        .entry("required")
+                        .or_insert_with(|| Value::Array(vec![]))
+                        .as_array_mut()
+                        .unwrap();
+
+                    for name in names {
+                        if !required.contains(&name) {
+                            required.push(name);
+                        }
+                    }
+                }
+
+                (_, val) => {
+                    let existing = self.doc.get(&keyword).cloned();
+                    self.merge_value(keyword, existing, val, &source)?;
+                }
+            }
+        }
+
+        self.sources.push(source.clone());
+
+        for (idx, member) in schema.all_of.iter().enumerate() {
+            match member {
+                ObjectOrReference::Object(member) => {
+                    self.add(member, format!("{}/allOf/{}", source, idx), spec)?;
+                }
+
+                ObjectOrReference::Ref { ref_path, .. } if self.chain.contains(ref_path) => {
+                    let pos = self.chain.iter().position(|link| link == ref_path).unwrap();
+                    let mut cycle = self.chain[pos..].to_vec();
+                    cycle.push(ref_path.clone());
+                    return Err(Error::AllOfCycle(cycle));
+                }
+
+                // referenced members already merged, e.g. a shared base, are skipped
+                ObjectOrReference::Ref { ref_path, .. } if self.sources.contains(ref_path) => {}
+
+                ObjectOrReference::Ref { ref_path, .. } => {
+                    let member = member.resolve(spec)?;
+                    self.chain.push(ref_path.clone());
+                    self.add(&member, ref_path.clone(), spec)?;
+                    self.chain.pop();
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Merges a single value, identified by `key`, reporting a conflict if it is already set to
+    /// a different value that the merge policy can not combine with it.
+    fn merge_value(
+        &mut self,
+        key: String,
+        existing: Option<Value>,
+        val: Value,
+        source: &str,
+    ) -> Result<(), Error> {
+        let existing = match existing {
+            Some(existing) if existing == val => return Ok(()),
+            Some(existing) => existing,
+            None => {
+                self.set(&key, val);
+                self.origins.insert(key, source.to_owned());
+                return Ok(());
+            }
+        };
+
+        let combined = match self.policy {
+            MergePolicy::Strict => None,
+            MergePolicy::TakeLast => {
+                self.origins.insert(key.clone(), source.to_owned());
+                Some(val)
+            }
+            MergePolicy::TakeMostRestrictive if key.starts_with("properties/") => {
+                most_restrictive_schema(&existing, &val)
+            }
+            MergePolicy::TakeMostRestrictive => most_restrictive(&key, &existing, &val),
+        };
+
+        match combined {
+            Some(val) => {
+                self.set(&key, val);
+                Ok(())
+            }
+            None => Err(Error::AllOfConflict {
+                first: self.origins[&key].clone(),
+                second: source.to_owned(),
+                keyword: key,
+            }),
+        }
+    }
+
+    fn set(&mut self, key: &str, val: Value) {
+        match key.strip_prefix("properties/") {
+            Some(name) => {
+                self.doc["properties"]
+                    .as_object_mut()
+                    .unwrap()
+                    .insert(name.to_owned(), val);
+            }
+            None => {
+                self.doc.insert(key.to_owned(), val);
+            }
+        }
+    }
+}
+
+/// Numeric bounds, with the OpenAPI 3.0 boolean flags that make them exclusive.
+const BOUNDS: [(&str, &str); 2] = [
+    ("maximum", "exclusiveMaximum"),
+    ("minimum", "exclusiveMinimum"),
+];
+
+/// Merges the `bound` of `incoming` into `existing`, if both schemas set it, along with its
+/// boolean `flag`, removing both from `incoming`. Returns true if the bound of `incoming` is
+/// kept.
+///
+/// The flag applies to the bound it is declared with, so it can not be merged on its own.
+fn merge_bound(
+    existing: &mut Map<String, Value>,
+    incoming: &mut Map<String, Value>,
+    bound: &str,
+    flag: &str,
+) -> bool {
+    let (x, y) = match (existing.get(bound), incoming.get(bound)) {
+        (Some(Value::Number(x)), Some(Value::Number(y))) => (x.as_f64(), y.as_f64()),
+        _ => return false,
+    };
+
+    let incoming_bound = incoming.remove(bound).unwrap();
+    let incoming_flag = match incoming.get(flag) {
+        Some(Value::Bool(_)) => incoming.remove(flag),
+        _ => None,
+    };
+    let exclusive = |flag: Option<&Value>| matches!(flag, Some(Value::Bool(true)));
+
+    let tighter = match bound {
+        "maximum" => y < x,
+        _ => y > x,
+    };
+
+    if x == y && exclusive(incoming_flag.as_ref()) {
+        existing.insert(flag.to_owned(), Value::Bool(true));
+    }
+
+    if !tighter {
+        return false;
+    }
+
+    existing.insert(bound.to_owned(), incoming_bound);
+
+    match incoming_flag {
+        Some(incoming_flag) => {
+            existing.insert(flag.to_owned(), incoming_flag);
+        }
+        None if matches!(existing.get(flag), Some(Value::Bool(_))) => {
+            existing.remove(flag);
+        }
+        None => {}
+    }
+
+    true
+}
+
+/// Combines two values of `keyword` as described by [`MergePolicy::TakeMostRestrictive`],
+/// returning `None` if they conflict.
+fn most_restrictive(keyword: &str, a: &Value, b: &Value) -> Option<Value> {
+    if a == b {
+        return Some(a.clone());
+    }
+
+    let smaller = |x: &serde_json::Number, y: &serde_json::Number| x.as_f64() <= y.as_f64();
+
+    match (keyword, a, b) {
+        (
+            "maximum" | "exclusiveMaximum" | "maxLength" | "maxItems" | "maxProperties",
+            Value::Number(x),
+            Value::Number(y),
+        ) => Some(if smaller(x, y) { a } else { b }.clone()),
+
+        (
+            "minimum" | "exclusiveMinimum" | "minLength" | "minItems" | "minProperties",
+            Value::Number(x),
+            Value::Number(y),
+        ) => Some(if smaller(x, y) { b } else { a }.clone()),
+
+        (
+            "exclusiveMaximum" | "exclusiveMinimum" | "uniqueItems" | "readOnly" | "writeOnly",
+            Value::Bool(x),
+            Value::Bool(y),
+        ) => Some(Value::Bool(*x || *y)),
+
+        ("nullable", Value::Bool(x), Value::Bool(y)) => Some(Value::Bool(*x && *y)),
+
+        ("enum", Value::Array(x), Value::Array(y)) => {
+            let both = x
+                .iter()
+                .filter(|val| y.contains(val))
+                .cloned()
+                .collect::<Vec<_>>();
+            (!both.is_empty()).then_some(Value::Array(both))
+        }
+
+        ("required", Value::Array(x), Value::Array(y)) => {
+            let mut names = x.clone();
+            names.extend(y.iter().filter(|name| !x.contains(name)).cloned());
+            Some(Value::Array(names))
+        }
+
+        ("properties", Value::Object(x), Value::Object(y)) => {
+            let mut props = x.clone();
+
+            for (name, prop) in y {
+                let prop = match x.get(name) {
+                    Some(existing) => most_restrictive_schema(existing, prop)?,
+                    None => prop.clone(),
+                };
+                props.insert(name.clone(), prop);
+            }
+
+            Some(Value::Object(props))
+        }
+
+        _ => None,
+    }
+}
+
+/// Combines two inline schemas keyword by keyword, returning `None` if they conflict or either
+/// is a reference.
+fn most_restrictive_schema(a: &Value, b: &Value) -> Option<Value> {
+    match (a, b) {
+        _ if a == b => Some(a.clone()),
+
+        (Value::Object(x), Value::Object(y))
+            if !x.contains_key("$ref") && !y.contains_key("$ref") =>
+        {
+            let mut merged = x.clone();
+            let mut y = y.clone();
+
+            for (bound, flag) in BOUNDS {
+                merge_bound(&mut merged, &mut y, bound, flag);
+            }
+
+            for (keyword, val) in &y {
+                let val = match x.get(keyword) {
+                    Some(existing) => most_restrictive(keyword, existing, val)?,
+                    None => val.clone(),
+                };
+                merged.insert(keyword.clone(), val);
+            }
+
+            Some(Value::Object(merged))
+        }
+
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spec::BooleanOrNumber;
+
+    const SPEC: &str = r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths: {}
+components:
+  schemas:
+    Named:
+      type: object
+      required: [name]
+      properties:
+        name: { type: string }
+    Pet:
+      allOf:
+        - $ref: "#/components/schemas/Named"
+        - type: object
+          required: [name, tag]
+          properties:
+            tag: { type: string }
+    Conflicting:
+      allOf:
+        - $ref: "#/components/schemas/Named"
+        - properties:
+            name: { type: integer }
+"##;
+
+    #[test]
+    fn flatten_with_provenance() {
+        let spec = crate::from_reader(SPEC.as_bytes()).unwrap();
+        let schemas = &spec.components.as_ref().unwrap().schemas;
+
+        let pet = schemas["Pet"].resolve(&spec).unwrap();
+        let (flat, sources) = pet.flatten_all_of(&spec).unwrap();
+
+        assert_eq!(sources, ["#", "#/components/schemas/Named", "#/allOf/1"]);
+        assert!(flat.all_of.is_empty());
+        assert_eq!(flat.required, ["name", "tag"]);
+        assert_eq!(flat.properties.keys().collect::<Vec<_>>(), ["name", "tag"]);
+
+        let conflicting = schemas["Conflicting"].resolve(&spec).unwrap();
+        match conflicting.flatten_all_of(&spec) {
+            Err(Error::AllOfConflict {
+                keyword,
+                first,
+                second,
+            }) => {
+                assert_eq!(keyword, "properties/name");
+                assert_eq!(first, "#/components/schemas/Named");
+                assert_eq!(second, "#/allOf/1");
+            }
+            res => panic!("expected conflict, got {:?}", res),
+        }
+    }
+
+    #[test]
+    fn merge_cycles() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths: {}
+components:
+  schemas:
+    Base: { properties: { id: { type: integer } } }
+    Named:
+      allOf:
+        - $ref: "#/components/schemas/Base"
+    Pet:
+      allOf:
+        - $ref: "#/components/schemas/Base"
+        - $ref: "#/components/schemas/Named"
+    A:
+      allOf:
+        - $ref: "#/components/schemas/B"
+    B:
+      allOf:
+        - $ref: "#/components/schemas/A"
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+        let schemas = &spec.components.as_ref().unwrap().schemas;
+
+        let pet = schemas["Pet"].resolve(&spec).unwrap();
+        let (_, sources) = pet.flatten_all_of(&spec).unwrap();
+        assert_eq!(
+            sources,
+            [
+                "#",
+                "#/components/schemas/Base",
+                "#/components/schemas/Named"
+            ]
+        );
+
+        let a = schemas["A"].resolve(&spec).unwrap();
+        match a.flatten_all_of(&spec) {
+            Err(Error::AllOfCycle(cycle)) => assert_eq!(
+                cycle,
+                [
+                    "#/components/schemas/B",
+                    "#/components/schemas/A",
+                    "#/components/schemas/B"
+                ]
+            ),
+            res => panic!("expected cycle, got {:?}", res),
+        }
+    }
+
+    #[test]
+    fn merge_policies() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.0.3
+info: { title: Test, version: "1" }
+paths: {}
+components:
+  schemas:
+    Name:
+      allOf:
+        - type: string
+          maxLength: 20
+          minLength: 1
+          nullable: true
+          enum: [a, b, c]
+        - maxLength: 10
+          minLength: 3
+          nullable: false
+          enum: [b, c, d]
+    Person:
+      allOf:
+        - properties:
+            age: { type: integer, maximum: 150 }
+        - properties:
+            age: { type: integer, maximum: 120, minimum: 0 }
+    Mismatched:
+      allOf:
+        - format: date
+        - format: date-time
+    Bounded:
+      allOf:
+        - { maximum: 10, exclusiveMaximum: true, minimum: 0, exclusiveMinimum: true }
+        - { maximum: 5, minimum: 0 }
+        - properties:
+            n: { maximum: 5, exclusiveMaximum: true, minimum: 1 }
+        - properties:
+            n: { maximum: 10, minimum: 2, exclusiveMinimum: true }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+        let schemas = &spec.components.as_ref().unwrap().schemas;
+
+        let name = schemas["Name"].resolve(&spec).unwrap();
+        assert!(name.merge_all_of(&spec, MergePolicy::Strict).is_err());
+
+        let (last, _) = name.merge_all_of(&spec, MergePolicy::TakeLast).unwrap();
+        assert_eq!(last.max_length, Some(10));
+        assert_eq!(last.min_length, Some(3));
+        assert_eq!(last.enum_values.map(|values| values.len()), Some(3));
+
+        let (strict, _) = name
+            .merge_all_of(&spec, MergePolicy::TakeMostRestrictive)
+            .unwrap();
+        assert_eq!(strict.max_length, Some(10));
+        assert_eq!(strict.min_length, Some(3));
+        assert_eq!(strict.nullable, Some(false));
+        assert_eq!(strict.enum_values.unwrap(), ["b", "c"]);
+
+        let person = schemas["Person"].resolve(&spec).unwrap();
+        let (merged, _) = person
+            .merge_all_of(&spec, MergePolicy::TakeMostRestrictive)
+            .unwrap();
+        let age = merged.properties["age"].resolve(&spec).unwrap();
+        assert_eq!(age.maximum, Some(120.into()));
+        assert_eq!(age.minimum, Some(0.into()));
+
+        let bounded = schemas["Bounded"].resolve(&spec).unwrap();
+        let (merged, _) = bounded
+            .merge_all_of(&spec, MergePolicy::TakeMostRestrictive)
+            .unwrap();
+        assert_eq!(merged.maximum, Some(5.into()));
+        assert_eq!(merged.exclusive_maximum, None);
+        assert_eq!(merged.minimum, Some(0.into()));
+        assert_eq!(
+            merged.exclusive_minimum,
+            Some(BooleanOrNumber::Boolean(true))
+        );
+        let n = merged.properties["n"].resolve(&spec).unwrap();
+        assert_eq!(n.maximum, Some(5.into()));
+        assert_eq!(n.exclusive_maximum, Some(BooleanOrNumber::Boolean(true)));
+        assert_eq!(n.minimum, Some(2.into()));
+        assert_eq!(n.exclusive_minimum, Some(BooleanOrNumber::Boolean(true)));
+
+        let mismatched = schemas["Mismatched"].resolve(&spec).unwrap();
+        match mismatched.merge_all_of(&spec, MergePolicy::TakeMostRestrictive) {
+            Err(Error::AllOfConflict { keyword, .. }) => assert_eq!(keyword, "format"),
+            res => panic!("expected conflict, got {:?}", res),
+        }
+    }
+}
@@ -0,0 +1,464 @@
+//! Bundling of multi-file specifications into a single document.
+
+use std::{collections::BTreeMap, fs::File, path::PathBuf};
+
+use serde_json::Value;
+
+use super::{r#ref, Error, Spec};
+use crate::json_pointer::{escape, unescape};
+
+/// Loads the documents that external `$ref`s point to, for [`Spec::bundle`].
+pub trait RefResolver {
+    /// Loads the document at `uri`, the part of an external reference before the `#`, e.g.
+    /// `schemas/pet.yaml`.
+    ///
+    /// Relative URIs in a loaded document are resolved against the URI of that document before
+    /// being passed on, so a resolver only needs to resolve them against the root document.
+    fn load(&self, uri: &str) -> Result<Value, Error>;
+}
+
+/// Resolves external documents from in-memory values, keyed by URI.
+impl RefResolver for BTreeMap<String, Value> {
+    fn load(&self, uri: &str) -> Result<Value, Error> {
+        self.get(uri).cloned().ok_or_else(|| Error::ExternalRef {
+            reference: uri.to_owned(),
+            reason: "document not found".to_owned(),
+        })
+    }
+}
+
+/// Resolves external documents as YAML or JSON files relative to a base directory, usually
+/// that of the root document.
+#[derive(Debug, Clone)]
+pub struct FileResolver {
+    base: PathBuf,
+}
+
+impl FileResolver {
+    pub fn new(base: impl Into<PathBuf>) -> Self {
+        Self { base: base.into() }
+    }
+}
+
+impl RefResolver for FileResolver {
+    fn load(&self, uri: &str) -> Result<Value, Error> {
+        let err = |reason: String| Error::ExternalRef {
+            reference: uri.to_owned(),
+            reason,
+        };
+
+        let file = File::open(self.base.join(uri)).map_err(|err_| err(err_.to_string()))?;
+        serde_yaml::from_reader(file).map_err(|err_| err(err_.to_string()))
+    }
+}
+
+impl Spec {
+    /// Produces a single self-contained document by pulling the targets of all external `$ref`s
+    /// into `components`.
+    ///
+    /// External references are rewritten to point to the new local components, while internal
+    /// references are left as-is, so recursive schemas stay recursive. References within pulled
+    /// documents are followed too, and each external target is added only once. Components
+    /// are named after the last segment of the target pointer, or the document's file name if
+    /// the whole document is referenced, with a numeric suffix if the name is taken. The
+    /// component type is taken from the target pointer if it points into another document's
+    /// `components`, and otherwise from where the reference is used.
+    ///
+    /// References within literal data, such as examples, defaults and extensions, are not
+    /// followed.
+    ///
+    /// Returns [`Error::ExternalRef`] if a document can not be loaded, a target does not exist,
+    /// the component type of a reference can not be determined or an external reference is left
+    /// in the bundled document.
+    pub fn bundle(&self, resolver: &dyn RefResolver) -> Result<Spec, Error> {
+        let mut doc = serde_json::to_value(self).unwrap();
+        let mut documents = BTreeMap::<String, Value>::new();
+        let mut bundled = BTreeMap::<String, String>::new();
+
+        loop {
+            let external = external_refs(&doc);
+
+            if external.is_empty() {
+                break;
+            }
+
+            for (location, ref_path) in external {
+                let reference = resolve_uri("", &ref_path);
+
+                if bundled.contains_key(&reference) {
+                    continue;
+                }
+
+                let err = |reason: &str| Error::ExternalRef {
+                    reference: reference.clone(),
+                    reason: reason.to_owned(),
+                };
+
+                let (uri, fragment) = reference
+                    .split_once('#')
+                    .unwrap_or((reference.as_str(), ""));
+
+                if !documents.contains_key(uri) {
+                    documents.insert(uri.to_owned(), resolver.load(uri)?);
+                }
+
+                let mut target = documents[uri]
+                    .pointer(fragment)
+                    .cloned()
+                    .ok_or_else(|| err("target not found"))?;
+
+                let fragment_segments = fragment
+                    .split('/')
+                    .skip(1)
+                    .map(unescape)
+                    .collect::<Vec<_>>();
+
+                let section = match fragment_segments.as_slice() {
+                    [components, section, _] if components == "components" => section.clone(),
+                    _ => component_section(&location)
+                        .ok_or_else(|| err("can not determine component type"))?
+                        .to_owned(),
+                };
+
+                let base_name = match fragment_segments.last() {
+                    Some(name) if !name.is_empty() => name.clone(),
+                    _ => file_stem(uri),
+                };
+
+                // references in the pulled document are relative to it
+                r#ref::rewrite_refs_at(
+                    &mut target,
+                    &format!("#/components/{}/{}", section, escape(&base_name)),
+                    &mut |nested| Some(resolve_uri(uri, nested)),
+                );
+
+                let components = doc
+                    .as_object_mut()
+                    .unwrap()
+                    .entry("components")
+                    .or_insert_with(|| Value::Object(Default::default()))
+                    .as_object_mut()
+                    .unwrap()
+                    .entry(section.clone())
+                    .or_insert_with(|| Value::Object(Default::default()))
+                    .as_object_mut()
+                    .unwrap();
+
+                let mut name = base_name.clone();
+                let mut suffix = 1;
+
+                while components.contains_key(&name) {
+                    suffix += 1;
+                    name = format!("{}{}", base_name, suffix);
+                }
+
+                components.insert(name.clone(), target);
+                bundled.insert(
+                    reference,
+                    format!("#/components/{}/{}", section, escape(&name)),
+                );
+            }
+
+            let rewritten = r#ref::rewrite_refs(&mut doc, &mut |ref_path| {
+                bundled.get(&resolve_uri("", ref_path)).cloned()
+            });
+
+            if !rewritten {
+                break;
+            }
+        }
+
+        let bundle: Spec = serde_json::from_value(doc).map_err(Error::InvalidBundle)?;
+
+        match external_refs(&serde_json::to_value(&bundle).unwrap())
+            .into_iter()
+            .next()
+        {
+            Some((_, reference)) => Err(Error::ExternalRef {
+                reference,
+                reason: "reference is left in the bundled document".to_owned(),
+            }),
+            None => Ok(bundle),
+        }
+    }
+}
+
+/// Returns the locations and paths of the `$ref`s in `doc` that point to other documents.
+fn external_refs(doc: &Value) -> Vec<(String, String)> {
+    r#ref::find_refs(doc)
+        .into_iter()
+        .filter(|(_, ref_path)| !ref_path.starts_with('#'))
+        .collect()
+}
+
+/// Resolves `reference` against the document at `base`, normalizing `.` and `..` segments.
+///
+/// Absolute URLs and local references to the root document (`#...` with an empty `base`) are
+/// returned as-is.
+fn resolve_uri(base: &str, reference: &str) -> String {
+    if reference.contains("://") {
+        return reference.to_owned();
+    }
+
+    // an empty fragment refers to the whole document, as does no fragment
+    let (path, fragment) = match reference.split_once('#') {
+        Some((path, fragment)) if !fragment.is_empty() => (path, Some(fragment)),
+        Some((path, _)) => (path, None),
+        None => (reference, None),
+    };
+
+    let path = if path.is_empty() {
+        base.to_owned()
+    } else if path.starts_with('/') {
+        path.to_owned()
+    } else {
+        let dir = base.rfind('/').map(|idx| &base[..=idx]).unwrap_or("");
+        format!("{}{}", dir, path)
+    };
+
+    let mut segments = Vec::<&str>::new();
+
+    for segment in path.split('/') {
+        match segment {
+            "." => {}
+            ".." if matches!(segments.last(), Some(last) if *last != "..") => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+
+    let path = segments.join("/");
+
+    match fragment {
+        Some(fragment) => format!("{}#{}", path, fragment),
+        None => path,
+    }
+}
+
+/// Returns the file name of `uri` without its extension, e.g. `pet` for `schemas/pet.yaml`.
+fn file_stem(uri: &str) -> String {
+    let name = uri.rsplit('/').next().unwrap_or(uri);
+    name.split('.').next().unwrap_or(name).to_owned()
+}
+
+/// Keywords whose value is a single subschema.
+const SCHEMA_KEYWORDS: &[&str] = &[
+    "schema",
+    "items",
+    "additionalProperties",
+    "not",
+    "if",
+    "then",
+    "else",
+    "contains",
+    "propertyNames",
+    "unevaluatedProperties",
+    "unevaluatedItems",
+    "contentSchema",
+];
+
+/// Keywords whose value is a map or list of subschemas.
+const SCHEMA_COLLECTION_KEYWORDS: &[&str] = &[
+    "properties",
+    "patternProperties",
+    "dependentSchemas",
+    "$defs",
+    "allOf",
+    "anyOf",
+    "oneOf",
+    "prefixItems",
+];
+
+/// Returns the component section for an object referenced at the JSON pointer to a `$ref`,
+/// e.g. `parameters` for `#/paths/~1pets/get/parameters/0/$ref`.
+fn component_section(ref_location: &str) -> Option<&'static str> {
+    let segments = ref_location
+        .strip_prefix("#/")?
+        .split('/')
+        .map(unescape)
+        .collect::<Vec<_>>();
+
+    // drop the trailing `$ref`
+    let segments = &segments[..segments.len().checked_sub(1)?];
+    let last = segments.last()?.as_str();
+    let parent = segments
+        .len()
+        .checked_sub(2)
+        .map(|idx| segments[idx].as_str());
+    let grandparent = segments
+        .len()
+        .checked_sub(3)
+        .map(|idx| segments[idx].as_str());
+
+    if let [components, section, _] = segments {
+        if components == "components" {
+            return SECTIONS.iter().copied().find(|known| known == section);
+        }
+    }
+
+    if let [paths, _] = segments {
+        if paths == "paths" || paths == "webhooks" {
+            return Some("pathItems");
+        }
+    }
+
+    if SCHEMA_KEYWORDS.contains(&last)
+        || matches!(parent, Some(parent) if SCHEMA_COLLECTION_KEYWORDS.contains(&parent))
+    {
+        return Some("schemas");
+    }
+
+    if last == "requestBody" {
+        return Some("requestBodies");
+    }
+
+    if grandparent == Some("callbacks") {
+        return Some("pathItems");
+    }
+
+    match parent? {
+        "parameters" => Some("parameters"),
+        "responses" => Some("responses"),
+        "headers" => Some("headers"),
+        "examples" => Some("examples"),
+        "links" => Some("links"),
+        "callbacks" => Some("callbacks"),
+        _ => None,
+    }
+}
+
+/// Sections of `components`.
+const SECTIONS: &[&str] = &[
+    "schemas",
+    "responses",
+    "parameters",
+    "examples",
+    "requestBodies",
+    "headers",
+    "securitySchemes",
+    "links",
+    "callbacks",
+    "pathItems",
+];
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn bundle() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /pets:
+    get:
+      parameters:
+        - $ref: "./common.yaml#/components/parameters/Limit"
+      responses:
+        "200":
+          description: pets
+          content:
+            application/json:
+              schema: { $ref: "schemas/pet.yaml" }
+components:
+  schemas:
+    Error: { $ref: "common.yaml#/Error" }
+    Message:
+      properties:
+        headers: { $ref: "common.yaml#/Headers" }
+      example:
+        headers: { $ref: "missing.yaml#/Headers" }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let resolver = BTreeMap::from([
+            (
+                "common.yaml".to_owned(),
+                json!({
+                    "Error": { "type": "object" },
+                    "Headers": { "type": "object" },
+                    "components": {
+                        "parameters": {
+                            "Limit": { "name": "limit", "in": "query" }
+                        }
+                    }
+                }),
+            ),
+            (
+                "schemas/pet.yaml".to_owned(),
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "children": { "items": { "$ref": "#" } },
+                        "error": { "$ref": "../common.yaml#/Error" },
+                        "owner": { "$ref": "person.yaml#/Person" }
+                    }
+                }),
+            ),
+            (
+                "schemas/person.yaml".to_owned(),
+                json!({ "Person": { "type": "object" } }),
+            ),
+        ]);
+
+        let bundled = spec.bundle(&resolver).unwrap();
+        assert!(bundled.dangling_refs().is_empty());
+
+        let doc = serde_json::to_value(&bundled).unwrap();
+        let schemas = &doc["components"]["schemas"];
+
+        assert_eq!(
+            doc["paths"]["/pets"]["get"]["parameters"][0],
+            json!({ "$ref": "#/components/parameters/Limit" })
+        );
+        assert_eq!(
+            schemas["Error"],
+            json!({ "$ref": "#/components/schemas/Error2" })
+        );
+        assert_eq!(schemas["Error2"], json!({ "type": "object" }));
+        assert_eq!(
+            schemas["Message"],
+            json!({
+                "properties": {
+                    "headers": { "$ref": "#/components/schemas/Headers" }
+                },
+                "example": {
+                    "headers": { "$ref": "missing.yaml#/Headers" }
+                }
+            })
+        );
+        assert_eq!(
+            schemas["pet"]["properties"],
+            json!({
+                "children": { "items": { "$ref": "#/components/schemas/pet" } },
+                "error": { "$ref": "#/components/schemas/Error2" },
+                "owner": { "$ref": "#/components/schemas/Person" }
+            })
+        );
+
+        assert!(matches!(
+            spec.bundle(&BTreeMap::new()),
+            Err(Error::ExternalRef { .. })
+        ));
+    }
+
+    #[test]
+    fn resolve_uris() {
+        assert_eq!(resolve_uri("", "./a.yaml#/X"), "a.yaml#/X");
+        assert_eq!(resolve_uri("dir/b.yaml", "#/X"), "dir/b.yaml#/X");
+        assert_eq!(resolve_uri("dir/b.yaml", "#"), "dir/b.yaml");
+        assert_eq!(resolve_uri("dir/b.yaml", "../a.yaml"), "a.yaml");
+        assert_eq!(resolve_uri("dir/b.yaml", "c.yaml#/Y"), "dir/c.yaml#/Y");
+        assert_eq!(
+            resolve_uri("dir/b.yaml", "https://example.com/a.yaml#/X"),
+            "https://example.com/a.yaml#/X"
+        );
+    }
+}
@@ -0,0 +1,181 @@
+//! Classification of schemas by the kind of type a code generator would emit for them.
+
+use std::collections::BTreeMap;
+
+use log::error;
+
+use super::{
+    schema::{Type, COMPOSITION_MAX_DEPTH},
+    ObjectOrReference, Schema, Spec,
+};
+
+/// The kind of type a code generator would emit for a schema.
+///
+/// Classes are ordered in a typical generation order: enums have no dependencies, objects may
+/// use enums, unions may combine objects, and newtypes may wrap any of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SchemaClass {
+    /// A schema restricted to a fixed list of values by `enum` or `const`.
+    Enum,
+
+    /// An object with properties, including `allOf` compositions with an object member.
+    Object,
+
+    /// A `oneOf` or `anyOf` composition.
+    Union,
+
+    /// A wrapper around a primitive or array type.
+    Newtype,
+
+    /// A schema without type information, accepting any value.
+    Any,
+}
+
+impl Schema {
+    /// Classifies this schema by the kind of type a code generator would emit for it.
+    ///
+    /// References are resolved, and an `allOf` composition is classified as an object if
+    /// any of its members is one, or as its first member otherwise. Unresolvable references
+    /// are classified as [`SchemaClass::Any`].
+    pub fn classify(&self, spec: &Spec) -> SchemaClass {
+        self.classify_inner(spec, 0)
+    }
+
+    fn classify_inner(&self, spec: &Spec, depth: usize) -> SchemaClass {
+        if matches!(self.enum_values, Some(ref values) if !values.is_empty())
+            || self.const_value.is_some()
+        {
+            return SchemaClass::Enum;
+        }
+
+        if !self.one_of.is_empty() || !self.any_of.is_empty() {
+            return SchemaClass::Union;
+        }
+
+        match self
+            .schema_type
+            .as_ref()
+            .and_then(|types| types.single_non_null())
+        {
+            Some(Type::Object) => return SchemaClass::Object,
+            Some(_) => return SchemaClass::Newtype,
+            None => {}
+        }
+
+        if !self.properties.is_empty() || self.additional_properties.is_some() {
+            return SchemaClass::Object;
+        }
+
+        if depth > COMPOSITION_MAX_DEPTH {
+            return SchemaClass::Any;
+        }
+
+        let members = self
+            .all_of
+            .iter()
+            .map(|member| match member {
+                ObjectOrReference::Object(schema) => schema.classify_inner(spec, depth + 1),
+                ObjectOrReference::Ref { .. } => match member.resolve(spec) {
+                    Ok(schema) => schema.classify_inner(spec, depth + 1),
+                    Err(_) => SchemaClass::Any,
+                },
+            })
+            .collect::<Vec<_>>();
+
+        if members.contains(&SchemaClass::Object) {
+            return SchemaClass::Object;
+        }
+
+        members.first().copied().unwrap_or(SchemaClass::Any)
+    }
+}
+
+impl Spec {
+    /// Groups the names of component schemas by their [`Schema::classify`] result.
+    ///
+    /// Iterating the map gives a generation plan in [`SchemaClass`] order, with names sorted
+    /// within each class. Components that are references to other schemas are classified as
+    /// their target.
+    pub fn group_schemas_by_class(&self) -> BTreeMap<SchemaClass, Vec<String>> {
+        let mut groups = BTreeMap::<_, Vec<_>>::new();
+
+        for (name, schema) in self
+            .components
+            .iter()
+            .flat_map(|components| &components.schemas)
+        {
+            match schema.resolve(self) {
+                Ok(schema) => groups
+                    .entry(schema.classify(self))
+                    .or_default()
+                    .push(name.clone()),
+
+                // TODO: find better error solution
+                Err(err) => error!("{}", err),
+            }
+        }
+
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn group_schemas() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths: {}
+components:
+  schemas:
+    Status: { type: string, enum: [available, sold] }
+    Kind: { const: pet }
+    Pet:
+      type: object
+      properties:
+        status: { $ref: "#/components/schemas/Status" }
+    Dog:
+      allOf:
+        - { $ref: "#/components/schemas/Pet" }
+        - { properties: { barks: { type: boolean } } }
+    Animal:
+      oneOf:
+        - { $ref: "#/components/schemas/Dog" }
+    Id: { type: integer }
+    Tags: { type: array, items: { type: string } }
+    PetAlias: { $ref: "#/components/schemas/Pet" }
+    StatusWrapper:
+      allOf:
+        - { $ref: "#/components/schemas/Status" }
+    Anything: {}
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let groups = spec.group_schemas_by_class();
+
+        assert_eq!(
+            groups[&SchemaClass::Enum],
+            ["Kind", "Status", "StatusWrapper"]
+        );
+        assert_eq!(groups[&SchemaClass::Object], ["Dog", "Pet", "PetAlias"]);
+        assert_eq!(groups[&SchemaClass::Union], ["Animal"]);
+        assert_eq!(groups[&SchemaClass::Newtype], ["Id", "Tags"]);
+        assert_eq!(groups[&SchemaClass::Any], ["Anything"]);
+        assert_eq!(
+            groups.keys().copied().collect::<Vec<_>>(),
+            [
+                SchemaClass::Enum,
+                SchemaClass::Object,
+                SchemaClass::Union,
+                SchemaClass::Newtype,
+                SchemaClass::Any,
+            ]
+        );
+    }
+}
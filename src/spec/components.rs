@@ -1,9 +1,9 @@
-use std::collections::BTreeMap;
+use std::collections::{btree_map::Entry, BTreeMap};
 
 use serde::{Deserialize, Serialize};
 
 use super::{
-    schema::Schema, Callback, Example, Header, Link, ObjectOrReference, Parameter, PathItem,
+    schema::Schema, Callback, Error, Example, Header, Link, ObjectOrReference, Parameter, PathItem,
     RequestBody, Response, SecurityScheme,
 };
 
@@ -66,3 +66,96 @@ pub struct Components {
     pub callbacks: BTreeMap<String, ObjectOrReference<Callback>>,
     // TODO: Add "Specification Extensions" https://github.com/OAI/OpenAPI-Specification/blob/HEAD/versions/3.1.0.md#specificationExtensions}
 }
+
+/// Generates the `add_*` and `insert_*` methods of a components section.
+macro_rules! component_methods {
+    ($(($field:ident, $section:literal, $ty:ty, $add:ident, $insert:ident),)*) => {
+        impl Components {
+            $(
+                #[doc = concat!("Adds a component to `", $section, "`, returning ")]
+                #[doc = "[`Error::ComponentExists`] if the name is already taken."]
+                pub fn $add(
+                    &mut self,
+                    name: &str,
+                    component: impl Into<ObjectOrReference<$ty>>,
+                ) -> Result<(), Error> {
+                    match self.$field.entry(name.to_owned()) {
+                        Entry::Occupied(_) => Err(Error::ComponentExists(format!(
+                            "#/components/{}/{}",
+                            $section,
+                            crate::json_pointer::escape(name)
+                        ))),
+                        Entry::Vacant(entry) => {
+                            entry.insert(component.into());
+                            Ok(())
+                        }
+                    }
+                }
+
+                #[doc = concat!("Adds a component to `", $section, "`, replacing and returning ")]
+                #[doc = "any existing component with the same name."]
+                pub fn $insert(
+                    &mut self,
+                    name: &str,
+                    component: impl Into<ObjectOrReference<$ty>>,
+                ) -> Option<ObjectOrReference<$ty>> {
+                    self.$field.insert(name.to_owned(), component.into())
+                }
+            )*
+        }
+    };
+}
+
+component_methods! {
+    (schemas, "schemas", Schema, add_schema, insert_schema),
+    (responses, "responses", Response, add_response, insert_response),
+    (parameters, "parameters", Parameter, add_parameter, insert_parameter),
+    (examples, "examples", Example, add_example, insert_example),
+    (request_bodies, "requestBodies", RequestBody, add_request_body, insert_request_body),
+    (headers, "headers", Header, add_header, insert_header),
+    (path_items, "pathItems", PathItem, add_path_item, insert_path_item),
+    (security_schemes, "securitySchemes", SecurityScheme, add_security_scheme, insert_security_scheme),
+    (links, "links", Link, add_link, insert_link),
+    (callbacks, "callbacks", Callback, add_callback, insert_callback),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_components() {
+        let mut components = Components::default();
+
+        components.add_schema("Pet", Schema::default()).unwrap();
+        components
+            .add_schema(
+                "Dog",
+                ObjectOrReference::new_ref("#/components/schemas/Pet"),
+            )
+            .unwrap();
+
+        match components.add_schema("Pet", Schema::default()).unwrap_err() {
+            Error::ComponentExists(ref_path) => assert_eq!(ref_path, "#/components/schemas/Pet"),
+            err => panic!("unexpected error: {}", err),
+        }
+
+        let previous = components.insert_schema(
+            "Pet",
+            Schema {
+                title: Some("Pet".to_owned()),
+                ..Schema::default()
+            },
+        );
+        assert_eq!(previous, Some(ObjectOrReference::Object(Schema::default())));
+        assert_eq!(components.schemas.len(), 2);
+
+        components
+            .add_request_body("Pet", RequestBody::default())
+            .unwrap();
+        assert!(matches!(
+            components.add_request_body("Pet", RequestBody::default()),
+            Err(Error::ComponentExists(ref_path)) if ref_path == "#/components/requestBodies/Pet"
+        ));
+    }
+}
@@ -0,0 +1,126 @@
+//! JSON Schema dialects that schemas can be interpreted with.
+
+use super::{Error, Schema, Spec, JSON_SCHEMA_DIALECT_2020_12};
+
+const JSON_SCHEMA_DIALECT_DRAFT_07: &str = "http://json-schema.org/draft-07/schema";
+const OAS_3_1_DIALECT_BASE: &str = "https://spec.openapis.org/oas/3.1/dialect/base";
+
+/// A supported JSON Schema dialect.
+///
+/// The dialects differ in how array keywords are applied:
+///
+/// - In 2020-12, `prefixItems` validates leading items by position and `items` validates the
+///   rest.
+/// - In draft-07, `items` validates every item and `prefixItems` is not a keyword, so it is
+///   ignored. The tuple form of draft-07 `items` (and so `additionalItems`) is not supported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SchemaDialect {
+    Draft07,
+
+    /// JSON Schema 2020-12, and the OpenAPI 3.1 base dialect that extends it.
+    #[default]
+    Draft2020_12,
+}
+
+impl SchemaDialect {
+    /// Parses a dialect from its meta-schema URI, as used by `$schema` and `jsonSchemaDialect`.
+    ///
+    /// Returns [`Error::UnsupportedDialect`] for any other URI.
+    pub fn from_uri(uri: &str) -> Result<Self, Error> {
+        let normalized = uri.trim_end_matches('#');
+
+        match normalized {
+            JSON_SCHEMA_DIALECT_2020_12 | OAS_3_1_DIALECT_BASE => Ok(SchemaDialect::Draft2020_12),
+            JSON_SCHEMA_DIALECT_DRAFT_07 => Ok(SchemaDialect::Draft07),
+            _ if normalized == JSON_SCHEMA_DIALECT_DRAFT_07.replacen("http:", "https:", 1) => {
+                Ok(SchemaDialect::Draft07)
+            }
+            _ => Err(Error::UnsupportedDialect(uri.to_owned())),
+        }
+    }
+
+    /// Returns the meta-schema URI of this dialect.
+    pub fn uri(self) -> &'static str {
+        match self {
+            SchemaDialect::Draft07 => JSON_SCHEMA_DIALECT_DRAFT_07,
+            SchemaDialect::Draft2020_12 => JSON_SCHEMA_DIALECT_2020_12,
+        }
+    }
+}
+
+impl Spec {
+    /// Returns the default dialect of this spec's schemas, given by `jsonSchemaDialect`.
+    ///
+    /// Defaults to JSON Schema 2020-12.
+    pub fn schema_dialect(&self) -> Result<SchemaDialect, Error> {
+        match self.json_schema_dialect.as_deref() {
+            Some(uri) => SchemaDialect::from_uri(uri),
+            None => Ok(SchemaDialect::default()),
+        }
+    }
+}
+
+impl Schema {
+    /// Returns the dialect of this schema, given by its `$schema` keyword, or `inherited` if it
+    /// has none.
+    pub fn dialect(&self, inherited: SchemaDialect) -> Result<SchemaDialect, Error> {
+        match self.schema_dialect.as_deref() {
+            Some(uri) => SchemaDialect::from_uri(uri),
+            None => Ok(inherited),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dialect_uris() {
+        for uri in [
+            "https://json-schema.org/draft/2020-12/schema",
+            "https://spec.openapis.org/oas/3.1/dialect/base",
+        ] {
+            assert_eq!(
+                SchemaDialect::from_uri(uri).unwrap(),
+                SchemaDialect::Draft2020_12
+            );
+        }
+
+        for uri in [
+            "http://json-schema.org/draft-07/schema#",
+            "https://json-schema.org/draft-07/schema",
+        ] {
+            assert_eq!(
+                SchemaDialect::from_uri(uri).unwrap(),
+                SchemaDialect::Draft07
+            );
+        }
+
+        let err = SchemaDialect::from_uri("http://json-schema.org/draft-04/schema#").unwrap_err();
+        assert!(matches!(err, Error::UnsupportedDialect(_)));
+    }
+
+    #[test]
+    fn schema_dialect_inheritance() {
+        let mut spec = Spec::default();
+        assert_eq!(spec.schema_dialect().unwrap(), SchemaDialect::Draft2020_12);
+
+        spec.json_schema_dialect = Some("http://json-schema.org/draft-07/schema#".to_owned());
+        let inherited = spec.schema_dialect().unwrap();
+        assert_eq!(inherited, SchemaDialect::Draft07);
+
+        let schema = Schema {
+            schema_dialect: Some(SchemaDialect::Draft2020_12.uri().to_owned()),
+            ..Schema::default()
+        };
+        assert_eq!(
+            schema.dialect(inherited).unwrap(),
+            SchemaDialect::Draft2020_12
+        );
+        assert_eq!(
+            Schema::default().dialect(inherited).unwrap(),
+            SchemaDialect::Draft07
+        );
+    }
+}
@@ -0,0 +1,393 @@
+//! Structural differences between specifications, and JSON Patch support.
+
+use serde_json::{json, Value};
+
+use super::{Error, Spec};
+use crate::json_pointer::{pointer, unescape};
+
+/// A single difference between two spec documents.
+///
+/// Locations are JSON pointer fragments into the serialized spec, e.g. `#/paths/~1pets/get`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    /// A value present only in the new spec.
+    Added { location: String, value: Value },
+
+    /// A value present only in the old spec.
+    Removed { location: String, old: Value },
+
+    /// A value that differs between the specs.
+    Modified {
+        location: String,
+        old: Value,
+        new: Value,
+    },
+}
+
+impl Change {
+    /// Returns the location of the changed value.
+    pub fn location(&self) -> &str {
+        match self {
+            Change::Added { location, .. }
+            | Change::Removed { location, .. }
+            | Change::Modified { location, .. } => location,
+        }
+    }
+}
+
+/// The differences between two specs, returned by [`Spec::diff`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpecDiff {
+    /// Changes in document order, with object keys compared by name.
+    ///
+    /// Arrays of different lengths are reported as a single modification of the whole array.
+    pub changes: Vec<Change>,
+}
+
+impl SpecDiff {
+    /// Returns true if the specs are identical.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Expresses the changes as an [RFC 6902](https://www.rfc-editor.org/rfc/rfc6902) JSON
+    /// Patch document over the serialized old spec.
+    ///
+    /// Applying the patch to the old spec with [`Spec::apply_json_patch`] yields the new spec.
+    pub fn to_json_patch(&self) -> Value {
+        let ops = self
+            .changes
+            .iter()
+            .map(|change| {
+                let path = &change.location()[1..];
+
+                match change {
+                    Change::Added { value, .. } => {
+                        json!({ "op": "add", "path": path, "value": value })
+                    }
+                    Change::Removed { .. } => json!({ "op": "remove", "path": path }),
+                    Change::Modified { new, .. } => {
+                        json!({ "op": "replace", "path": path, "value": new })
+                    }
+                }
+            })
+            .collect();
+
+        Value::Array(ops)
+    }
+}
+
+impl Spec {
+    /// Compares the serialized forms of this spec and `new`, returning the changes from this
+    /// spec to `new`.
+    pub fn diff(&self, new: &Spec) -> SpecDiff {
+        let old = serde_json::to_value(self).unwrap();
+        let new = serde_json::to_value(new).unwrap();
+
+        let mut diff = SpecDiff::default();
+        diff_values(&old, &new, &mut vec![], &mut diff.changes);
+        diff
+    }
+
+    /// Applies an [RFC 6902](https://www.rfc-editor.org/rfc/rfc6902) JSON Patch document to the
+    /// serialized form of this spec, returning the patched spec.
+    ///
+    /// All operations are supported. Returns [`Error::InvalidPatch`] if the patch is malformed,
+    /// an operation fails (including a failed `test`), or the result is not a valid spec.
+    pub fn apply_json_patch(&self, patch: &Value) -> Result<Spec, Error> {
+        let err = |reason: String| Error::InvalidPatch(reason);
+
+        let mut doc = serde_json::to_value(self).unwrap();
+        let ops = patch
+            .as_array()
+            .ok_or_else(|| err("patch is not an array".to_owned()))?;
+
+        for (idx, op) in ops.iter().enumerate() {
+            let field = |name: &str| {
+                op.get(name)
+                    .ok_or_else(|| err(format!("operation {} has no `{}`", idx, name)))
+            };
+
+            let path = field("path")?
+                .as_str()
+                .ok_or_else(|| err(format!("operation {} has a non-string `path`", idx)))?;
+            let from = || {
+                field("from")?
+                    .as_str()
+                    .ok_or_else(|| err(format!("operation {} has a non-string `from`", idx)))
+            };
+
+            let result = match field("op")?.as_str() {
+                Some("add") => add(&mut doc, path, field("value")?.clone()),
+                Some("remove") => remove(&mut doc, path).map(drop),
+                Some("replace") => remove(&mut doc, path)
+                    .and_then(|_| add(&mut doc, path, field("value")?.clone())),
+                Some("move") => {
+                    let from = from()?;
+                    remove(&mut doc, from).and_then(|val| add(&mut doc, path, val))
+                }
+                Some("copy") => {
+                    let val = get(&doc, from()?)?.clone();
+                    add(&mut doc, path, val)
+                }
+                Some("test") => {
+                    if get(&doc, path)? == field("value")? {
+                        Ok(())
+                    } else {
+                        Err(err(format!("test of {} failed", path)))
+                    }
+                }
+                _ => Err(err(format!("operation {} has an unknown `op`", idx))),
+            };
+
+            result.map_err(|inner| match inner {
+                Error::InvalidPatch(reason) => err(format!("operation {}: {}", idx, reason)),
+                other => other,
+            })?;
+        }
+
+        serde_json::from_value(doc).map_err(|err_| err(err_.to_string()))
+    }
+}
+
+fn diff_values(old: &Value, new: &Value, segments: &mut Vec<String>, changes: &mut Vec<Change>) {
+    match (old, new) {
+        (Value::Object(old_obj), Value::Object(new_obj)) => {
+            for (key, old_val) in old_obj {
+                segments.push(key.clone());
+
+                match new_obj.get(key) {
+                    Some(new_val) => diff_values(old_val, new_val, segments, changes),
+                    None => changes.push(Change::Removed {
+                        location: pointer(&*segments),
+                        old: old_val.clone(),
+                    }),
+                }
+
+                segments.pop();
+            }
+
+            for (key, new_val) in new_obj {
+                if !old_obj.contains_key(key) {
+                    segments.push(key.clone());
+                    changes.push(Change::Added {
+                        location: pointer(&*segments),
+                        value: new_val.clone(),
+                    });
+                    segments.pop();
+                }
+            }
+        }
+
+        (Value::Array(old_items), Value::Array(new_items))
+            if old_items.len() == new_items.len() =>
+        {
+            for (idx, (old_item, new_item)) in old_items.iter().zip(new_items).enumerate() {
+                segments.push(idx.to_string());
+                diff_values(old_item, new_item, segments, changes);
+                segments.pop();
+            }
+        }
+
+        (old, new) if old != new => changes.push(Change::Modified {
+            location: pointer(&*segments),
+            old: old.clone(),
+            new: new.clone(),
+        }),
+
+        _ => {}
+    }
+}
+
+/// Checks that `path` is an RFC 6901 JSON pointer, i.e. empty or starting with `/`.
+fn check_pointer(path: &str) -> Result<(), Error> {
+    if path.is_empty() || path.starts_with('/') {
+        Ok(())
+    } else {
+        Err(Error::InvalidPatch(format!("invalid pointer {}", path)))
+    }
+}
+
+fn get<'a>(doc: &'a Value, path: &str) -> Result<&'a Value, Error> {
+    check_pointer(path)?;
+
+    doc.pointer(path)
+        .ok_or_else(|| Error::InvalidPatch(format!("{} does not exist", path)))
+}
+
+/// Returns the parent of the value at `path` and the last token of `path`.
+fn parent_mut<'a>(doc: &'a mut Value, path: &str) -> Result<(&'a mut Value, String), Error> {
+    check_pointer(path)?;
+
+    let idx = path
+        .rfind('/')
+        .ok_or_else(|| Error::InvalidPatch("the document root has no parent".to_owned()))?;
+    let (parent_path, last) = (&path[..idx], unescape(&path[idx + 1..]));
+
+    let parent = doc
+        .pointer_mut(parent_path)
+        .ok_or_else(|| Error::InvalidPatch(format!("parent of {} does not exist", path)))?;
+
+    Ok((parent, last))
+}
+
+fn index(token: &str, len: usize, path: &str) -> Result<usize, Error> {
+    match token.parse::<usize>() {
+        Ok(idx) if idx <= len && (token == "0" || !token.starts_with('0')) => Ok(idx),
+        _ => Err(Error::InvalidPatch(format!(
+            "invalid array index in {}",
+            path
+        ))),
+    }
+}
+
+fn add(doc: &mut Value, path: &str, val: Value) -> Result<(), Error> {
+    if path.is_empty() {
+        *doc = val;
+        return Ok(());
+    }
+
+    match parent_mut(doc, path)? {
+        (Value::Object(obj), key) => {
+            obj.insert(key, val);
+        }
+        (Value::Array(items), token) if token == "-" => items.push(val),
+        (Value::Array(items), token) => {
+            let idx = index(&token, items.len(), path)?;
+            items.insert(idx, val);
+        }
+        _ => {
+            return Err(Error::InvalidPatch(format!(
+                "parent of {} is not a container",
+                path
+            )))
+        }
+    }
+
+    Ok(())
+}
+
+fn remove(doc: &mut Value, path: &str) -> Result<Value, Error> {
+    if path.is_empty() {
+        return Ok(std::mem::take(doc));
+    }
+
+    let missing = || Error::InvalidPatch(format!("{} does not exist", path));
+
+    match parent_mut(doc, path)? {
+        (Value::Object(obj), key) => obj.remove(&key).ok_or_else(missing),
+        (Value::Array(items), token) => {
+            let idx = index(&token, items.len(), path)?;
+
+            if idx < items.len() {
+                Ok(items.remove(idx))
+            } else {
+                Err(missing())
+            }
+        }
+        _ => Err(missing()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_round_trip() {
+        let old = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+servers:
+  - url: https://a.example.com
+paths:
+  /pets:
+    get:
+      tags: [pets]
+      responses: {}
+  /pets/{id}:
+    delete: { responses: {} }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let new = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "2", description: Pets API. }
+servers:
+  - url: https://a.example.com
+  - url: https://b.example.com
+paths:
+  /pets:
+    get:
+      tags: [animals]
+      responses: {}
+  /owners:
+    get: { responses: {} }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let diff = old.diff(&new);
+        assert!(diff.changes.contains(&Change::Removed {
+            location: "#/paths/~1pets~1{id}".to_owned(),
+            old: json!({ "delete": { "responses": {} } }),
+        }));
+        assert!(diff.changes.contains(&Change::Modified {
+            location: "#/paths/~1pets/get/tags/0".to_owned(),
+            old: json!("pets"),
+            new: json!("animals"),
+        }));
+
+        let patch = diff.to_json_patch();
+        assert_eq!(old.apply_json_patch(&patch).unwrap(), new);
+
+        assert!(new.diff(&new).is_empty());
+        assert!(new
+            .diff(&new)
+            .to_json_patch()
+            .as_array()
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn apply_json_patch() {
+        let spec = Spec::minimal_valid("Test", "1");
+
+        let patched = spec
+            .apply_json_patch(&json!([
+                { "op": "test", "path": "/info/version", "value": "1" },
+                { "op": "add", "path": "/tags", "value": [{ "name": "a" }] },
+                { "op": "add", "path": "/tags/0", "value": { "name": "b" } },
+                { "op": "add", "path": "/tags/-", "value": { "name": "c" } },
+                { "op": "copy", "from": "/info/title", "path": "/info/summary" },
+                { "op": "move", "from": "/tags/2", "path": "/tags/0" },
+                { "op": "replace", "path": "/info/version", "value": "2" },
+                { "op": "remove", "path": "/tags/1" },
+            ]))
+            .unwrap();
+
+        let tags = patched.tags.iter().map(|tag| &tag.name).collect::<Vec<_>>();
+        assert_eq!(tags, ["c", "a"]);
+        assert_eq!(patched.info.version, "2");
+        assert_eq!(patched.info.summary.as_deref(), Some("Test"));
+
+        for patch in [
+            json!({ "op": "add" }),
+            json!([{ "op": "test", "path": "/info/version", "value": "2" }]),
+            json!([{ "op": "remove", "path": "/tags" }]),
+            json!([{ "op": "add", "path": "/info/title/x", "value": 1 }]),
+            json!([{ "op": "replace", "path": "/info", "value": 1 }]),
+        ] {
+            assert!(matches!(
+                spec.apply_json_patch(&patch),
+                Err(Error::InvalidPatch(_))
+            ));
+        }
+    }
+}
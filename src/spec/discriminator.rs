@@ -0,0 +1,173 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Error, FromRef, ObjectOrReference, Schema, Spec, COMPONENT_SCHEMAS_REF_PREFIX};
+use crate::json_pointer;
+
+/// When request bodies or response payloads may be one of a number of different schemas, a
+/// discriminator object can be used to aid in serialization, deserialization, and validation.
+///
+/// See <https://github.com/OAI/OpenAPI-Specification/blob/HEAD/versions/3.1.0.md#discriminatorObject>.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
+pub struct Discriminator {
+    /// The name of the property in the payload that will hold the discriminator value.
+    #[serde(rename = "propertyName")]
+    pub property_name: String,
+
+    /// An object to hold mappings between payload values and schema names or references.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub mapping: BTreeMap<String, String>,
+    // TODO: Add "Specification Extensions" https://github.com/OAI/OpenAPI-Specification/blob/HEAD/versions/3.1.0.md#specificationExtensions}
+}
+
+impl Schema {
+    /// Returns the `(discriminator value, schema name)` pairs of this schema's discriminator,
+    /// ordered by discriminator value.
+    ///
+    /// Variants are the component schemas referenced by `oneOf`, or `anyOf` if there is no
+    /// `oneOf`. Explicit `mapping` entries come first; each member schema not targeted by the
+    /// mapping is then implicitly identified by its name. Schemas without a discriminator have no
+    /// variants.
+    ///
+    /// Returns [`Error::InvalidDiscriminatorMapping`] if a mapping value does not refer to one of
+    /// the members, [`Error::DuplicateDiscriminatorValue`] if a member's implicit value is already
+    /// mapped to another member, or an [`Error::Ref`] if a member can not be resolved.
+    pub fn discriminator_variants(&self, spec: &Spec) -> Result<Vec<(String, String)>, Error> {
+        let discriminator = match self.discriminator {
+            Some(ref discriminator) => discriminator,
+            None => return Ok(vec![]),
+        };
+
+        let members = if self.one_of.is_empty() {
+            &self.any_of
+        } else {
+            &self.one_of
+        };
+
+        let mut names = vec![];
+
+        for member in members {
+            if let ObjectOrReference::Ref { ref_path, .. } = member {
+                if let Some(name) = ref_path.strip_prefix(COMPONENT_SCHEMAS_REF_PREFIX) {
+                    Schema::from_ref(spec, ref_path)?;
+                    names.push(json_pointer::unescape(name));
+                }
+            }
+        }
+
+        let mut variants = BTreeMap::new();
+
+        for (value, target) in &discriminator.mapping {
+            // mapping values may be bare schema names instead of references
+            let name = match target.strip_prefix(COMPONENT_SCHEMAS_REF_PREFIX) {
+                Some(name) => json_pointer::unescape(name),
+                None if !target.contains('/') && !target.starts_with('#') => target.clone(),
+                None => String::new(),
+            };
+
+            if !names.contains(&name) {
+                return Err(Error::InvalidDiscriminatorMapping {
+                    value: value.clone(),
+                    target: target.clone(),
+                });
+            }
+
+            variants.insert(value.clone(), name);
+        }
+
+        for name in names {
+            if variants.values().any(|mapped| *mapped == name) {
+                continue;
+            }
+
+            if let Some(target) = variants.get(&name) {
+                return Err(Error::DuplicateDiscriminatorValue {
+                    value: name,
+                    target: target.clone(),
+                });
+            }
+
+            variants.insert(name.clone(), name);
+        }
+
+        Ok(variants.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn discriminator_variants() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths: {}
+components:
+  schemas:
+    Pet:
+      oneOf:
+        - $ref: "#/components/schemas/Dog"
+        - $ref: "#/components/schemas/Cat"
+        - $ref: "#/components/schemas/Lizard"
+      discriminator:
+        propertyName: kind
+        mapping:
+          dog: "#/components/schemas/Dog"
+          cat: Cat
+    Broken:
+      oneOf:
+        - $ref: "#/components/schemas/Dog"
+      discriminator:
+        propertyName: kind
+        mapping:
+          cat: "#/components/schemas/Cat"
+    Clash:
+      oneOf:
+        - $ref: "#/components/schemas/Dog"
+        - $ref: "#/components/schemas/Cat"
+      discriminator:
+        propertyName: kind
+        mapping:
+          Cat: Dog
+    Dog: { type: object }
+    Cat: { type: object }
+    Lizard: { type: object }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+        let schemas = &spec.components.as_ref().unwrap().schemas;
+
+        let pet = schemas["Pet"].resolve(&spec).unwrap();
+        assert_eq!(
+            pet.discriminator_variants(&spec).unwrap(),
+            [
+                ("Lizard".to_owned(), "Lizard".to_owned()),
+                ("cat".to_owned(), "Cat".to_owned()),
+                ("dog".to_owned(), "Dog".to_owned()),
+            ]
+        );
+
+        let broken = schemas["Broken"].resolve(&spec).unwrap();
+        assert!(matches!(
+            broken.discriminator_variants(&spec),
+            Err(Error::InvalidDiscriminatorMapping { .. })
+        ));
+
+        let clash = schemas["Clash"].resolve(&spec).unwrap();
+        match clash.discriminator_variants(&spec) {
+            Err(Error::DuplicateDiscriminatorValue { value, target }) => {
+                assert_eq!((value.as_str(), target.as_str()), ("Cat", "Dog"));
+            }
+            res => panic!("unexpected result: {:?}", res),
+        }
+
+        let dog = schemas["Dog"].resolve(&spec).unwrap();
+        assert!(dog.discriminator_variants(&spec).unwrap().is_empty());
+    }
+}
@@ -1,7 +1,11 @@
 use derive_more::{Display, Error, From};
 use semver::{Error as SemVerError, Version};
 
-use crate::spec::{r#ref::RefError, schema::Error as SchemaError};
+use crate::spec::{
+    r#ref::{DanglingRef, RefError},
+    schema::Error as SchemaError,
+    OpenApiVersion,
+};
 
 /// Spec Errors
 #[derive(Debug, Display, Error, From)]
@@ -17,4 +21,105 @@ pub enum Error {
 
     #[display(fmt = "Unsupported spec file version ({})", _0)]
     UnsupportedSpecFileVersion(#[error(not(source))] Version),
+
+    #[display(fmt = "{} unresolvable reference(s)", "_0.len()")]
+    DanglingRefs(#[error(not(source))] Vec<DanglingRef>),
+
+    #[display(fmt = "Invalid response key: {}", _0)]
+    #[from(ignore)]
+    InvalidResponseKey(#[error(not(source))] String),
+
+    #[display(fmt = "Unsupported JSON Schema dialect: {}", _0)]
+    #[from(ignore)]
+    UnsupportedDialect(#[error(not(source))] String),
+
+    #[display(fmt = "Invalid callback")]
+    #[from(ignore)]
+    InvalidCallback(serde_json::Error),
+
+    #[display(fmt = "Could not resolve external reference {}: {}", reference, reason)]
+    #[from(ignore)]
+    ExternalRef { reference: String, reason: String },
+
+    #[display(fmt = "Invalid bundled document")]
+    #[from(ignore)]
+    InvalidBundle(serde_json::Error),
+
+    #[display(fmt = "Invalid spec document")]
+    #[from(ignore)]
+    InvalidDocument(serde_json::Error),
+
+    #[display(fmt = "Invalid JSON Patch: {}", _0)]
+    #[from(ignore)]
+    InvalidPatch(#[error(not(source))] String),
+
+    #[display(fmt = "Component already exists: {}", _0)]
+    #[from(ignore)]
+    ComponentExists(#[error(not(source))] String),
+
+    #[display(fmt = "Path not found: {}", _0)]
+    PathNotFound(#[error(not(source))] String),
+
+    #[display(
+        fmt = "Discriminator value {} maps to {}, which is not a member schema",
+        value,
+        target
+    )]
+    #[from(ignore)]
+    InvalidDiscriminatorMapping { value: String, target: String },
+
+    #[display(
+        fmt = "Discriminator value {} maps to {}, so member schema {} has no value",
+        value,
+        target,
+        value
+    )]
+    #[from(ignore)]
+    DuplicateDiscriminatorValue { value: String, target: String },
+
+    #[display(fmt = "Invalid path {}: {}", path, reason)]
+    #[from(ignore)]
+    InvalidPath { path: String, reason: String },
+
+    #[display(fmt = "Path already exists: {}", _0)]
+    #[from(ignore)]
+    PathExists(#[error(not(source))] String),
+
+    #[display(fmt = "Paths {} and {} have different template parameters", old, new)]
+    #[from(ignore)]
+    PathTemplateMismatch { old: String, new: String },
+
+    #[display(fmt = "{} can not be represented in OpenAPI {}", _1, _0)]
+    Unconvertible(OpenApiVersion, #[error(not(source))] String),
+
+    #[display(fmt = "Reference cycle: {}", "_0.join(\" -> \")")]
+    ReferenceCycle(#[error(not(source))] Vec<String>),
+
+    #[display(fmt = "`allOf` inheritance cycle: {}", "_0.join(\" -> \")")]
+    #[from(ignore)]
+    AllOfCycle(#[error(not(source))] Vec<String>),
+
+    #[display(fmt = "Merged `allOf` members do not form a valid schema")]
+    #[from(ignore)]
+    InvalidMergedSchema(serde_json::Error),
+
+    #[display(fmt = "Conflicting `{}` in {} and {}", keyword, first, second)]
+    AllOfConflict {
+        keyword: String,
+        first: String,
+        second: String,
+    },
+
+    #[display(
+        fmt = "Paths {} and {} both normalize to {}",
+        first,
+        second,
+        normalized
+    )]
+    #[from(ignore)]
+    PathCollision {
+        first: String,
+        second: String,
+        normalized: String,
+    },
 }
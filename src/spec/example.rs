@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use super::{FromRef, Ref, RefError, RefType, Spec};
+use super::{is_json_media_type, FromRef, Ref, RefError, RefType, Spec};
 
 /// Multi-purpose example objects.
 ///
@@ -41,6 +41,19 @@ impl Example {
             None => vec![],
         }
     }
+
+    /// Returns the example serialized as a body of the given media type.
+    ///
+    /// Non-JSON media types use string values as-is, since that is how examples that can not
+    /// be represented in JSON, such as XML documents, are embedded.
+    pub fn body_bytes(&self, media_type: &str) -> Vec<u8> {
+        match self.value {
+            Some(serde_json::Value::String(ref val)) if !is_json_media_type(media_type) => {
+                val.as_bytes().to_owned()
+            }
+            _ => self.as_bytes(),
+        }
+    }
 }
 
 impl FromRef for Example {
@@ -59,3 +72,29 @@ impl FromRef for Example {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn body_bytes() {
+        let example = Example {
+            value: Some(serde_json::Value::String("<pet>Rex</pet>".to_owned())),
+            ..Example::default()
+        };
+
+        assert_eq!(example.body_bytes("application/xml"), b"<pet>Rex</pet>");
+        assert_eq!(
+            example.body_bytes("application/json"),
+            br#""<pet>Rex</pet>""#
+        );
+
+        let example = Example {
+            value: Some(serde_json::json!({ "name": "Rex" })),
+            ..Example::default()
+        };
+
+        assert_eq!(example.body_bytes("text/plain"), br#"{"name":"Rex"}"#);
+    }
+}
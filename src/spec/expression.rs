@@ -0,0 +1,156 @@
+//! Syntax of [runtime expressions], as used by callback keys and links.
+//!
+//! [runtime expressions]: https://github.com/OAI/OpenAPI-Specification/blob/HEAD/versions/3.1.0.md#runtimeExpression
+
+/// Returns true if `expr` is a syntactically valid runtime expression, e.g. `$statusCode` or
+/// `$request.body#/url`.
+pub fn is_runtime_expression(expr: &str) -> bool {
+    match expr {
+        "$url" | "$method" | "$statusCode" => return true,
+        _ => {}
+    }
+
+    let source = match expr
+        .strip_prefix("$request.")
+        .or_else(|| expr.strip_prefix("$response."))
+    {
+        Some(source) => source,
+        None => return false,
+    };
+
+    if let Some(token) = source.strip_prefix("header.") {
+        return !token.is_empty() && token.chars().all(is_tchar);
+    }
+
+    if let Some(name) = source
+        .strip_prefix("query.")
+        .or_else(|| source.strip_prefix("path."))
+    {
+        return !name.is_empty();
+    }
+
+    match source.strip_prefix("body") {
+        Some("") => true,
+        Some(pointer) => match pointer.strip_prefix('#') {
+            Some(pointer) => is_json_pointer(pointer),
+            None => false,
+        },
+        None => false,
+    }
+}
+
+/// Returns the invalid runtime expressions embedded in a callback URL template, such as
+/// `http://example.com?id={$request.body#/id}`.
+///
+/// A template consisting of a bare expression, starting with `$`, is checked as a whole. Brace
+/// delimited parts that are unterminated or nested are returned as-is.
+pub(crate) fn invalid_template_expressions(template: &str) -> Vec<&str> {
+    if template.starts_with('$') {
+        return if is_runtime_expression(template) {
+            vec![]
+        } else {
+            vec![template]
+        };
+    }
+
+    let mut invalid = vec![];
+    let mut rest = template;
+
+    while let Some(start) = rest.find(['{', '}']) {
+        if rest[start..].starts_with('}') {
+            invalid.push(&rest[start..]);
+            break;
+        }
+
+        let expr_start = &rest[start + 1..];
+
+        match expr_start.find(['{', '}']) {
+            Some(end) if expr_start[end..].starts_with('}') => {
+                let expr = &expr_start[..end];
+
+                if !is_runtime_expression(expr) {
+                    invalid.push(expr);
+                }
+
+                rest = &expr_start[end + 1..];
+            }
+            _ => {
+                invalid.push(&rest[start..]);
+                break;
+            }
+        }
+    }
+
+    invalid
+}
+
+/// `tchar` of RFC 7230, the characters allowed in header names.
+fn is_tchar(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(ch)
+}
+
+fn is_json_pointer(pointer: &str) -> bool {
+    if pointer.is_empty() {
+        return true;
+    }
+
+    match pointer.strip_prefix('/') {
+        Some(tokens) => tokens
+            .split('~')
+            .skip(1)
+            .all(|escaped| escaped.starts_with(['0', '1'])),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runtime_expressions() {
+        for expr in [
+            "$url",
+            "$statusCode",
+            "$request.header.X-Callback-Url",
+            "$request.query.queryUrl",
+            "$request.path.id",
+            "$request.body",
+            "$response.body#/links/0/a~1b",
+        ] {
+            assert!(is_runtime_expression(expr), "{}", expr);
+        }
+
+        for expr in [
+            "$status",
+            "$request",
+            "$request.header.X Url",
+            "$request.query.",
+            "$response.body#links",
+            "$response.body#/a~2",
+            "$request.cookie.id",
+        ] {
+            assert!(!is_runtime_expression(expr), "{}", expr);
+        }
+    }
+
+    #[test]
+    fn template_expressions() {
+        assert!(invalid_template_expressions("{$request.body#/callbackUrl}").is_empty());
+        assert!(invalid_template_expressions(
+            "http://example.com?id={$request.body#/id}&email={$request.body#/email}"
+        )
+        .is_empty());
+        assert!(invalid_template_expressions("$request.query.url").is_empty());
+
+        assert_eq!(
+            invalid_template_expressions("http://example.com/{$request.id}/{$url}"),
+            ["$request.id"]
+        );
+        assert_eq!(
+            invalid_template_expressions("http://example.com/{$url"),
+            ["{$url"]
+        );
+        assert_eq!(invalid_template_expressions("$req.body"), ["$req.body"]);
+    }
+}
@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 use url::Url;
 
+use super::Spec;
+use crate::json_pointer::pointer;
+
 /// Allows referencing an external resource for extended documentation.
 ///
 /// See <https://github.com/OAI/OpenAPI-Specification/blob/HEAD/versions/3.1.0.md#externalDocumentationObject>.
@@ -15,3 +18,79 @@ pub struct ExternalDoc {
     pub description: Option<String>,
     // TODO: Add "Specification Extensions" https://github.com/OAI/OpenAPI-Specification/blob/HEAD/versions/3.1.0.md#specificationExtensions}
 }
+
+impl Spec {
+    /// Returns the documentation URLs of the spec, along with JSON pointers to their locations.
+    ///
+    /// Covers `info.termsOfService` and the `externalDocs` of the spec and of each operation.
+    pub fn external_docs_urls(&self) -> Vec<(String, &Url)> {
+        let mut urls = vec![];
+
+        if let Some(ref url) = self.info.terms_of_service {
+            urls.push((pointer(["info", "termsOfService"]), url));
+        }
+
+        if let Some(ref docs) = self.external_docs {
+            urls.push((pointer(["externalDocs", "url"]), &docs.url));
+        }
+
+        for (section, path_items) in [("paths", &self.paths), ("webhooks", &self.webhooks)] {
+            for (path, path_item) in path_items {
+                for (method, op) in path_item.methods() {
+                    if let Some(ref docs) = op.external_docs {
+                        let method = method.as_str().to_ascii_lowercase();
+                        let location = pointer([section, path, &method, "externalDocs", "url"]);
+                        urls.push((location, &docs.url));
+                    }
+                }
+            }
+        }
+
+        urls
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn external_docs_urls() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info:
+  title: Test
+  version: "1"
+  termsOfService: https://example.com/terms
+externalDocs: { url: https://example.com/docs }
+paths:
+  /pets:
+    get:
+      externalDocs: { url: https://example.com/docs/pets }
+      responses: {}
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let urls = spec
+            .external_docs_urls()
+            .into_iter()
+            .map(|(location, url)| (location, url.as_str()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            urls,
+            [
+                (
+                    "#/info/termsOfService".to_owned(),
+                    "https://example.com/terms"
+                ),
+                ("#/externalDocs/url".to_owned(), "https://example.com/docs"),
+                (
+                    "#/paths/~1pets/get/externalDocs/url".to_owned(),
+                    "https://example.com/docs/pets"
+                ),
+            ]
+        );
+    }
+}
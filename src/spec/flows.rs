@@ -3,6 +3,8 @@ use std::collections::BTreeMap;
 use serde::{Deserialize, Serialize};
 use url::Url;
 
+use super::{r#ref, Error, FromRef, ObjectOrReference, PathItem, Ref, RefError, RefType, Spec};
+
 /// Allows configuration of the supported OAuth Flows.
 ///
 /// See <https://github.com/OAI/OpenAPI-Specification/blob/HEAD/versions/3.1.0.md#oauth-flows-object>.
@@ -90,9 +92,86 @@ pub struct AuthorizationCodeFlow {
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
 pub struct Callback(
     /// A Path Item Object used to define a callback request and expected responses.
-    serde_json::Value, // TODO: Add "Specification Extensions" https://github.com/OAI/OpenAPI-Specification/blob/HEAD/versions/3.1.0.md#specificationExtensions}
+    pub(crate) serde_json::Value, // TODO: Add "Specification Extensions" https://github.com/OAI/OpenAPI-Specification/blob/HEAD/versions/3.1.0.md#specificationExtensions}
 );
 
+impl Callback {
+    /// Returns true if this callback is a `$ref` to another callback.
+    pub fn is_ref(&self) -> bool {
+        self.0.get("$ref").is_some()
+    }
+
+    /// Returns the path items of this callback, keyed by their runtime expression.
+    ///
+    /// A callback that is a `$ref` is resolved first, and referenced path items are resolved
+    /// with [`PathItem::resolve`]. Specification extensions are skipped. Returns
+    /// [`Error::ReferenceCycle`] if the callback references lead back to a callback already
+    /// followed.
+    pub fn path_items(&self, spec: &Spec) -> Result<BTreeMap<String, PathItem>, Error> {
+        let mut followed = vec![];
+        let mut callback = self;
+
+        while let Some(ref_path) = callback.0.get("$ref").and_then(|val| val.as_str()) {
+            callback = Callback::follow(spec, ref_path, &mut followed)?;
+        }
+
+        let expressions = BTreeMap::<String, serde_json::Value>::deserialize(&callback.0)
+            .map_err(Error::InvalidCallback)?;
+
+        expressions
+            .into_iter()
+            .filter(|(expr, _)| !expr.starts_with("x-"))
+            .map(|(expr, val)| {
+                let path_item = PathItem::deserialize(val).map_err(Error::InvalidCallback)?;
+                Ok((expr, path_item.resolve(spec)?))
+            })
+            .collect()
+    }
+
+    /// Returns the callback component at `ref_path`, following references between components.
+    fn follow<'a>(
+        spec: &'a Spec,
+        ref_path: &str,
+        followed: &mut Vec<String>,
+    ) -> Result<&'a Callback, Error> {
+        r#ref::follow_ref(followed, ref_path).map_err(Error::ReferenceCycle)?;
+
+        match Callback::component(spec, ref_path)? {
+            ObjectOrReference::Object(callback) => Ok(callback),
+            ObjectOrReference::Ref { ref_path, .. } => Callback::follow(spec, ref_path, followed),
+        }
+    }
+
+    fn component<'a>(
+        spec: &'a Spec,
+        path: &str,
+    ) -> Result<&'a ObjectOrReference<Callback>, RefError> {
+        let refpath = path.parse::<Ref>()?;
+
+        match refpath.kind {
+            RefType::Callback => spec
+                .components
+                .as_ref()
+                .and_then(|cs| cs.callbacks.get(&refpath.name))
+                .ok_or_else(|| RefError::Unresolvable(path.to_owned())),
+
+            typ => Err(RefError::MismatchedType(typ, RefType::Callback)),
+        }
+    }
+}
+
+impl FromRef for Callback {
+    /// Looks up a callback component, following references between components. A cycle of such
+    /// references is unresolvable.
+    fn from_ref(spec: &Spec, path: &str) -> Result<Self, RefError> {
+        match Callback::follow(spec, path, &mut vec![]) {
+            Ok(callback) => Ok(callback.clone()),
+            Err(Error::Ref(err)) => Err(err),
+            Err(_) => Err(RefError::Unresolvable(path.to_owned())),
+        }
+    }
+}
+
 // FIXME: Implement
 // /// Allows configuration of the supported OAuth Flows.
 // /// https://github.com/OAI/OpenAPI-Specification/blob/HEAD/versions/3.1.0.md#oauthFlowsObject
@@ -0,0 +1,448 @@
+//! Dependency graph of the components of a specification.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::{r#ref, Error, ObjectOrReference, Schema, Spec, COMPONENT_SCHEMAS_REF_PREFIX};
+
+const COMPONENTS_REF_PREFIX: &str = "#/components/";
+
+/// Graph of `$ref` dependencies between the components of a spec.
+///
+/// Nodes are components, identified by their local reference path, e.g.
+/// `#/components/schemas/Pet`. An edge from `a` to `b` means that `a` contains a reference to
+/// (part of) `b`. References to anything other than components, such as external files or
+/// paths, are not included.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ComponentGraph {
+    dependencies: BTreeMap<String, BTreeSet<String>>,
+    dependents: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl ComponentGraph {
+    /// Returns all components, in reference path order.
+    pub fn nodes(&self) -> impl Iterator<Item = &str> {
+        self.dependencies.keys().map(String::as_str)
+    }
+
+    /// Returns the components that `node` references directly.
+    pub fn dependencies(&self, node: &str) -> impl Iterator<Item = &str> {
+        Self::neighbours(&self.dependencies, node)
+    }
+
+    /// Returns the components that reference `node` directly.
+    pub fn dependents(&self, node: &str) -> impl Iterator<Item = &str> {
+        Self::neighbours(&self.dependents, node)
+    }
+
+    /// Returns the components that reference `node` directly or indirectly, excluding `node`
+    /// itself unless it is part of a cycle.
+    ///
+    /// These are the components affected by a change to `node`.
+    pub fn transitive_dependents(&self, node: &str) -> BTreeSet<&str> {
+        let mut found = BTreeSet::new();
+        let mut queue = self.dependents(node).collect::<Vec<_>>();
+
+        while let Some(next) = queue.pop() {
+            if found.insert(next) {
+                queue.extend(self.dependents(next));
+            }
+        }
+
+        found
+    }
+
+    /// Returns all components ordered so that each comes after the components it references.
+    ///
+    /// Reversing the order gives a safe deletion order, where no component is removed while it
+    /// is still referenced. Returns [`Error::ReferenceCycle`] if the components can not be
+    /// ordered.
+    pub fn topological_order(&self) -> Result<Vec<&str>, Error> {
+        let mut remaining = self
+            .dependencies
+            .iter()
+            .map(|(node, deps)| (node.as_str(), deps.len()))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut ready = remaining
+            .iter()
+            .filter(|(_, count)| **count == 0)
+            .map(|(node, _)| *node)
+            .collect::<Vec<_>>();
+
+        let mut order = vec![];
+
+        // nodes become ready in reverse order so they are popped in reference path order
+        ready.reverse();
+
+        while let Some(node) = ready.pop() {
+            remaining.remove(node);
+            order.push(node);
+
+            let mut unblocked = vec![];
+
+            for dependent in self.dependents(node) {
+                if let Some(count) = remaining.get_mut(dependent) {
+                    *count -= 1;
+
+                    if *count == 0 {
+                        unblocked.push(dependent);
+                    }
+                }
+            }
+
+            ready.extend(unblocked.into_iter().rev());
+        }
+
+        if remaining.is_empty() {
+            return Ok(order);
+        }
+
+        let cycle = r#ref::find_cycle(&self.dependencies)
+            .expect("unordered components must be part of or depend on a cycle");
+
+        Err(Error::ReferenceCycle(cycle))
+    }
+
+    fn neighbours<'a>(
+        edges: &'a BTreeMap<String, BTreeSet<String>>,
+        node: &str,
+    ) -> impl Iterator<Item = &'a str> {
+        edges.get(node).into_iter().flatten().map(String::as_str)
+    }
+}
+
+impl Spec {
+    /// Builds the graph of `$ref` dependencies between this spec's components.
+    ///
+    /// Every component is a node, even if it neither references nor is referenced by others.
+    pub fn component_dependency_graph(&self) -> ComponentGraph {
+        let doc = serde_json::to_value(self).unwrap();
+        let mut graph = ComponentGraph::default();
+
+        if let Some(sections) = doc.get("components").and_then(|val| val.as_object()) {
+            for (section, components) in sections {
+                for name in components
+                    .as_object()
+                    .into_iter()
+                    .flat_map(|obj| obj.keys())
+                {
+                    let node = format!(
+                        "{}{}/{}",
+                        COMPONENTS_REF_PREFIX,
+                        section,
+                        crate::json_pointer::escape(name)
+                    );
+                    graph.dependencies.entry(node).or_default();
+                }
+            }
+        }
+
+        for (location, ref_path) in r#ref::find_refs(&doc) {
+            if !location.starts_with(COMPONENTS_REF_PREFIX) {
+                continue;
+            }
+
+            if let (Some(from), Some(to)) = (
+                r#ref::containing_node(&location),
+                r#ref::containing_node(&ref_path),
+            ) {
+                if !to.starts_with(COMPONENTS_REF_PREFIX) {
+                    continue;
+                }
+
+                graph
+                    .dependencies
+                    .entry(from.clone())
+                    .or_default()
+                    .insert(to.clone());
+                graph.dependencies.entry(to.clone()).or_default();
+                graph.dependents.entry(to).or_default().insert(from);
+            }
+        }
+
+        graph
+    }
+}
+
+/// Component schemas a schema depends on, in the order code for them should be generated.
+///
+/// Returned by [`Schema::generation_dependencies`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GenerationDependencies {
+    /// Names of the component schemas referenced directly or indirectly, each after the
+    /// schemas it references, except where that would require following a cycle.
+    pub order: Vec<String>,
+
+    /// References that were ignored to break cycles, as pairs of the referencing and referenced
+    /// component schema names.
+    ///
+    /// Generators typically need to introduce indirection, such as boxing, for these.
+    pub cyclic_refs: Vec<(String, String)>,
+}
+
+impl Schema {
+    /// Returns the component schemas this schema depends on, in dependency order.
+    ///
+    /// Dependencies are found as by [`Schema::referenced_components`], through `$ref`s and
+    /// discriminator mapping values, directly in this schema or in the component schemas it
+    /// references. The order is a topological order of the dependency graph where it
+    /// is acyclic. Cycles are broken deterministically by visiting references in name order and
+    /// ignoring the reference that closes each cycle; these are reported in
+    /// [`GenerationDependencies::cyclic_refs`]. References to other kinds of components are
+    /// not included.
+    pub fn generation_dependencies(&self, spec: &Spec) -> GenerationDependencies {
+        struct Visit<'a> {
+            spec: &'a Spec,
+            visiting: BTreeSet<String>,
+            deps: GenerationDependencies,
+        }
+
+        fn schema_name(node: &str) -> Option<String> {
+            node.strip_prefix(COMPONENT_SCHEMAS_REF_PREFIX)
+                .map(crate::json_pointer::unescape)
+        }
+
+        /// Returns the component schemas referenced by `refs`, in reference path order.
+        fn schema_nodes(refs: Vec<String>) -> BTreeSet<String> {
+            refs.iter()
+                .filter_map(|ref_path| r#ref::containing_node(ref_path))
+                .filter(|node| node.starts_with(COMPONENT_SCHEMAS_REF_PREFIX))
+                .collect()
+        }
+
+        impl Visit<'_> {
+            fn visit(&mut self, node: &str) {
+                let name = match schema_name(node) {
+                    Some(name) => name,
+                    None => return,
+                };
+
+                if self.deps.order.contains(&name) {
+                    return;
+                }
+
+                self.visiting.insert(name.clone());
+
+                let refs = match self
+                    .spec
+                    .components
+                    .as_ref()
+                    .and_then(|components| components.schemas.get(&name))
+                {
+                    Some(ObjectOrReference::Ref { ref_path, .. }) => vec![ref_path.clone()],
+                    Some(ObjectOrReference::Object(schema)) => schema.dependency_refs(),
+                    None => vec![],
+                };
+
+                for dep in schema_nodes(refs) {
+                    match schema_name(&dep) {
+                        Some(dep_name) if self.visiting.contains(&dep_name) => {
+                            self.deps.cyclic_refs.push((name.clone(), dep_name))
+                        }
+                        Some(_) => self.visit(&dep),
+                        None => {}
+                    }
+                }
+
+                self.visiting.remove(&name);
+                self.deps.order.push(name);
+            }
+        }
+
+        let mut visit = Visit {
+            spec,
+            visiting: BTreeSet::new(),
+            deps: GenerationDependencies::default(),
+        };
+
+        for root in schema_nodes(self.dependency_refs()) {
+            visit.visit(&root);
+        }
+
+        visit.deps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(components: &str) -> Spec {
+        crate::from_reader(
+            format!(
+                "openapi: 3.1.0\ninfo: {{ title: Test, version: \"1\" }}\npaths: {{}}\ncomponents:\n{}",
+                components
+            )
+            .as_bytes(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn dependency_graph() {
+        let spec = spec(
+            r##"
+  schemas:
+    Pet:
+      properties:
+        owner: { $ref: "#/components/schemas/Person" }
+        tag: { $ref: "#/components/schemas/Tag" }
+    Person: { properties: { tag: { $ref: "#/components/schemas/Tag" } } }
+    Tag: { type: string }
+    Unused: { type: integer }
+  responses:
+    PetResponse:
+      description: pet
+      content:
+        application/json:
+          schema: { $ref: "#/components/schemas/Pet" }
+"##,
+        );
+
+        let graph = spec.component_dependency_graph();
+
+        assert_eq!(graph.nodes().count(), 5);
+        assert_eq!(
+            graph
+                .dependencies("#/components/schemas/Pet")
+                .collect::<Vec<_>>(),
+            ["#/components/schemas/Person", "#/components/schemas/Tag"]
+        );
+        assert_eq!(
+            graph
+                .dependents("#/components/schemas/Tag")
+                .collect::<Vec<_>>(),
+            ["#/components/schemas/Person", "#/components/schemas/Pet"]
+        );
+        assert_eq!(
+            graph.transitive_dependents("#/components/schemas/Tag"),
+            BTreeSet::from([
+                "#/components/responses/PetResponse",
+                "#/components/schemas/Person",
+                "#/components/schemas/Pet",
+            ])
+        );
+        assert!(graph
+            .transitive_dependents("#/components/schemas/Unused")
+            .is_empty());
+
+        assert_eq!(
+            graph.topological_order().unwrap(),
+            [
+                "#/components/schemas/Tag",
+                "#/components/schemas/Person",
+                "#/components/schemas/Pet",
+                "#/components/responses/PetResponse",
+                "#/components/schemas/Unused",
+            ]
+        );
+    }
+
+    #[test]
+    fn dependency_graph_of_named_properties() {
+        let spec = spec(
+            r##"
+  schemas:
+    Envelope:
+      properties:
+        headers: { $ref: "#/components/schemas/Headers" }
+        content:
+          properties:
+            schema: { $ref: "#/components/schemas/Body" }
+        example: { $ref: "#/components/schemas/Example" }
+      example:
+        $ref: "#/components/schemas/Literal"
+    Headers: { type: object }
+    Body: { type: object }
+    Example: { type: object }
+"##,
+        );
+
+        assert_eq!(
+            spec.component_dependency_graph()
+                .dependencies("#/components/schemas/Envelope")
+                .collect::<Vec<_>>(),
+            [
+                "#/components/schemas/Body",
+                "#/components/schemas/Example",
+                "#/components/schemas/Headers",
+            ]
+        );
+    }
+
+    #[test]
+    fn generation_dependencies() {
+        let spec = spec(
+            r##"
+  schemas:
+    Pet:
+      properties:
+        owner: { $ref: "#/components/schemas/Person" }
+        tag: { $ref: "#/components/schemas/Tag" }
+    Person:
+      properties:
+        pets: { items: { $ref: "#/components/schemas/Pet" } }
+        tag: { $ref: "#/components/schemas/Tag" }
+    Tag: { type: string }
+    Unused: { type: integer }
+    Species:
+      type: object
+      discriminator:
+        propertyName: kind
+        mapping: { person: Person }
+"##,
+        );
+
+        let schema: Schema = serde_json::from_value(serde_json::json!({
+            "properties": {
+                "pet": { "$ref": "#/components/schemas/Pet" },
+                "tags": { "items": { "$ref": "#/components/schemas/Tag" } },
+                "species": { "$ref": "#/components/schemas/Species" },
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            schema.generation_dependencies(&spec),
+            GenerationDependencies {
+                order: vec![
+                    "Tag".to_owned(),
+                    "Person".to_owned(),
+                    "Pet".to_owned(),
+                    "Species".to_owned(),
+                ],
+                cyclic_refs: vec![("Person".to_owned(), "Pet".to_owned())],
+            }
+        );
+    }
+
+    #[test]
+    fn dependency_graph_cycle() {
+        let spec = spec(
+            r##"
+  schemas:
+    Person: { properties: { pet: { $ref: "#/components/schemas/Pet" } } }
+    Pet: { properties: { owner: { $ref: "#/components/schemas/Person" } } }
+"##,
+        );
+
+        let graph = spec.component_dependency_graph();
+
+        assert_eq!(
+            graph.transitive_dependents("#/components/schemas/Pet"),
+            BTreeSet::from(["#/components/schemas/Person", "#/components/schemas/Pet"])
+        );
+
+        match graph.topological_order().unwrap_err() {
+            Error::ReferenceCycle(cycle) => assert_eq!(
+                cycle,
+                [
+                    "#/components/schemas/Person",
+                    "#/components/schemas/Pet",
+                    "#/components/schemas/Person",
+                ]
+            ),
+            err => panic!("unexpected error: {}", err),
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 
+use super::{FromRef, MediaTypeExamples, Ref, RefError, RefType, Spec};
 use crate::Schema;
 
 // TODO: update to 3.1 spec including JSON Schema conformance.
@@ -42,6 +43,11 @@ pub struct Header {
     /// of use.  GitHub Flavored Markdown is allowed.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+
+    /// Example of the header's potential value. The `example` field is mutually exclusive of
+    /// the `examples` field.
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub examples: Option<MediaTypeExamples>,
     // collectionFormat: ???
     // default: ???
     // maximum ?
@@ -57,3 +63,20 @@ pub struct Header {
     // multipleOf ??
     // allowEmptyValue ( for query / body params )
 }
+
+impl FromRef for Header {
+    fn from_ref(spec: &Spec, path: &str) -> Result<Self, RefError> {
+        let refpath = path.parse::<Ref>()?;
+
+        match refpath.kind {
+            RefType::Header => spec
+                .components
+                .as_ref()
+                .and_then(|cs| cs.headers.get(&refpath.name))
+                .ok_or_else(|| RefError::Unresolvable(path.to_owned()))
+                .and_then(|oor| oor.resolve(spec)),
+
+            typ => Err(RefError::MismatchedType(typ, RefType::Header)),
+        }
+    }
+}
@@ -0,0 +1,241 @@
+//! Inference of schemas from sample values.
+
+use std::collections::BTreeSet;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::{
+    schema::{Type, TypeSet},
+    ObjectOrReference, Schema,
+};
+
+static DATE_TIME: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\d{4}-\d{2}-\d{2}[Tt]\d{2}:\d{2}:\d{2}(\.\d+)?([Zz]|[+-]\d{2}:\d{2})$").unwrap()
+});
+static DATE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\d{4}-\d{2}-\d{2}$").unwrap());
+
+/// Types in the order they are listed in inferred type sets.
+const TYPE_ORDER: [Type; 7] = [
+    Type::Object,
+    Type::Array,
+    Type::String,
+    Type::Number,
+    Type::Integer,
+    Type::Boolean,
+    Type::Null,
+];
+
+impl Schema {
+    /// Infers a schema describing a sample value.
+    ///
+    /// Objects get a property for each key, all of them required. Array item schemas are merged
+    /// across all elements: differing types form a type list, object properties are unioned and
+    /// only those present in every element are required. Strings that look like RFC 3339 dates
+    /// or date-times get a `date` or `date-time` format. The result only depends on the sample.
+    pub fn infer_from_value(value: &serde_json::Value) -> Schema {
+        use serde_json::Value;
+
+        let single = |type_: Type| Some(TypeSet::Single(type_));
+
+        match value {
+            Value::Null => Schema {
+                schema_type: single(Type::Null),
+                ..Schema::default()
+            },
+
+            Value::Bool(_) => Schema {
+                schema_type: single(Type::Boolean),
+                ..Schema::default()
+            },
+
+            Value::Number(num) => Schema {
+                schema_type: single(if num.is_f64() {
+                    Type::Number
+                } else {
+                    Type::Integer
+                }),
+                ..Schema::default()
+            },
+
+            Value::String(val) => {
+                let format = if DATE_TIME.is_match(val) {
+                    Some("date-time".to_owned())
+                } else if DATE.is_match(val) {
+                    Some("date".to_owned())
+                } else {
+                    None
+                };
+
+                Schema {
+                    schema_type: single(Type::String),
+                    format,
+                    ..Schema::default()
+                }
+            }
+
+            Value::Array(items) => Schema {
+                schema_type: single(Type::Array),
+                items: items
+                    .iter()
+                    .map(Schema::infer_from_value)
+                    .reduce(merge)
+                    .map(|items| Box::new(ObjectOrReference::Object(items))),
+                ..Schema::default()
+            },
+
+            Value::Object(obj) => Schema {
+                schema_type: single(Type::Object),
+                required: obj.keys().cloned().collect(),
+                properties: obj
+                    .iter()
+                    .map(|(key, val)| {
+                        let prop = ObjectOrReference::Object(Schema::infer_from_value(val));
+                        (key.clone(), prop)
+                    })
+                    .collect(),
+                ..Schema::default()
+            },
+        }
+    }
+}
+
+/// Merges two inferred schemas into one describing the values of both.
+fn merge(a: Schema, b: Schema) -> Schema {
+    let types = |schema: &Schema| {
+        schema
+            .schema_type
+            .as_ref()
+            .map(|types| types.types().to_vec())
+            .unwrap_or_default()
+    };
+
+    let mut all_types = types(&a);
+    all_types.extend(types(&b));
+
+    // integers are numbers too
+    if all_types.contains(&Type::Number) {
+        all_types.retain(|type_| *type_ != Type::Integer);
+    }
+
+    let all_types = TYPE_ORDER
+        .into_iter()
+        .filter(|type_| all_types.contains(type_))
+        .collect::<Vec<_>>();
+
+    let schema_type = match all_types.as_slice() {
+        [] => None,
+        [type_] => Some(TypeSet::Single(*type_)),
+        _ => Some(TypeSet::Multiple(all_types)),
+    };
+
+    let format = if a.format == b.format { a.format } else { None };
+
+    let items = match (a.items, b.items) {
+        (Some(a), Some(b)) => match (*a, *b) {
+            (ObjectOrReference::Object(a), ObjectOrReference::Object(b)) => {
+                Some(Box::new(ObjectOrReference::Object(merge(a, b))))
+            }
+            (a, _) => Some(Box::new(a)),
+        },
+        (a, b) => a.or(b),
+    };
+
+    // only objects contribute required properties, so a non-object sample keeps those of the
+    // other
+    let required = match (&a.schema_type, &b.schema_type) {
+        (Some(a_type), Some(b_type))
+            if a_type.contains(Type::Object) && b_type.contains(Type::Object) =>
+        {
+            let b_required = b.required.iter().collect::<BTreeSet<_>>();
+            a.required
+                .iter()
+                .filter(|name| b_required.contains(name))
+                .cloned()
+                .collect()
+        }
+        (Some(a_type), _) if a_type.contains(Type::Object) => a.required,
+        _ => b.required,
+    };
+
+    let mut properties = a.properties;
+
+    for (name, b_prop) in b.properties {
+        let prop = match properties.remove(&name) {
+            Some(ObjectOrReference::Object(a_prop)) => match b_prop {
+                ObjectOrReference::Object(b_prop) => {
+                    ObjectOrReference::Object(merge(a_prop, b_prop))
+                }
+                b_prop => b_prop,
+            },
+            Some(a_prop) => a_prop,
+            None => b_prop,
+        };
+
+        properties.insert(name, prop);
+    }
+
+    Schema {
+        schema_type,
+        format,
+        items,
+        required,
+        properties,
+        ..Schema::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn infer_from_value() {
+        let sample = json!({
+            "id": 1,
+            "name": "Rex",
+            "born": "2020-01-02",
+            "updated": "2024-05-06T07:08:09Z",
+            "tags": [
+                { "name": "dog", "weight": 1 },
+                { "name": "good", "weight": 0.5, "color": null },
+            ],
+            "scores": [1, "high"],
+            "empty": [],
+        });
+
+        let schema = Schema::infer_from_value(&sample);
+
+        assert_eq!(
+            serde_json::to_value(&schema).unwrap(),
+            json!({
+                "type": "object",
+                "required": ["born", "empty", "id", "name", "scores", "tags", "updated"],
+                "properties": {
+                    "born": { "type": "string", "format": "date" },
+                    "empty": { "type": "array" },
+                    "id": { "type": "integer" },
+                    "name": { "type": "string" },
+                    "scores": { "type": "array", "items": { "type": ["string", "integer"] } },
+                    "tags": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "required": ["name", "weight"],
+                            "properties": {
+                                "color": { "type": "null" },
+                                "name": { "type": "string" },
+                                "weight": { "type": "number" },
+                            },
+                        },
+                    },
+                    "updated": { "type": "string", "format": "date-time" },
+                },
+            })
+        );
+
+        assert_eq!(Schema::infer_from_value(&sample), schema);
+    }
+}
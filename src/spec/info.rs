@@ -1,7 +1,9 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-use super::{Contact, License};
+use super::{spec_extensions, Contact, License};
 
 /// General information about the API.
 ///
@@ -37,4 +39,88 @@ pub struct Info {
     /// The license information for the exposed API.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub license: Option<License>,
+
+    /// Specification extensions.
+    ///
+    /// Only "x-" prefixed keys are collected, and the prefix is stripped.
+    ///
+    /// See <https://github.com/OAI/OpenAPI-Specification/blob/HEAD/versions/3.1.0.md#specificationExtensions>.
+    #[serde(flatten, with = "spec_extensions")]
+    pub extensions: BTreeMap<String, serde_json::Value>,
+}
+
+/// The API logo described by the `x-logo` extension, as used by Redoc.
+///
+/// See <https://redocly.com/docs/api-reference-docs/specification-extensions/x-logo>.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Logo {
+    /// URL of the logo image; absolute or relative to the spec.
+    pub url: String,
+
+    /// Background color of the logo, as a CSS color.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background_color: Option<String>,
+
+    /// Alternative text of the logo image.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alt_text: Option<String>,
+
+    /// URL that the logo links to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub href: Option<String>,
+}
+
+impl Info {
+    /// Parses the API version as a semantic version, if it is one.
+    pub fn semver(&self) -> Option<semver::Version> {
+        semver::Version::parse(&self.version).ok()
+    }
+
+    /// Parses the `x-logo` extension. Returns `None` if it is absent or malformed.
+    pub fn logo(&self) -> Option<Logo> {
+        Logo::deserialize(self.extensions.get("logo")?).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn logo_extension() {
+        let info = serde_json::from_value::<Info>(json!({
+            "title": "Test",
+            "version": "1",
+            "x-logo": {
+                "url": "https://example.com/logo.png",
+                "backgroundColor": "#FFFFFF",
+                "altText": "Example logo"
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            info.logo().unwrap(),
+            Logo {
+                url: "https://example.com/logo.png".to_owned(),
+                background_color: Some("#FFFFFF".to_owned()),
+                alt_text: Some("Example logo".to_owned()),
+                href: None,
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(&info).unwrap()["x-logo"]["altText"],
+            "Example logo"
+        );
+
+        let info = Info {
+            extensions: BTreeMap::from([("logo".to_owned(), json!({ "altText": "no url" }))]),
+            ..Info::default()
+        };
+        assert_eq!(info.logo(), None);
+        assert_eq!(Info::default().logo(), None);
+    }
 }
@@ -2,9 +2,33 @@ use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
-use super::{Encoding, Error, Example, MediaTypeExamples, ObjectOrReference, Spec};
+use super::{
+    Encoding, Error, Example, MediaTypeExamples, ObjectOrReference, Ref, RefError, RefType, Spec,
+};
 use crate::Schema;
 
+/// Returns the essence of `media_type`, its lowercase type and subtype without parameters, e.g.
+/// `text/plain` for `Text/Plain; charset=utf-8`.
+pub fn media_type_essence(media_type: &str) -> String {
+    media_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+}
+
+/// Returns true if `media_type` is a JSON media type, such as `application/json`,
+/// `application/problem+json` or `text/json`.
+///
+/// Parameters like `charset` are ignored.
+pub fn is_json_media_type(media_type: &str) -> bool {
+    match media_type_essence(media_type).split_once('/') {
+        Some((_, subtype)) => subtype == "json" || subtype.ends_with("+json"),
+        None => false,
+    }
+}
+
 /// Each Media Type Object provides schema and examples for the media type identified by its key.
 ///
 /// See <https://github.com/OAI/OpenAPI-Specification/blob/HEAD/versions/3.1.0.md#media-type-object>.
@@ -44,4 +68,126 @@ impl MediaType {
             .map(|examples| examples.resolve_all(spec))
             .unwrap_or_default()
     }
+
+    /// Returns the schema of this media type, borrowed from the spec if it is a reference.
+    ///
+    /// Returns `Ok(None)` if no schema is declared.
+    pub fn schema_resolved<'a>(&'a self, spec: &'a Spec) -> Result<Option<&'a Schema>, Error> {
+        let ref_path = match self.schema {
+            Some(ObjectOrReference::Object(ref schema)) => return Ok(Some(schema)),
+            Some(ObjectOrReference::Ref { ref ref_path, .. }) => ref_path,
+            None => return Ok(None),
+        };
+
+        let schemas = spec.components.as_ref().map(|cs| &cs.schemas);
+        let mut next = ref_path;
+
+        // a chain of references longer than the number of component schemas must be a cycle
+        for _ in 0..=schemas.map_or(0, BTreeMap::len) {
+            let refpath = next.parse::<Ref>()?;
+
+            if refpath.kind != RefType::Schema {
+                return Err(RefError::MismatchedType(refpath.kind, RefType::Schema).into());
+            }
+
+            match schemas.and_then(|schemas| schemas.get(&refpath.name)) {
+                Some(ObjectOrReference::Object(schema)) => return Ok(Some(schema)),
+                Some(ObjectOrReference::Ref { ref_path, .. }) => next = ref_path,
+                None => return Err(RefError::Unresolvable(next.clone()).into()),
+            }
+        }
+
+        Err(RefError::Unresolvable(ref_path.clone()).into())
+    }
+
+    /// Returns the number of examples declared for this media type.
+    pub fn example_count(&self) -> usize {
+        match self.examples {
+            Some(MediaTypeExamples::Example { .. }) => 1,
+            Some(MediaTypeExamples::Examples { ref examples }) => examples.len(),
+            None => 0,
+        }
+    }
+
+    /// Returns true if this media type declares a schema.
+    pub fn has_schema(&self) -> bool {
+        self.schema.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_media_types() {
+        assert!(is_json_media_type("application/json"));
+        assert!(is_json_media_type(
+            "application/problem+json; charset=utf-8"
+        ));
+        assert!(is_json_media_type("Text/JSON"));
+        assert!(!is_json_media_type("application/xml"));
+        assert!(!is_json_media_type("application/jsonl"));
+        assert!(!is_json_media_type("json"));
+    }
+    #[test]
+    fn schema_resolved() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths: {}
+components:
+  schemas:
+    Pet: { $ref: "#/components/schemas/Animal" }
+    Animal: { type: object, title: Animal }
+  responses:
+    Inline:
+      description: inline schema
+      content:
+        application/json:
+          schema: { type: string }
+          example: foo
+    Referenced:
+      description: referenced schema
+      content:
+        application/json:
+          schema: { $ref: "#/components/schemas/Pet" }
+          examples:
+            a: { value: 1 }
+            b: { value: 2 }
+    Empty:
+      description: no schema
+      content:
+        text/plain: {}
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let responses = &spec.components.as_ref().unwrap().responses;
+        let media_type = |name: &str| {
+            let res = responses[name].resolve(&spec).unwrap();
+            res.content.into_values().next().unwrap()
+        };
+
+        let inline = media_type("Inline");
+        assert!(inline.has_schema());
+        assert_eq!(inline.example_count(), 1);
+        let schema = inline.schema_resolved(&spec).unwrap().unwrap();
+        assert_eq!(
+            schema.schema_type,
+            Some(crate::spec::SchemaType::String.into())
+        );
+
+        let referenced = media_type("Referenced");
+        assert_eq!(referenced.example_count(), 2);
+        let schema = referenced.schema_resolved(&spec).unwrap().unwrap();
+        assert_eq!(schema.title.as_deref(), Some("Animal"));
+
+        let empty = media_type("Empty");
+        assert!(!empty.has_schema());
+        assert_eq!(empty.example_count(), 0);
+        assert!(empty.schema_resolved(&spec).unwrap().is_none());
+    }
 }
@@ -2,22 +2,36 @@
 //!
 //! High-level structures include [`Spec`], [`Components`] & [`Schema`].
 
-use std::{collections::BTreeMap, iter::Iterator};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    iter::Iterator,
+};
 
 use derive_more::Error;
 use http::Method;
 use log::debug;
 use serde::{Deserialize, Serialize};
 
+use crate::json_pointer;
+
+mod all_of;
+mod bundle;
+mod classify;
 mod components;
 mod contact;
+mod dialect;
+mod diff;
+mod discriminator;
 mod encoding;
 
 mod error;
 mod example;
+mod expression;
 mod external_doc;
 mod flows;
+mod graph;
 mod header;
+mod infer;
 mod info;
 mod license;
 mod link;
@@ -25,21 +39,35 @@ mod media_type;
 mod media_type_examples;
 mod operation;
 mod parameter;
+mod parameter_style;
 mod path_item;
+pub mod prelude;
 mod r#ref;
 mod request_body;
 mod response;
 mod schema;
 mod security_scheme;
 mod server;
+mod spec_extensions;
 mod tag;
+mod usage;
+mod version;
+pub(crate) mod visit;
 
+pub use all_of::*;
+pub use bundle::*;
+pub use classify::*;
 pub use components::*;
 pub use contact::*;
+pub use dialect::*;
+pub use diff::*;
+pub use discriminator::*;
 pub use encoding::*;
 pub use example::*;
+pub use expression::*;
 pub use external_doc::*;
 pub use flows::*;
+pub use graph::*;
 pub use header::*;
 pub use info::*;
 pub use license::*;
@@ -48,6 +76,7 @@ pub use media_type::*;
 pub use media_type_examples::*;
 pub use operation::*;
 pub use parameter::*;
+pub use parameter_style::*;
 pub use path_item::*;
 pub use r#ref::*;
 pub use request_body::*;
@@ -55,11 +84,19 @@ pub use response::*;
 pub use security_scheme::*;
 pub use server::*;
 pub use tag::*;
+pub use usage::*;
+pub use version::*;
 
 pub use error::Error;
-pub use schema::{Error as SchemaError, Schema, Type as SchemaType};
+pub use schema::{
+    BooleanOrNumber, BooleanOrSchema, Context as SchemaContext, Error as SchemaError,
+    NumericBounds, Schema, Type as SchemaType, TypeSet as SchemaTypeSet, TYPE_NAME_EXTENSIONS,
+};
 
 const OPENAPI_SUPPORTED_VERSION_RANGE: &str = "~3";
+const JSON_SCHEMA_DIALECT_2020_12: &str = "https://json-schema.org/draft/2020-12/schema";
+const COMPONENT_SCHEMAS_REF_PREFIX: &str = "#/components/schemas/";
+const PATHS_REF_PREFIX: &str = "#/paths/";
 
 /// A complete OpenAPI specification.
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
@@ -72,11 +109,22 @@ pub struct Spec {
     /// the API
     /// [`info.version`](https://github.com/OAI/OpenAPI-Specification/blob/HEAD/versions/3.1.0.md#infoVersion)
     /// string.
+    ///
+    /// The version is kept verbatim, so it round-trips exactly; see
+    /// [`validate_version`](Self::validate_version) for the parsed form.
     pub openapi: String,
 
     /// Provides metadata about the API. The metadata MAY be used by tooling as required.
     pub info: Info,
 
+    /// The default value for the `$schema` keyword within Schema Objects contained within this
+    /// OAS document. This MUST be in the form of a URI.
+    ///
+    /// See [`Spec::schema_dialect`].
+    #[serde(rename = "jsonSchemaDialect")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json_schema_dialect: Option<String>,
+
     /// An array of Server Objects, which provide connectivity information to a target server.
     /// If the `servers` property is not provided, or is an empty array, the default value would
     /// be a
@@ -100,13 +148,14 @@ pub struct Spec {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub components: Option<Components>,
 
-    // FIXME: Implement
-    // /// A declaration of which security mechanisms can be used across the API.
-    // /// The list of  values includes alternative security requirement objects that can be used.
-    // /// Only one of the security requirement objects need to be satisfied to authorize a request.
-    // /// Individual operations can override this definition.
-    // #[serde(skip_serializing_if = "Option::is_none")]
-    // pub security: Option<SecurityRequirement>,
+    /// A declaration of which security mechanisms can be used across the API.
+    /// The list of  values includes alternative security requirement objects that can be used.
+    /// Only one of the security requirement objects need to be satisfied to authorize a request.
+    /// Individual operations can override this definition.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub security: Vec<SecurityRequirement>,
+
     /// A list of tags used by the specification with additional metadata.
     ///The order of the tags can be used to reflect on their order by the parsing tools.
     /// Not all tags that are used by the
@@ -137,6 +186,116 @@ pub struct Spec {
 // TODO: Add "Specification Extensions" https://github.com/OAI/OpenAPI-Specification/blob/HEAD/versions/3.1.0.md#specificationExtensions}
 
 impl Spec {
+    /// Returns the smallest valid spec: an OpenAPI 3.1.0 document with the given `info.title`
+    /// and `info.version` and no paths.
+    ///
+    /// Useful as a starting point for building specs and as a test fixture.
+    pub fn minimal_valid(title: &str, version: &str) -> Spec {
+        Spec {
+            openapi: "3.1.0".to_owned(),
+            info: Info {
+                title: title.to_owned(),
+                version: version.to_owned(),
+                ..Info::default()
+            },
+            ..Spec::default()
+        }
+    }
+
+    /// Builds an OpenAPI 3.1.0 spec with the given `info` from path items added one at a time,
+    /// validating each as it is added.
+    ///
+    /// Each path must start with `/` and contain well-formed, uniquely named template
+    /// parameters, e.g. `/pets/{id}` or `/files/{name}.{ext}`. Inline path parameters of the path
+    /// item and its operations must be `required` and appear in the template, and every template
+    /// parameter must be declared for every operation. Referenced parameters can not be resolved
+    /// while building, so operations with any are not checked for missing declarations.
+    ///
+    /// Returns [`Error::PathExists`] for a repeated path and [`Error::InvalidPath`] for any other
+    /// problem, naming the offending path.
+    pub fn with_paths(
+        info: Info,
+        paths: impl IntoIterator<Item = (String, PathItem)>,
+    ) -> Result<Spec, Error> {
+        let mut spec = Spec {
+            openapi: "3.1.0".to_owned(),
+            info,
+            ..Spec::default()
+        };
+
+        for (path, path_item) in paths {
+            if spec.paths.contains_key(&path) {
+                return Err(Error::PathExists(path));
+            }
+
+            let err = |reason: String| Error::InvalidPath {
+                path: path.clone(),
+                reason,
+            };
+
+            let template_params = path_template_params(&path).map_err(&err)?;
+
+            let inline_path_params = |params: &[ObjectOrReference<Parameter>]| {
+                params
+                    .iter()
+                    .filter_map(|param| match param {
+                        ObjectOrReference::Object(param) if param.location == "path" => {
+                            Some(param.clone())
+                        }
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>()
+            };
+
+            let item_params = inline_path_params(&path_item.parameters);
+
+            for (method, op) in path_item.methods() {
+                let op_params = inline_path_params(&op.parameters);
+                let declared = item_params.iter().chain(&op_params).collect::<Vec<_>>();
+
+                for param in &declared {
+                    if !template_params.contains(&param.name.as_str()) {
+                        return Err(err(format!(
+                            "path parameter `{}` of {} is not in the path template",
+                            param.name, method
+                        )));
+                    }
+
+                    if param.required != Some(true) {
+                        return Err(err(format!(
+                            "path parameter `{}` of {} is not required",
+                            param.name, method
+                        )));
+                    }
+                }
+
+                let has_refs = path_item
+                    .parameters
+                    .iter()
+                    .chain(&op.parameters)
+                    .any(|param| matches!(param, ObjectOrReference::Ref { .. }));
+
+                if has_refs {
+                    continue;
+                }
+
+                if let Some(missing) = template_params
+                    .iter()
+                    .find(|name| !declared.iter().any(|param| param.name == **name))
+                {
+                    return Err(err(format!(
+                        "path parameter `{}` is not declared for {}",
+                        missing, method
+                    )));
+                }
+            }
+
+            spec.paths.insert(path, path_item);
+        }
+
+        Ok(spec)
+    }
+
     pub fn validate_version(&self) -> Result<semver::Version, Error> {
         let spec_version = &self.openapi;
         let sem_ver = semver::Version::parse(spec_version)?;
@@ -149,6 +308,21 @@ impl Spec {
         }
     }
 
+    /// Returns true if this spec's API version is higher than `other`'s.
+    ///
+    /// Returns `None` if either `info.version` is not a semantic version.
+    pub fn is_newer_than(&self, other: &Spec) -> Option<bool> {
+        Some(self.info.semver()? > other.info.semver()?)
+    }
+
+    /// Returns true if this spec's API major version is higher than `previous`'s.
+    ///
+    /// Useful for asserting that breaking changes are accompanied by a major version bump.
+    /// Returns `None` if either `info.version` is not a semantic version.
+    pub fn is_major_bump_of(&self, previous: &Spec) -> Option<bool> {
+        Some(self.info.semver()?.major > previous.info.semver()?.major)
+    }
+
     pub fn operation(&self, method: &http::Method, path: &str) -> Option<&Operation> {
         let resource = self.paths.get(path)?;
 
@@ -165,7 +339,16 @@ impl Spec {
         }
     }
 
-    pub fn operations(&self) -> impl Iterator<Item = (String, Method, &Operation)> {
+    /// Looks up a path item by its path key, resolving it first if it is a `$ref`.
+    pub fn resolve_path_item(&self, path: &str) -> Result<PathItem, Error> {
+        self.paths
+            .get(path)
+            .ok_or_else(|| Error::PathNotFound(path.to_owned()))?
+            .resolve(self)
+    }
+
+    /// Returns all operations of `paths`, in path and then method order.
+    pub fn operations(&self) -> impl Iterator<Item = OperationRef<'_>> {
         let paths = &self.paths;
 
         debug!("num paths: {}", paths.len());
@@ -181,7 +364,7 @@ impl Spec {
 
                 item.methods()
                     .into_iter()
-                    .map(move |(method, op)| (path.to_owned(), method, op))
+                    .map(move |(method, op)| OperationRef::new(path, method, op))
             })
             .collect::<Vec<_>>();
 
@@ -190,7 +373,1001 @@ impl Spec {
         ops.into_iter()
     }
 
+    /// Finds the operation with the given `operationId`.
+    ///
+    /// If multiple operations share the ID, the first in [`operations`](Self::operations) order
+    /// is returned.
+    pub fn operation_by_id(&self, operation_id: &str) -> Option<OperationRef<'_>> {
+        self.operations()
+            .find(|op| op.operation_id() == Some(operation_id))
+    }
+
+    /// Returns the operation IDs of all operations, in path and then method order.
+    ///
+    /// Operations without an ID are skipped.
+    pub fn operation_ids(&self) -> impl Iterator<Item = &str> {
+        self.operations().filter_map(|op| op.operation_id())
+    }
+
+    /// Returns the path and method of each operation, keyed by operation ID.
+    ///
+    /// If multiple operations share an ID, the last one in [`operation_ids`](Self::operation_ids)
+    /// order is kept.
+    pub fn operation_id_map(&self) -> BTreeMap<&str, (String, Method)> {
+        self.operations()
+            .filter_map(|op| {
+                op.operation_id()
+                    .map(|id| (id, (op.path_template().to_owned(), op.method().clone())))
+            })
+            .collect()
+    }
+
+    /// Calls `visit` with the path, method and a mutable reference to every operation.
+    pub fn walk_operations_mut(&mut self, mut visit: impl FnMut(&str, Method, &mut Operation)) {
+        for (path, item) in self.paths.iter_mut() {
+            for (method, op) in item.methods_mut() {
+                visit(path, method, op);
+            }
+        }
+    }
+
+    /// Prepends `prefix` (e.g. `/v2`) to every path.
+    ///
+    /// Slashes around the prefix are normalized, so `v2`, `/v2` and `/v2/` are equivalent, and
+    /// the root path `/` becomes the bare prefix. References and link `operationRef`s that point
+    /// into `paths` are updated to match. If two distinct paths would be prefixed to the same
+    /// path, e.g. `/` and an empty path, [`Error::PathCollision`] is returned and the spec is left
+    /// unchanged.
+    pub fn prefix_paths(&mut self, prefix: &str) -> Result<(), Error> {
+        let prefix = prefix.trim_matches('/');
+
+        if prefix.is_empty() {
+            return Ok(());
+        }
+
+        let renames = self
+            .paths
+            .keys()
+            .map(|path| {
+                let new_path = match path.as_str() {
+                    "/" | "" => format!("/{}", prefix),
+                    path => format!("/{}/{}", prefix, path.trim_start_matches('/')),
+                };
+                (path.clone(), new_path)
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        self.rename_paths(&renames)
+    }
+
+    /// Canonicalizes the slashes in every path, collapsing repeated slashes and stripping
+    /// trailing slashes from all paths but the root, e.g. `/users//{id}/` becomes `/users/{id}`.
+    ///
+    /// Returns the `(old, new)` pairs of changed paths. References and link `operationRef`s that
+    /// point into `paths` are updated to match. If two distinct paths would normalize to the same
+    /// path, [`Error::PathCollision`] is returned and the spec is left unchanged.
+    pub fn normalize_paths(&mut self) -> Result<Vec<(String, String)>, Error> {
+        fn normalize(path: &str) -> String {
+            let segments = path.split('/').filter(|segment| !segment.is_empty());
+            let mut normalized = String::new();
+
+            for segment in segments {
+                normalized.push('/');
+                normalized.push_str(segment);
+            }
+
+            if normalized.is_empty() {
+                normalized.push('/');
+            }
+
+            normalized
+        }
+
+        let mut normalized_paths = BTreeMap::<String, &String>::new();
+
+        for path in self.paths.keys() {
+            let normalized = normalize(path);
+
+            if let Some(first) = normalized_paths.insert(normalized.clone(), path) {
+                return Err(Error::PathCollision {
+                    first: first.clone(),
+                    second: path.clone(),
+                    normalized,
+                });
+            }
+        }
+
+        let renames = normalized_paths
+            .into_iter()
+            .map(|(new_path, path)| (path.clone(), new_path))
+            .collect::<BTreeMap<_, _>>();
+
+        let changes = renames
+            .iter()
+            .filter(|(path, new_path)| path != new_path)
+            .map(|(path, new_path)| (path.clone(), new_path.clone()))
+            .collect::<Vec<_>>();
+
+        if !changes.is_empty() {
+            self.rename_paths(&renames)?;
+        }
+
+        Ok(changes)
+    }
+
+    /// Moves the path item at `old` to `new`.
+    ///
+    /// References and link `operationRef`s that point into the old path are updated to match.
+    /// Returns [`Error::PathNotFound`] if `old` does not exist, [`Error::PathExists`] if `new`
+    /// already does and [`Error::PathTemplateMismatch`] if the two paths do not have the same
+    /// template parameters, including those within segments such as `{name}.json`, or either is
+    /// not a valid path template, since the path item's parameters would no longer apply.
+    pub fn rename_path(&mut self, old: &str, new: &str) -> Result<(), Error> {
+        fn template_params(path: &str) -> Option<Vec<&str>> {
+            let mut params = path_template_params(path).ok()?;
+            params.sort_unstable();
+            Some(params)
+        }
+
+        if !self.paths.contains_key(old) {
+            return Err(Error::PathNotFound(old.to_owned()));
+        }
+
+        if old == new {
+            return Ok(());
+        }
+
+        if self.paths.contains_key(new) {
+            return Err(Error::PathExists(new.to_owned()));
+        }
+
+        let params = template_params(old);
+
+        if params.is_none() || params != template_params(new) {
+            return Err(Error::PathTemplateMismatch {
+                old: old.to_owned(),
+                new: new.to_owned(),
+            });
+        }
+
+        let renames = self
+            .paths
+            .keys()
+            .map(|path| {
+                let new_path = if path == old { new } else { path };
+                (path.clone(), new_path.to_owned())
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        self.rename_paths(&renames)
+    }
+
+    /// Moves every path item to the path `renames` maps it to, updating references into `paths`.
+    ///
+    /// Returns [`Error::PathCollision`] if two paths are mapped to the same path. The spec is
+    /// only changed if no error is returned.
+    fn rename_paths(&mut self, renames: &BTreeMap<String, String>) -> Result<(), Error> {
+        let mut sources = BTreeMap::<&String, &String>::new();
+
+        for (path, new_path) in renames {
+            if let Some(first) = sources.insert(new_path, path) {
+                return Err(Error::PathCollision {
+                    first: first.clone(),
+                    second: path.clone(),
+                    normalized: new_path.clone(),
+                });
+            }
+        }
+
+        let mut doc = serde_json::to_value(&*self).unwrap();
+
+        if let Some(serde_json::Value::Object(paths)) = doc.get_mut("paths") {
+            *paths = std::mem::take(paths)
+                .into_iter()
+                .map(|(path, item)| (renames.get(&path).cloned().unwrap_or(path), item))
+                .collect();
+        }
+
+        r#ref::rewrite_refs(&mut doc, &mut |ref_path| {
+            let rest = ref_path.strip_prefix(PATHS_REF_PREFIX)?;
+            let (path, tail) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+            let new_path = renames.get(&json_pointer::unescape(path))?;
+
+            Some(format!(
+                "{}{}{}",
+                PATHS_REF_PREFIX,
+                json_pointer::escape(new_path),
+                tail
+            ))
+        });
+
+        *self = serde_json::from_value(doc).map_err(Error::InvalidDocument)?;
+        Ok(())
+    }
+
     pub fn primary_server(&self) -> Option<&Server> {
         self.servers.first()
     }
+
+    /// Resolves `oor` against this spec, returning an owned copy of the target.
+    ///
+    /// The result does not borrow the spec, so it can be moved to another thread or held across
+    /// an `.await` while the spec is dropped or replaced. The cost is a deep clone of the target,
+    /// including all of its nested subschemas, on every call; references within the target are
+    /// left as-is. Equivalent to [`ObjectOrReference::resolve`], with the error wrapped in
+    /// [`Error::Ref`].
+    pub fn resolve_owned<T: FromRef>(&self, oor: &ObjectOrReference<T>) -> Result<T, Error> {
+        oor.resolve(self).map_err(Error::Ref)
+    }
+
+    /// Returns every `$ref` in the spec that cannot be resolved.
+    ///
+    /// Only references to local components (`#/components/...`) can be resolved; all others,
+    /// including references to external documents, are reported as dangling.
+    pub fn dangling_refs(&self) -> Vec<DanglingRef> {
+        let doc = serde_json::to_value(self).unwrap();
+
+        r#ref::find_refs(&doc)
+            .into_iter()
+            .filter_map(|(location, ref_path)| {
+                let result = ref_path
+                    .parse::<Ref>()
+                    .and_then(|r| r.kind.check(self, &ref_path));
+
+                result.err().map(|error| DanglingRef {
+                    location,
+                    ref_path,
+                    error,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the first `$ref` cycle found between components or path items, if any.
+    ///
+    /// The cycle is given as pointers to the components and path items involved, starting and
+    /// ending with the same pointer, e.g.
+    /// `["#/components/schemas/Node", "#/components/schemas/Node"]` for a self-referential
+    /// schema. This is cheaper than dereferencing the spec and indicates whether cycle-safe
+    /// processing is needed.
+    pub fn has_reference_cycles(&self) -> Option<Vec<String>> {
+        let doc = serde_json::to_value(self).unwrap();
+
+        let mut edges = BTreeMap::<String, BTreeSet<String>>::new();
+
+        for (location, ref_path) in r#ref::find_refs(&doc) {
+            if let (Some(from), Some(to)) = (
+                r#ref::containing_node(&location),
+                r#ref::containing_node(&ref_path),
+            ) {
+                edges.entry(from).or_default().insert(to);
+            }
+        }
+
+        r#ref::find_cycle(&edges)
+    }
+
+    /// Returns a copy of this spec after verifying that all of its `$ref`s resolve.
+    ///
+    /// References are kept as-is rather than being inlined, keeping the spec compact. If any
+    /// references can not be resolved, all of them are listed in the returned error.
+    pub fn clone_shallow_resolved(&self) -> Result<Spec, Error> {
+        let dangling = self.dangling_refs();
+
+        if dangling.is_empty() {
+            Ok(self.clone())
+        } else {
+            Err(Error::DanglingRefs(dangling))
+        }
+    }
+
+    /// Exports all component schemas as a single JSON Schema (2020-12) document.
+    ///
+    /// Each component schema is placed under `$defs` and all references to component schemas,
+    /// including those in discriminator mappings, are rewritten to point at `#/$defs/...`. Bare
+    /// schema names in mappings are rewritten to such references too, while literal data, such as
+    /// examples and defaults, is left as-is.
+    pub fn to_json_schema_bundle(&self) -> serde_json::Value {
+        let defs = self
+            .components
+            .iter()
+            .flat_map(|components| &components.schemas)
+            .map(|(name, schema)| {
+                let mut schema = serde_json::to_value(schema).unwrap();
+                rewrite_schema_refs(&mut schema, name);
+                (name.clone(), schema)
+            })
+            .collect::<serde_json::Map<_, _>>();
+
+        serde_json::json!({
+            "$schema": JSON_SCHEMA_DIALECT_2020_12,
+            "$defs": defs,
+        })
+    }
+}
+
+/// Rewrites `#/components/schemas/*` references to `#/$defs/*` in the serialized component
+/// schema `name`, including discriminator mapping values, which may also be bare schema names.
+fn rewrite_schema_refs(val: &mut serde_json::Value, name: &str) {
+    fn rewrite(ref_path: &mut String) {
+        if let Some(name) = ref_path.strip_prefix(COMPONENT_SCHEMAS_REF_PREFIX) {
+            *ref_path = format!("#/$defs/{}", name);
+        }
+    }
+
+    let location = format!(
+        "{}{}",
+        COMPONENT_SCHEMAS_REF_PREFIX,
+        json_pointer::escape(name)
+    );
+
+    r#ref::for_each_schema_at(val, &location, &mut |schema| {
+        if let Some(serde_json::Value::String(ref_path)) = schema.get_mut("$ref") {
+            rewrite(ref_path);
+        }
+
+        let mapping = schema
+            .get_mut("discriminator")
+            .and_then(|discriminator| discriminator.get_mut("mapping"))
+            .and_then(|mapping| mapping.as_object_mut());
+
+        for target in mapping.into_iter().flat_map(|mapping| mapping.values_mut()) {
+            if let serde_json::Value::String(target) = target {
+                if !target.contains('/') && !target.starts_with('#') {
+                    *target = format!("#/$defs/{}", json_pointer::escape(target));
+                } else {
+                    rewrite(target);
+                }
+            }
+        }
+    });
+}
+
+/// Returns the names of the template parameters of `path`, or why it is not a valid path
+/// template.
+fn path_template_params(path: &str) -> Result<Vec<&str>, String> {
+    if !path.starts_with('/') {
+        return Err("path does not start with `/`".to_owned());
+    }
+
+    let mut params = vec![];
+    let mut rest = path;
+
+    while let Some(start) = rest.find(['{', '}']) {
+        if rest[start..].starts_with('}') {
+            return Err("unmatched `}`".to_owned());
+        }
+
+        let after = &rest[start + 1..];
+        let end = after.find('}').ok_or_else(|| "unmatched `{`".to_owned())?;
+        let name = &after[..end];
+
+        if name.is_empty() {
+            return Err("empty template parameter".to_owned());
+        }
+
+        if name.contains(['{', '/']) {
+            return Err(format!("invalid template parameter `{}`", name));
+        }
+
+        if params.contains(&name) {
+            return Err(format!("repeated template parameter `{}`", name));
+        }
+
+        params.push(name);
+        rest = &after[end + 1..];
+    }
+
+    Ok(params)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    fn spec_with_version(version: &str) -> Spec {
+        Spec {
+            info: Info {
+                version: version.to_owned(),
+                ..Info::default()
+            },
+            ..Spec::default()
+        }
+    }
+
+    #[test]
+    fn with_paths() {
+        let path_item = |yaml: &str| serde_yaml::from_str::<PathItem>(yaml).unwrap();
+        let info = || Spec::minimal_valid("Test", "1").info;
+
+        let pet = path_item(
+            r##"
+parameters:
+  - { name: id, in: path, required: true }
+get:
+  parameters:
+    - { name: q, in: query }
+  responses: {}
+delete: { responses: {} }
+"##,
+        );
+        let file = path_item(
+            r##"
+get:
+  parameters:
+    - $ref: "#/components/parameters/Name"
+  responses: {}
+"##,
+        );
+
+        let spec = Spec::with_paths(
+            info(),
+            [
+                ("/pets/{id}".to_owned(), pet.clone()),
+                ("/files/{name}.{ext}".to_owned(), file),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            spec.paths.keys().collect::<Vec<_>>(),
+            ["/files/{name}.{ext}", "/pets/{id}"]
+        );
+
+        for path in [
+            "pets/{id}",
+            "/pets/{id",
+            "/pets/id}",
+            "/pets/{}",
+            "/pets/{id}/{id}",
+        ] {
+            let res = Spec::with_paths(info(), [(path.to_owned(), pet.clone())]);
+            assert!(matches!(res, Err(Error::InvalidPath { .. })), "{}", path);
+        }
+
+        match Spec::with_paths(info(), [("/pets".to_owned(), pet.clone())]) {
+            Err(Error::InvalidPath { path, reason }) => {
+                assert_eq!(path, "/pets");
+                assert_eq!(
+                    reason,
+                    "path parameter `id` of GET is not in the path template"
+                );
+            }
+            res => panic!("expected invalid path, got {:?}", res),
+        }
+
+        let undeclared = path_item("get: { responses: {} }");
+        match Spec::with_paths(info(), [("/pets/{id}".to_owned(), undeclared)]) {
+            Err(Error::InvalidPath { reason, .. }) => {
+                assert_eq!(reason, "path parameter `id` is not declared for GET")
+            }
+            res => panic!("expected invalid path, got {:?}", res),
+        }
+
+        let optional = path_item(
+            r##"
+get:
+  parameters:
+    - { name: id, in: path }
+  responses: {}
+"##,
+        );
+        assert!(matches!(
+            Spec::with_paths(info(), [("/pets/{id}".to_owned(), optional)]),
+            Err(Error::InvalidPath { .. })
+        ));
+
+        assert!(matches!(
+            Spec::with_paths(
+                info(),
+                [
+                    ("/pets/{id}".to_owned(), pet.clone()),
+                    ("/pets/{id}".to_owned(), pet)
+                ]
+            ),
+            Err(Error::PathExists(_))
+        ));
+    }
+
+    #[test]
+    fn minimal_valid() {
+        let spec = Spec::minimal_valid("Test", "1.0.0");
+        spec.validate_version().unwrap();
+        assert!(crate::lint::lint(&spec).is_empty());
+
+        let json = crate::to_json(&spec).unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&json).unwrap(),
+            serde_json::json!({
+                "openapi": "3.1.0",
+                "info": { "title": "Test", "version": "1.0.0" },
+                "paths": {}
+            })
+        );
+        assert_eq!(crate::from_reader(json.as_bytes()).unwrap(), spec);
+    }
+
+    #[test]
+    fn info_version_comparison() {
+        let v1 = spec_with_version("1.4.0");
+        let v1_patch = spec_with_version("1.4.1");
+        let v2 = spec_with_version("2.0.0");
+        let dated = spec_with_version("2024-01-01");
+
+        assert_eq!(v1.info.semver(), Some(semver::Version::new(1, 4, 0)));
+        assert_eq!(dated.info.semver(), None);
+
+        assert_eq!(v1_patch.is_newer_than(&v1), Some(true));
+        assert_eq!(v1.is_newer_than(&v1_patch), Some(false));
+        assert_eq!(v1.is_newer_than(&dated), None);
+
+        assert_eq!(v2.is_major_bump_of(&v1), Some(true));
+        assert_eq!(v1_patch.is_major_bump_of(&v1), Some(false));
+    }
+
+    #[test]
+    fn resolve_owned() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths: {}
+components:
+  schemas:
+    Pet: { type: object }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let pet = ObjectOrReference::<Schema>::new_ref("#/components/schemas/Pet");
+        let resolved = spec.resolve_owned(&pet).unwrap();
+
+        let handle = std::thread::spawn(move || resolved.schema_type);
+        assert_eq!(handle.join().unwrap(), Some(SchemaType::Object.into()));
+
+        let missing = ObjectOrReference::<Schema>::new_ref("#/components/schemas/Missing");
+        assert!(matches!(spec.resolve_owned(&missing), Err(Error::Ref(_))));
+    }
+
+    #[test]
+    fn dangling_refs() {
+        let spec = r#"openapi: "3.1.0"
+info:
+  title: Test API
+  version: "0.1"
+paths:
+  /pets:
+    get:
+      parameters:
+        - $ref: '#/components/parameters/limit'
+        - $ref: '#/components/parameters/offset'
+      responses:
+        "200":
+          description: ok
+          content:
+            application/json:
+              schema: { $ref: '#/components/schemas/Pets' }
+              example: { $ref: "not a reference" }
+components:
+  parameters:
+    limit: { name: limit, in: query }
+  schemas:
+    Pets:
+      type: array
+      items: { $ref: '#/components/schemas/Pet' }
+    Owner:
+      type: object
+      properties:
+        example: { $ref: '#/components/responses/Owner' }
+        headers: { $ref: '#/components/schemas/Headers' }
+        content: { items: { $ref: '#/components/schemas/Content' } }
+"#;
+
+        let spec = crate::from_reader(spec.as_bytes()).unwrap();
+
+        let dangling = spec.dangling_refs();
+        let dangling = dangling
+            .iter()
+            .map(|r| (r.location.as_str(), r.ref_path.as_str()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            dangling,
+            vec![
+                (
+                    "#/components/schemas/Owner/properties/content/items/$ref",
+                    "#/components/schemas/Content"
+                ),
+                (
+                    "#/components/schemas/Owner/properties/example/$ref",
+                    "#/components/responses/Owner"
+                ),
+                (
+                    "#/components/schemas/Owner/properties/headers/$ref",
+                    "#/components/schemas/Headers"
+                ),
+                (
+                    "#/components/schemas/Pets/items/$ref",
+                    "#/components/schemas/Pet"
+                ),
+                (
+                    "#/paths/~1pets/get/parameters/1/$ref",
+                    "#/components/parameters/offset"
+                ),
+            ]
+        );
+
+        assert!(matches!(
+            spec.clone_shallow_resolved(),
+            Err(Error::DanglingRefs(refs)) if refs.len() == 5
+        ));
+
+        let spec = Spec::default();
+        assert_eq!(spec.clone_shallow_resolved().unwrap(), spec);
+    }
+
+    #[test]
+    fn json_schema_bundle() {
+        let spec = r#"openapi: "3.1.0"
+paths: {}
+info:
+  title: Test API
+  version: "0.1"
+components:
+  schemas:
+    Pet:
+      oneOf:
+        - $ref: '#/components/schemas/Cat'
+        - $ref: '#/components/schemas/Dog'
+      discriminator:
+        propertyName: kind
+        mapping:
+          cat: '#/components/schemas/Cat'
+          dog: Dog
+    Cat:
+      type: object
+      properties:
+        lives: { type: integer }
+        discriminator: { $ref: '#/components/schemas/Dog' }
+      example:
+        $ref: '#/components/schemas/Dog'
+    Dog:
+      type: object
+      properties:
+        friends:
+          type: array
+          items: { $ref: '#/components/schemas/Dog' }
+"#;
+
+        let spec = crate::from_reader(spec.as_bytes()).unwrap();
+
+        assert_eq!(
+            spec.to_json_schema_bundle(),
+            json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "$defs": {
+                    "Cat": {
+                        "type": "object",
+                        "properties": {
+                            "lives": { "type": "integer" },
+                            "discriminator": { "$ref": "#/$defs/Dog" },
+                        },
+                        "example": { "$ref": "#/components/schemas/Dog" },
+                    },
+                    "Dog": {
+                        "type": "object",
+                        "properties": {
+                            "friends": {
+                                "type": "array",
+                                "items": { "$ref": "#/$defs/Dog" },
+                            },
+                        },
+                    },
+                    "Pet": {
+                        "oneOf": [
+                            { "$ref": "#/$defs/Cat" },
+                            { "$ref": "#/$defs/Dog" },
+                        ],
+                        "discriminator": {
+                            "propertyName": "kind",
+                            "mapping": { "cat": "#/$defs/Cat", "dog": "#/$defs/Dog" },
+                        },
+                    },
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn prefix_paths() {
+        let mut spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /:
+    get:
+      responses: {}
+  /pets/{id}:
+    get:
+      operationId: getPet
+      responses:
+        "200":
+          description: pet
+          links:
+            self:
+              operationRef: "#/paths/~1pets~1{id}/get"
+  /pets/{id}/owner:
+    $ref: "#/paths/~1pets~1{id}"
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        spec.prefix_paths("/v2/").unwrap();
+
+        assert_eq!(
+            spec.paths.keys().collect::<Vec<_>>(),
+            ["/v2", "/v2/pets/{id}", "/v2/pets/{id}/owner"]
+        );
+        assert_eq!(
+            spec.paths["/v2/pets/{id}/owner"].reference.as_deref(),
+            Some("#/paths/~1v2~1pets~1{id}")
+        );
+
+        let doc = serde_json::to_value(&spec).unwrap();
+        assert_eq!(
+            doc["paths"]["/v2/pets/{id}"]["get"]["responses"]["200"]["links"]["self"]
+                ["operationRef"],
+            "#/paths/~1v2~1pets~1{id}/get"
+        );
+
+        let mut spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /: {}
+  "": {}
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+        let before = spec.clone();
+
+        match spec.prefix_paths("v2") {
+            Err(Error::PathCollision { normalized, .. }) => assert_eq!(normalized, "/v2"),
+            res => panic!("unexpected result: {:?}", res),
+        }
+        assert_eq!(spec, before);
+    }
+
+    #[test]
+    fn normalize_paths() {
+        let mut spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  //:
+    get:
+      responses: {}
+  /users//{id}/:
+    get:
+      responses: {}
+  /users/{id}/pets:
+    $ref: "#/paths/~1users~1~1{id}~1"
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            spec.normalize_paths().unwrap(),
+            [
+                ("//".to_owned(), "/".to_owned()),
+                ("/users//{id}/".to_owned(), "/users/{id}".to_owned()),
+            ]
+        );
+        assert_eq!(
+            spec.paths.keys().collect::<Vec<_>>(),
+            ["/", "/users/{id}", "/users/{id}/pets"]
+        );
+        assert_eq!(
+            spec.paths["/users/{id}/pets"].reference.as_deref(),
+            Some("#/paths/~1users~1{id}")
+        );
+        assert!(spec.normalize_paths().unwrap().is_empty());
+
+        spec.paths
+            .insert("/users/{id}/".to_owned(), PathItem::default());
+        let before = spec.clone();
+
+        assert!(matches!(
+            spec.normalize_paths(),
+            Err(Error::PathCollision { .. })
+        ));
+        assert_eq!(spec, before);
+    }
+
+    #[test]
+    fn rename_path() {
+        let mut spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /pets/{id}:
+    get:
+      responses:
+        "200":
+          description: pet
+          links:
+            self:
+              operationRef: "#/paths/~1pets~1{id}/get"
+  /animals:
+    get:
+      responses: {}
+  /files/{name}.json:
+    get:
+      responses: {}
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        spec.rename_path("/pets/{id}", "/v2/pets/{id}").unwrap();
+
+        assert_eq!(
+            spec.paths.keys().collect::<Vec<_>>(),
+            ["/animals", "/files/{name}.json", "/v2/pets/{id}"]
+        );
+        let doc = serde_json::to_value(&spec).unwrap();
+        assert_eq!(
+            doc["paths"]["/v2/pets/{id}"]["get"]["responses"]["200"]["links"]["self"]
+                ["operationRef"],
+            "#/paths/~1v2~1pets~1{id}/get"
+        );
+
+        assert!(matches!(
+            spec.rename_path("/pets/{id}", "/pets"),
+            Err(Error::PathNotFound(_))
+        ));
+        assert!(matches!(
+            spec.rename_path("/v2/pets/{id}", "/pets"),
+            Err(Error::PathTemplateMismatch { .. })
+        ));
+        assert!(matches!(
+            spec.rename_path("/animals", "/v2/pets/{id}"),
+            Err(Error::PathExists(_))
+        ));
+        assert!(matches!(
+            spec.rename_path("/files/{name}.json", "/files/x.json"),
+            Err(Error::PathTemplateMismatch { .. })
+        ));
+        spec.rename_path("/files/{name}.json", "/v2/files/{name}.json")
+            .unwrap();
+    }
+
+    #[test]
+    fn walk_operations_mut() {
+        let mut spec = crate::from_reader(
+            r#"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /pets:
+    get: { responses: {} }
+    post: { responses: {} }
+"#
+            .as_bytes(),
+        )
+        .unwrap();
+
+        spec.walk_operations_mut(|path, method, op| {
+            op.operation_id = Some(format!("{}{}", method, path.replace('/', "_")));
+        });
+
+        let ids = spec
+            .operations()
+            .filter_map(|op| op.operation_id.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, ["GET_pets", "POST_pets"]);
+    }
+
+    #[test]
+    fn operation_ids() {
+        let spec = crate::from_reader(
+            r#"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /pets:
+    post: { operationId: createPet, responses: {} }
+    get: { operationId: listPets, responses: {} }
+  /health:
+    get: { responses: {} }
+"#
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            spec.operation_ids().collect::<Vec<_>>(),
+            ["listPets", "createPet"]
+        );
+
+        let map = spec.operation_id_map();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["createPet"], ("/pets".to_owned(), Method::POST));
+
+        let op = spec.operation_by_id("createPet").unwrap();
+        assert_eq!(op.path_template(), "/pets");
+        assert_eq!(op.method(), Method::POST);
+        assert_eq!(op.display_title(), "createPet");
+        assert!(op.responses.is_empty());
+        assert!(spec.operation_by_id("deletePet").is_none());
+    }
+
+    #[test]
+    fn reference_cycles() {
+        let spec = |components: &str| {
+            let yaml = format!(
+                "openapi: 3.1.0\ninfo: {{ title: Test, version: '1' }}\npaths: {{}}\ncomponents:\n{}",
+                components
+            );
+            crate::from_reader(yaml.as_bytes()).unwrap()
+        };
+
+        let acyclic = spec(
+            r##"
+  schemas:
+    Pet: { properties: { tag: { $ref: "#/components/schemas/Tag" } } }
+    Tag: { type: string }
+"##,
+        );
+        assert_eq!(acyclic.has_reference_cycles(), None);
+
+        let schemas = spec(
+            r##"
+  schemas:
+    Person: { properties: { pet: { $ref: "#/components/schemas/Pet" } } }
+    Pet: { properties: { owner: { $ref: "#/components/schemas/Person" } } }
+"##,
+        );
+        assert_eq!(
+            schemas.has_reference_cycles().unwrap(),
+            [
+                "#/components/schemas/Person",
+                "#/components/schemas/Pet",
+                "#/components/schemas/Person",
+            ]
+        );
+
+        let parameters = spec(
+            r##"
+  parameters:
+    Limit: { $ref: "#/components/parameters/Limit" }
+"##,
+        );
+        assert_eq!(
+            parameters.has_reference_cycles().unwrap(),
+            [
+                "#/components/parameters/Limit",
+                "#/components/parameters/Limit"
+            ]
+        );
+
+        let path_items = spec(
+            r##"
+  pathItems:
+    A: { $ref: "#/components/pathItems/B" }
+    B: { $ref: "#/components/pathItems/A" }
+"##,
+        );
+        assert_eq!(path_items.has_reference_cycles().unwrap().len(), 3);
+    }
 }
@@ -1,10 +1,12 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
-use log::error;
+use http::{Method, StatusCode};
+use log::{error, warn};
 use serde::{Deserialize, Serialize};
 
 use super::{
-    Callback, Error, ExternalDoc, ObjectOrReference, Parameter, RequestBody, Response, Server, Spec,
+    spec_extensions, Callback, Error, ExternalDoc, Header, MediaType, ObjectOrReference, Parameter,
+    PathItem, RequestBody, Response, ResponseKey, SecurityRequirement, Server, Spec,
 };
 
 /// Describes a single API operation on a path.
@@ -92,20 +94,29 @@ pub struct Operation {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub deprecated: Option<bool>,
 
-    // FIXME: Implement
-    // /// A declaration of which security mechanisms can be used for this operation. The list of
-    // /// values includes alternative security requirement objects that can be used. Only one
-    // /// of the security requirement objects need to be satisfied to authorize a request.
-    // /// This definition overrides any declared top-level
-    // /// [`security`](https://github.com/OAI/OpenAPI-Specification/blob/HEAD/versions/3.1.0.md#oasSecurity).
-    // /// To remove a top-level security declaration, an empty array can be used.
-    // pub security: Option<SecurityRequirement>,
+    /// A declaration of which security mechanisms can be used for this operation. The list of
+    /// values includes alternative security requirement objects that can be used. Only one
+    /// of the security requirement objects need to be satisfied to authorize a request.
+    /// This definition overrides any declared top-level
+    /// [`security`](https://github.com/OAI/OpenAPI-Specification/blob/HEAD/versions/3.1.0.md#oasSecurity).
+    /// To remove a top-level security declaration, an empty array can be used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub security: Option<Vec<SecurityRequirement>>,
+
     /// An alternative `server` array to service this operation. If an alternative `server`
     /// object is specified at the Path Item Object or Root level, it will be overridden by
     /// this value.
     #[serde(default)]
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub servers: Vec<Server>,
+
+    /// Specification extensions.
+    ///
+    /// Only "x-" prefixed keys are collected, and the prefix is stripped.
+    ///
+    /// See <https://github.com/OAI/OpenAPI-Specification/blob/HEAD/versions/3.1.0.md#specificationExtensions>.
+    #[serde(flatten, with = "spec_extensions")]
+    pub extensions: BTreeMap<String, serde_json::Value>,
 }
 
 impl Operation {
@@ -117,6 +128,71 @@ impl Operation {
             .map_err(Error::Ref)
     }
 
+    /// Returns the request body media type that applies to a request with the given
+    /// `Content-Type`, along with its declared key. The request body is resolved if it is a
+    /// reference.
+    ///
+    /// See [`RequestBody::media_type`] for how content types are matched. Returns `None` if the
+    /// operation has no request body or does not accept the content type.
+    pub fn request_media_type(
+        &self,
+        content_type: &str,
+        spec: &Spec,
+    ) -> Option<(String, MediaType)> {
+        let body = self
+            .request_body
+            .as_ref()?
+            .resolve(spec)
+            // TODO: find better error solution
+            .map_err(|err| error!("{}", err))
+            .ok()?;
+
+        body.media_type(content_type)
+            .map(|(key, media_type)| (key.to_owned(), media_type.clone()))
+    }
+
+    /// Returns request body examples, keyed by media type, as (example name, value) pairs.
+    ///
+    /// Example `$ref`s are resolved. For media types that declare no examples but do have a
+    /// schema, a single example named `"schema"` is generated from the schema instead.
+    pub fn request_examples(
+        &self,
+        spec: &Spec,
+    ) -> BTreeMap<String, Vec<(String, serde_json::Value)>> {
+        let req_body = match self.request_body.as_ref().map(|oor| oor.resolve(spec)) {
+            Some(Ok(req_body)) => req_body,
+            Some(Err(err)) => {
+                // TODO: find better error solution
+                error!("{}", err);
+                return BTreeMap::new();
+            }
+            None => return BTreeMap::new(),
+        };
+
+        req_body
+            .content
+            .iter()
+            .map(|(media_type, media_spec)| {
+                let mut examples = media_spec
+                    .examples(spec)
+                    .into_iter()
+                    .filter_map(|(name, example)| example.value.map(|val| (name, val)))
+                    .collect::<Vec<_>>();
+
+                if examples.is_empty() && media_spec.schema.is_some() {
+                    match media_spec.schema(spec) {
+                        Ok(schema) => {
+                            examples.push(("schema".to_owned(), schema.generate_example(spec)))
+                        }
+                        Err(err) => error!("{}", err),
+                    }
+                }
+
+                (media_type.clone(), examples)
+            })
+            .collect()
+    }
+
     pub fn responses(&self, spec: &Spec) -> BTreeMap<String, Response> {
         self.responses
             .iter()
@@ -130,6 +206,80 @@ impl Operation {
             .collect()
     }
 
+    /// Returns the resolved response documented for `status`.
+    ///
+    /// The most specific matching response is used: an explicit status code, then its range
+    /// (e.g. `4XX`), then `default`. Responses that can not be resolved are skipped.
+    pub fn response_for_status(&self, status: StatusCode, spec: &Spec) -> Option<Response> {
+        self.responses(spec)
+            .into_iter()
+            .filter_map(|(key, response)| Some((key.parse::<ResponseKey>().ok()?, response)))
+            .filter(|(key, _)| key.matches(status))
+            .min_by_key(|(key, _)| *key)
+            .map(|(_, response)| response)
+    }
+
+    /// Returns the resolved headers of the response documented for `status`.
+    ///
+    /// The response is found as in [`Operation::response_for_status`]. If no response matches,
+    /// or `status` is not a valid status code, no headers are returned.
+    pub fn response_headers(
+        &self,
+        status: u16,
+        spec: &Spec,
+    ) -> Result<BTreeMap<String, Header>, Error> {
+        let response = StatusCode::from_u16(status)
+            .ok()
+            .and_then(|status| self.response_for_status(status, spec));
+
+        response
+            .into_iter()
+            .flat_map(|response| response.headers)
+            .map(|(name, header)| Ok((name, header.resolve(spec)?)))
+            .collect()
+    }
+
+    /// Returns the path items of each callback, keyed by callback name and then runtime
+    /// expression.
+    ///
+    /// See [`Callback::path_items`].
+    pub fn resolved_callbacks(
+        &self,
+        spec: &Spec,
+    ) -> Result<BTreeMap<String, BTreeMap<String, PathItem>>, Error> {
+        self.callbacks
+            .iter()
+            .map(|(name, callback)| Ok((name.clone(), callback.path_items(spec)?)))
+            .collect()
+    }
+
+    /// Returns the keys of the declared responses, from most to least specific.
+    ///
+    /// Invalid keys are skipped.
+    pub fn declared_response_codes(&self) -> Vec<ResponseKey> {
+        let mut keys = self
+            .responses
+            .keys()
+            .filter_map(|key| key.parse().map_err(|err| warn!("{}", err)).ok())
+            .collect::<Vec<_>>();
+
+        keys.sort();
+        keys
+    }
+
+    /// Returns true if a `default` response is declared.
+    pub fn has_default_response(&self) -> bool {
+        self.responses.contains_key("default")
+    }
+
+    /// Returns the media types of all responses' content, resolving referenced responses.
+    pub fn response_content_types(&self, spec: &Spec) -> BTreeSet<String> {
+        self.responses(spec)
+            .into_values()
+            .flat_map(|res| res.content.into_keys())
+            .collect()
+    }
+
     pub fn parameters(&self, spec: &Spec) -> Result<Vec<Parameter>, Error> {
         let params = self
             .parameters
@@ -141,6 +291,76 @@ impl Operation {
         Ok(params)
     }
 
+    /// Returns the parameters that apply to this operation, with references resolved.
+    ///
+    /// These are the parameters of `path_item`, the path item containing this operation, followed
+    /// by this operation's own parameters, which override path item parameters with the same name
+    /// and location. Parameters that can not be resolved are skipped.
+    pub fn effective_parameters(&self, path_item: &PathItem, spec: &Spec) -> Vec<Parameter> {
+        let mut params = path_item.parameters(spec).unwrap_or_default();
+
+        for param in self.parameters(spec).unwrap_or_default() {
+            params.retain(|other| other.name != param.name || other.location != param.location);
+            params.push(param);
+        }
+
+        params
+    }
+
+    /// Returns the [effective parameters](Self::effective_parameters) marked as deprecated.
+    pub fn deprecated_parameters(&self, path_item: &PathItem, spec: &Spec) -> Vec<Parameter> {
+        self.effective_parameters(path_item, spec)
+            .into_iter()
+            .filter(|param| param.deprecated == Some(true))
+            .collect()
+    }
+
+    /// Returns a human readable label for this operation.
+    ///
+    /// Uses the first of `summary`, the first line of `description` or `operationId`, falling
+    /// back to `"{method} {path}"`.
+    pub fn display_title(&self, method: &Method, path: &str) -> String {
+        let description = self
+            .description
+            .as_deref()
+            .and_then(|desc| desc.lines().map(str::trim).find(|line| !line.is_empty()));
+
+        self.summary
+            .as_deref()
+            .filter(|summary| !summary.trim().is_empty())
+            .or(description)
+            .or(self.operation_id.as_deref())
+            .map(ToOwned::to_owned)
+            .unwrap_or_else(|| format!("{} {}", method, path))
+    }
+
+    /// Returns true if repeating a request to this operation, sent with `method`, has the same
+    /// effect as sending it once, so that it is safe to retry.
+    ///
+    /// A boolean `x-idempotent` extension on the operation takes precedence. Otherwise, `GET`,
+    /// `HEAD`, `PUT`, `DELETE`, `OPTIONS` and `TRACE` are idempotent, as defined by
+    /// [RFC 9110](https://www.rfc-editor.org/rfc/rfc9110#name-idempotent-methods), while
+    /// `POST`, `PATCH` and any other method are not.
+    pub fn is_idempotent(&self, method: &Method) -> bool {
+        if let Some(idempotent) = self
+            .extensions
+            .get("idempotent")
+            .and_then(|ext| ext.as_bool())
+        {
+            return idempotent;
+        }
+
+        matches!(
+            *method,
+            Method::GET
+                | Method::HEAD
+                | Method::PUT
+                | Method::DELETE
+                | Method::OPTIONS
+                | Method::TRACE
+        )
+    }
+
     pub fn parameter(&self, search: &str, spec: &Spec) -> Result<Option<Parameter>, Error> {
         let param = self
             .parameters(spec)?
@@ -151,3 +371,363 @@ impl Operation {
         Ok(param)
     }
 }
+
+/// An operation along with the path and method it is declared under.
+///
+/// Returned by lookups such as [`Spec::operations`] and [`Spec::operation_by_id`] so that the
+/// operation's location is not lost. Dereferences to the inner [`Operation`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperationRef<'a> {
+    path: &'a str,
+    method: Method,
+    operation: &'a Operation,
+}
+
+impl<'a> OperationRef<'a> {
+    pub(crate) fn new(path: &'a str, method: Method, operation: &'a Operation) -> Self {
+        Self {
+            path,
+            method,
+            operation,
+        }
+    }
+
+    /// Returns the path template the operation is declared under, e.g. `/pets/{id}`.
+    pub fn path_template(&self) -> &'a str {
+        self.path
+    }
+
+    /// Returns the HTTP method of the operation.
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+
+    /// Returns the inner operation.
+    pub fn operation(&self) -> &'a Operation {
+        self.operation
+    }
+
+    /// Returns the operation's `operationId`, if set.
+    pub fn operation_id(&self) -> Option<&'a str> {
+        self.operation.operation_id.as_deref()
+    }
+
+    /// Returns a human readable label for the operation.
+    ///
+    /// See [`Operation::display_title`].
+    pub fn display_title(&self) -> String {
+        self.operation.display_title(&self.method, self.path)
+    }
+}
+
+impl std::ops::Deref for OperationRef<'_> {
+    type Target = Operation;
+
+    fn deref(&self) -> &Self::Target {
+        self.operation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn resolved_callbacks() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /subscribe:
+    post:
+      callbacks:
+        onEvent:
+          "{$request.body#/callbackUrl}":
+            $ref: "#/components/pathItems/Event"
+        onRemoved: { $ref: "#/components/callbacks/Removed" }
+      responses:
+        "201": { description: subscribed }
+  /loop:
+    post:
+      callbacks:
+        onLoop: { $ref: "#/components/callbacks/Loop" }
+      responses: {}
+components:
+  callbacks:
+    Loop: { $ref: "#/components/callbacks/Loop" }
+    Removed:
+      "{$request.body#/removedUrl}":
+        delete:
+          responses:
+            "200": { description: ok }
+  pathItems:
+    Event:
+      summary: event
+      post:
+        responses:
+          "200": { description: ok }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let op = spec.paths["/subscribe"].post.as_ref().unwrap();
+        let callbacks = op.resolved_callbacks(&spec).unwrap();
+
+        let on_event = &callbacks["onEvent"]["{$request.body#/callbackUrl}"];
+        assert_eq!(on_event.summary.as_deref(), Some("event"));
+        assert!(on_event.post.is_some());
+
+        let on_removed = &callbacks["onRemoved"]["{$request.body#/removedUrl}"];
+        assert!(on_removed.delete.is_some());
+
+        let op = spec.paths["/loop"].post.as_ref().unwrap();
+        match op.resolved_callbacks(&spec) {
+            Err(Error::ReferenceCycle(cycle)) => assert_eq!(
+                cycle,
+                ["#/components/callbacks/Loop", "#/components/callbacks/Loop"]
+            ),
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn response_inventory() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /pets:
+    get:
+      responses:
+        "200":
+          description: pets
+          content:
+            application/json: {}
+        4XX: { $ref: "#/components/responses/Error" }
+        default:
+          description: error
+          content:
+            text/plain: {}
+components:
+  responses:
+    Error:
+      description: error
+      content:
+        application/problem+json: {}
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let op = spec.paths["/pets"].get.as_ref().unwrap();
+
+        assert_eq!(
+            op.declared_response_codes(),
+            [
+                ResponseKey::Status(http::StatusCode::OK),
+                ResponseKey::Range(4),
+                ResponseKey::Default,
+            ]
+        );
+        assert!(op.has_default_response());
+        assert_eq!(
+            op.response_content_types(&spec),
+            BTreeSet::from([
+                "application/json".to_owned(),
+                "application/problem+json".to_owned(),
+                "text/plain".to_owned(),
+            ])
+        );
+    }
+
+    #[test]
+    fn response_headers() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /pets:
+    get:
+      responses:
+        "200":
+          description: pets
+          headers:
+            X-Rate-Limit: { $ref: "#/components/headers/RateLimit" }
+        4XX:
+          description: client error
+          headers:
+            Retry-After: { schema: { type: integer } }
+        default:
+          description: error
+components:
+  headers:
+    RateLimit:
+      description: remaining requests
+      schema: { type: integer }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let op = spec.paths["/pets"].get.as_ref().unwrap();
+
+        let headers = op.response_headers(200, &spec).unwrap();
+        assert_eq!(headers.keys().collect::<Vec<_>>(), ["X-Rate-Limit"]);
+        assert_eq!(
+            headers["X-Rate-Limit"].description.as_deref(),
+            Some("remaining requests")
+        );
+
+        let headers = op.response_headers(429, &spec).unwrap();
+        assert_eq!(headers.keys().collect::<Vec<_>>(), ["Retry-After"]);
+
+        assert!(op.response_headers(500, &spec).unwrap().is_empty());
+        assert!(op.response_headers(1000, &spec).unwrap().is_empty());
+    }
+
+    #[test]
+    fn is_idempotent() {
+        let op = Operation::default();
+        assert!(op.is_idempotent(&Method::GET));
+        assert!(op.is_idempotent(&Method::PUT));
+        assert!(op.is_idempotent(&Method::DELETE));
+        assert!(!op.is_idempotent(&Method::POST));
+        assert!(!op.is_idempotent(&Method::PATCH));
+
+        let op: Operation =
+            serde_json::from_value(serde_json::json!({ "responses": {}, "x-idempotent": true }))
+                .unwrap();
+        assert!(op.is_idempotent(&Method::POST));
+
+        let op: Operation =
+            serde_json::from_value(serde_json::json!({ "responses": {}, "x-idempotent": false }))
+                .unwrap();
+        assert!(!op.is_idempotent(&Method::PUT));
+    }
+
+    #[test]
+    fn display_title() {
+        let op = Operation {
+            description: Some("\nLists all pets.\n\nPaginated.".to_owned()),
+            operation_id: Some("listPets".to_owned()),
+            ..Operation::default()
+        };
+        assert_eq!(op.display_title(&Method::GET, "/pets"), "Lists all pets.");
+
+        let op = Operation {
+            summary: Some("List pets".to_owned()),
+            ..op
+        };
+        assert_eq!(op.display_title(&Method::GET, "/pets"), "List pets");
+
+        let op = Operation {
+            operation_id: Some("listPets".to_owned()),
+            ..Operation::default()
+        };
+        assert_eq!(op.display_title(&Method::GET, "/pets"), "listPets");
+
+        assert_eq!(
+            Operation::default().display_title(&Method::GET, "/pets"),
+            "GET /pets"
+        );
+    }
+
+    #[test]
+    fn request_examples() {
+        let spec = r#"openapi: "3.1.0"
+info:
+  title: Test API
+  version: "0.1"
+paths:
+  /pets:
+    post:
+      requestBody:
+        content:
+          application/json:
+            schema: { $ref: '#/components/schemas/Pet' }
+            examples:
+              cat: { $ref: '#/components/examples/cat' }
+              dog: { value: { name: Rex } }
+          application/x-www-form-urlencoded:
+            schema: { $ref: '#/components/schemas/Pet' }
+      responses: {}
+components:
+  examples:
+    cat:
+      value: { name: Tom }
+  schemas:
+    Pet:
+      type: object
+      properties:
+        name: { type: string }
+        age: { type: integer }
+"#;
+
+        let spec = crate::from_reader(spec.as_bytes()).unwrap();
+        let op = spec.operation(&Method::POST, "/pets").unwrap();
+
+        let examples = op.request_examples(&spec);
+        assert_eq!(
+            examples["application/json"],
+            vec![
+                ("cat".to_owned(), json!({ "name": "Tom" })),
+                ("dog".to_owned(), json!({ "name": "Rex" })),
+            ]
+        );
+        assert_eq!(
+            examples["application/x-www-form-urlencoded"],
+            vec![("schema".to_owned(), json!({ "name": "string", "age": 0 }))]
+        );
+    }
+
+    #[test]
+    fn deprecated_parameters() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /pets:
+    parameters:
+      - { name: limit, in: query, deprecated: true }
+      - { name: offset, in: query, deprecated: true }
+      - $ref: "#/components/parameters/Legacy"
+    get:
+      parameters:
+        - { name: offset, in: query }
+      responses: {}
+components:
+  parameters:
+    Legacy: { name: X-Legacy, in: header, deprecated: true }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let path_item = &spec.paths["/pets"];
+        let op = path_item.get.as_ref().unwrap();
+
+        let names = |params: Vec<Parameter>| {
+            params
+                .into_iter()
+                .map(|param| param.name)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            names(op.effective_parameters(path_item, &spec)),
+            ["limit", "X-Legacy", "offset"]
+        );
+        assert_eq!(
+            names(op.deprecated_parameters(path_item, &spec)),
+            ["limit", "X-Legacy"]
+        );
+    }
+}
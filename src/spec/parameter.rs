@@ -1,6 +1,11 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
-use super::{FromRef, Ref, RefError, RefType, Spec};
+use super::{
+    Error, FromRef, MediaType, MediaTypeExamples, ObjectOrReference, ParameterStyle, Ref, RefError,
+    RefType, Spec,
+};
 use crate::Schema;
 
 // FIXME: Verify against OpenAPI 3.0.1
@@ -24,8 +29,16 @@ pub struct Parameter {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub required: Option<bool>,
 
+    /// The schema defining the type used for the parameter.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub schema: Option<Schema>,
+    pub schema: Option<ObjectOrReference<Schema>>,
+
+    /// A map containing the representations for the parameter. The key is the media type and
+    /// the value describes it. The map MUST only contain one entry. A parameter MUST contain
+    /// either a `schema` property, or a `content` property, but not both.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub content: BTreeMap<String, MediaType>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "uniqueItems")]
@@ -44,6 +57,10 @@ pub struct Parameter {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
 
+    /// Specifies that a parameter is deprecated and SHOULD be transitioned out of usage.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<bool>,
+
     // collectionFormat: ???
     // default: ???
     // maximum ?
@@ -62,14 +79,54 @@ pub struct Parameter {
     /// value. Default values (based on value of in): for `query` - `form`; for `path` - `simple`; for
     /// `header` - `simple`; for cookie - `form`.
     #[serde(skip_serializing_if = "Option::is_none")]
-    style: Option<ParameterStyle>,
+    pub style: Option<ParameterStyle>,
+
+    /// When this is true, parameter values of type `array` or `object` generate separate
+    /// parameters for each value of the array or key-value pair of the map. For other types of
+    /// parameters this property has no effect. When `style` is `form`, the default value is
+    /// `true`. For all other styles, the default value is `false`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explode: Option<bool>,
+
+    /// Example of the parameter's potential value. The `example` field is mutually exclusive
+    /// of the `examples` field.
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub examples: Option<MediaTypeExamples>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-#[serde(rename_all = "camelCase")]
-enum ParameterStyle {
-    Form,
-    Simple,
+impl Parameter {
+    /// Returns the resolved schema describing this parameter's value.
+    ///
+    /// Uses `schema` if present, otherwise the schema of the (first) `content` media type.
+    pub fn effective_schema(&self, spec: &Spec) -> Result<Option<Schema>, Error> {
+        let schema = match self.schema {
+            Some(ref schema) => Some(schema),
+            None => self
+                .content
+                .values()
+                .next()
+                .and_then(|media_type| media_type.schema.as_ref()),
+        };
+
+        schema
+            .map(|schema| schema.resolve(spec))
+            .transpose()
+            .map_err(Error::Ref)
+    }
+
+    /// Returns the default value of this parameter, as declared by its schema.
+    pub fn default_value(&self, spec: &Spec) -> Option<serde_json::Value> {
+        self.effective_schema(spec).ok()??.default
+    }
+
+    /// Returns the allowed values of this parameter, as declared by its schema's `enum`.
+    ///
+    /// Returns `None` if the schema does not restrict the allowed values.
+    pub fn enum_values(&self, spec: &Spec) -> Option<Vec<serde_json::Value>> {
+        let schema = self.effective_schema(spec).ok()??;
+
+        schema.enum_values.filter(|values| !values.is_empty())
+    }
 }
 
 impl FromRef for Parameter {
@@ -91,3 +148,62 @@ impl FromRef for Parameter {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn default_and_enum_values() {
+        let spec = r#"openapi: "3.1.0"
+info:
+  title: Test API
+  version: "0.1"
+paths: {}
+components:
+  parameters:
+    status:
+      name: status
+      in: query
+      schema: { $ref: '#/components/schemas/Status' }
+    filter:
+      name: filter
+      in: query
+      content:
+        application/json:
+          schema: { type: object, default: { all: true } }
+    plain:
+      name: plain
+      in: query
+      schema: { type: string }
+  schemas:
+    Status:
+      type: string
+      enum: [available, sold]
+      default: available
+"#;
+
+        let spec = crate::from_reader(spec.as_bytes()).unwrap();
+        let param = |name: &str| {
+            spec.components.as_ref().unwrap().parameters[name]
+                .resolve(&spec)
+                .unwrap()
+        };
+
+        let status = param("status");
+        assert_eq!(status.default_value(&spec), Some(json!("available")));
+        assert_eq!(
+            status.enum_values(&spec),
+            Some(vec![json!("available"), json!("sold")])
+        );
+
+        let filter = param("filter");
+        assert_eq!(filter.default_value(&spec), Some(json!({ "all": true })));
+        assert_eq!(filter.enum_values(&spec), None);
+
+        let plain = param("plain");
+        assert_eq!(plain.default_value(&spec), None);
+        assert_eq!(plain.enum_values(&spec), None);
+    }
+}
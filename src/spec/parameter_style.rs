@@ -0,0 +1,663 @@
+//! Serialization and deserialization of parameter values according to their [style].
+//!
+//! [style]: https://github.com/OAI/OpenAPI-Specification/blob/HEAD/versions/3.1.0.md#style-values
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use super::{ObjectOrReference, Parameter, SchemaType};
+
+/// Describes how a parameter value is serialized.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ParameterStyle {
+    /// Path-style parameters, e.g. `;color=blue`.
+    Matrix,
+
+    /// Label style parameters, e.g. `.blue`.
+    Label,
+
+    /// Form style parameters, e.g. `color=blue`.
+    Form,
+
+    /// Simple style parameters, e.g. `blue,black`.
+    Simple,
+
+    /// Space separated array values, e.g. `color=blue%20black`.
+    SpaceDelimited,
+
+    /// Pipe separated array values, e.g. `color=blue|black`.
+    PipeDelimited,
+
+    /// Nested objects, e.g. `color[R]=100`.
+    DeepObject,
+}
+
+/// Kinds of values that serialize differently.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ValueKind {
+    Primitive,
+    Array,
+    Object,
+}
+
+/// The still percent-encoded parts of a serialized value.
+enum Parts<'a> {
+    Primitive(&'a str),
+    Items(Vec<&'a str>),
+    Pairs(Vec<(&'a str, &'a str)>),
+}
+
+impl Parameter {
+    /// Returns the declared `style`, or the default for the parameter's location.
+    pub fn effective_style(&self) -> ParameterStyle {
+        match self.style {
+            Some(style) => style,
+            None => match self.location.as_str() {
+                "query" | "cookie" => ParameterStyle::Form,
+                _ => ParameterStyle::Simple,
+            },
+        }
+    }
+
+    /// Returns the declared `explode`, or the default for the parameter's style.
+    pub fn effective_explode(&self) -> bool {
+        self.explode
+            .unwrap_or(self.effective_style() == ParameterStyle::Form)
+    }
+
+    /// Serializes a value the way this parameter's style and explode settings describe.
+    ///
+    /// The result is the raw path segment, query string part or header value, with names and
+    /// values percent-encoded; e.g. `;id=5` for a `matrix` path parameter or
+    /// `tag=a&tag=b` for an exploded `form` query parameter. Nested arrays and objects are
+    /// serialized as JSON.
+    pub fn serialize_value(&self, val: &JsonValue) -> String {
+        let name = encode(&self.name);
+        let explode = self.effective_explode();
+        let style = self.effective_style();
+
+        let items = match val {
+            JsonValue::Array(items) => items.iter().map(primitive).collect::<Vec<_>>(),
+            _ => vec![],
+        };
+
+        let pairs = match val {
+            JsonValue::Object(obj) => obj
+                .iter()
+                .map(|(key, val)| (encode(key), primitive(val)))
+                .collect::<Vec<_>>(),
+            _ => vec![],
+        };
+
+        let flat_pairs = |sep: &str| {
+            pairs
+                .iter()
+                .flat_map(|(key, val)| [key.as_str(), val.as_str()])
+                .collect::<Vec<_>>()
+                .join(sep)
+        };
+
+        let joined_pairs = |sep: &str| {
+            pairs
+                .iter()
+                .map(|(key, val)| format!("{}={}", key, val))
+                .collect::<Vec<_>>()
+                .join(sep)
+        };
+
+        let repeated = |prefix: &str, sep: &str| {
+            items
+                .iter()
+                .map(|item| format!("{}{}={}", prefix, name, item))
+                .collect::<Vec<_>>()
+                .join(sep)
+        };
+
+        match (style, kind(val), explode) {
+            (ParameterStyle::Matrix, ValueKind::Primitive, _) => match primitive(val).as_str() {
+                "" => format!(";{}", name),
+                val => format!(";{}={}", name, val),
+            },
+            (ParameterStyle::Matrix, ValueKind::Array, false) => {
+                format!(";{}={}", name, items.join(","))
+            }
+            (ParameterStyle::Matrix, ValueKind::Array, true) => repeated(";", ""),
+            (ParameterStyle::Matrix, ValueKind::Object, false) => {
+                format!(";{}={}", name, flat_pairs(","))
+            }
+            (ParameterStyle::Matrix, ValueKind::Object, true) => {
+                format!(";{}", joined_pairs(";"))
+            }
+
+            (ParameterStyle::Label, ValueKind::Primitive, _) => format!(".{}", primitive(val)),
+            (ParameterStyle::Label, ValueKind::Array, false) => format!(".{}", items.join(",")),
+            (ParameterStyle::Label, ValueKind::Array, true) => format!(".{}", items.join(".")),
+            (ParameterStyle::Label, ValueKind::Object, false) => format!(".{}", flat_pairs(",")),
+            (ParameterStyle::Label, ValueKind::Object, true) => format!(".{}", joined_pairs(".")),
+
+            (ParameterStyle::Simple, ValueKind::Primitive, _) => primitive(val),
+            (ParameterStyle::Simple, ValueKind::Array, _) => items.join(","),
+            (ParameterStyle::Simple, ValueKind::Object, false) => flat_pairs(","),
+            (ParameterStyle::Simple, ValueKind::Object, true) => joined_pairs(","),
+
+            (ParameterStyle::DeepObject, ValueKind::Object, _) => pairs
+                .iter()
+                .map(|(key, val)| format!("{}[{}]={}", name, key, val))
+                .collect::<Vec<_>>()
+                .join("&"),
+
+            (_, ValueKind::Primitive, _) => format!("{}={}", name, primitive(val)),
+            (_, ValueKind::Array, true) => repeated("", "&"),
+            (_, ValueKind::Array, false) => {
+                format!("{}={}", name, items.join(delimiter(style)))
+            }
+            (ParameterStyle::Form, ValueKind::Object, true) => joined_pairs("&"),
+            (_, ValueKind::Object, _) => format!("{}={}", name, flat_pairs(delimiter(style))),
+        }
+    }
+
+    /// Returns true if `raw_segment` could have been produced by serializing some value with
+    /// this parameter's style and explode settings.
+    ///
+    /// `raw_segment` is the raw (percent-encoded) path segment, query string or header value
+    /// that would be produced by [`Parameter::serialize_value`]. If the parameter has an inline
+    /// schema with a single type, only values of that type are considered; otherwise
+    /// primitives, arrays and objects all are.
+    pub fn matches_serialized(&self, raw_segment: &str) -> bool {
+        self.possible_kinds()
+            .into_iter()
+            .any(|kind| self.matches_kind(raw_segment, kind))
+    }
+
+    /// Deserializes a value serialized with this parameter's style and explode settings.
+    ///
+    /// The inverse of [`Parameter::serialize_value`]: `raw_segment` is split into array items or
+    /// object entries as the style describes, and percent-decoded. The kinds of values considered
+    /// are those of [`Parameter::matches_serialized`], tried as a primitive, an array and an object,
+    /// in that order. Primitive values, including array items and object values, are returned as
+    /// strings, since the serialized form does not record their types. Returns `None` if
+    /// `raw_segment` could not have been produced by this parameter.
+    pub fn deserialize_value(&self, raw_segment: &str) -> Option<JsonValue> {
+        let kind = self
+            .possible_kinds()
+            .into_iter()
+            .find(|kind| self.matches_kind(raw_segment, *kind))?;
+
+        let parts = self.split_kind(raw_segment, kind)?;
+        let string = |val: &str| JsonValue::String(decode(val));
+
+        Some(match parts {
+            Parts::Primitive(val) => string(val),
+            Parts::Items(items) => items.into_iter().map(string).collect(),
+            Parts::Pairs(pairs) => JsonValue::Object(
+                pairs
+                    .into_iter()
+                    .map(|(key, val)| (decode(key), string(val)))
+                    .collect(),
+            ),
+        })
+    }
+
+    /// Splits a raw value that [matches](Self::matches_kind) `kind` into its parts.
+    fn split_kind<'a>(&self, raw: &'a str, kind: ValueKind) -> Option<Parts<'a>> {
+        let name = encode(&self.name);
+        let explode = self.effective_explode();
+        let style = self.effective_style();
+
+        let pairs = |list: &'a str, sep: &str| {
+            list.split(sep)
+                .filter_map(|pair| pair.split_once('='))
+                .collect::<Vec<_>>()
+        };
+        let flat_pairs = |list: &'a str, sep: &str| {
+            let items = list.split(sep).collect::<Vec<_>>();
+
+            items
+                .chunks(2)
+                .map(|pair| (pair[0], pair.get(1).copied().unwrap_or("")))
+                .collect::<Vec<_>>()
+        };
+
+        let parts = match style {
+            ParameterStyle::Matrix => {
+                let raw = raw.strip_prefix(';')?;
+                let values = raw
+                    .split(';')
+                    .map(|part| part.split_once('=').map_or("", |(_, val)| val))
+                    .collect::<Vec<_>>();
+
+                match (kind, explode) {
+                    (ValueKind::Primitive, _) => Parts::Primitive(values[0]),
+                    (ValueKind::Array, false) => Parts::Items(values[0].split(',').collect()),
+                    (ValueKind::Array, true) => Parts::Items(values),
+                    (ValueKind::Object, false) => Parts::Pairs(flat_pairs(values[0], ",")),
+                    (ValueKind::Object, true) => Parts::Pairs(pairs(raw, ";")),
+                }
+            }
+
+            ParameterStyle::Label => {
+                let val = raw.strip_prefix('.')?;
+
+                match (kind, explode) {
+                    (ValueKind::Primitive, _) => Parts::Primitive(val),
+                    (ValueKind::Array, false) => Parts::Items(val.split(',').collect()),
+                    (ValueKind::Array, true) => Parts::Items(val.split('.').collect()),
+                    (ValueKind::Object, false) => Parts::Pairs(flat_pairs(val, ",")),
+                    (ValueKind::Object, true) => Parts::Pairs(pairs(val, ".")),
+                }
+            }
+
+            ParameterStyle::Simple => match (kind, explode) {
+                (ValueKind::Primitive, _) => Parts::Primitive(raw),
+                (ValueKind::Array, _) => Parts::Items(raw.split(',').collect()),
+                (ValueKind::Object, false) => Parts::Pairs(flat_pairs(raw, ",")),
+                (ValueKind::Object, true) => Parts::Pairs(pairs(raw, ",")),
+            },
+
+            ParameterStyle::DeepObject => Parts::Pairs(
+                pairs(raw, "&")
+                    .into_iter()
+                    .map(|(key, val)| (&key[name.len() + 1..key.len() - 1], val))
+                    .collect(),
+            ),
+
+            ParameterStyle::Form
+            | ParameterStyle::SpaceDelimited
+            | ParameterStyle::PipeDelimited => {
+                let pairs = pairs(raw, "&");
+                let single_value = pairs[0].1;
+
+                match (kind, explode) {
+                    (ValueKind::Primitive, _) => Parts::Primitive(single_value),
+                    (ValueKind::Array, false) => {
+                        Parts::Items(single_value.split(delimiter(style)).collect())
+                    }
+                    (ValueKind::Array, true) => {
+                        Parts::Items(pairs.into_iter().map(|(_, val)| val).collect())
+                    }
+                    (ValueKind::Object, false) => {
+                        Parts::Pairs(flat_pairs(single_value, delimiter(style)))
+                    }
+                    (ValueKind::Object, true) => Parts::Pairs(pairs),
+                }
+            }
+        };
+
+        Some(parts)
+    }
+
+    fn possible_kinds(&self) -> Vec<ValueKind> {
+        let schema_type = match self.schema {
+            Some(ObjectOrReference::Object(ref schema)) => schema
+                .schema_type
+                .as_ref()
+                .and_then(|types| types.single_non_null()),
+            _ => None,
+        };
+
+        match schema_type {
+            Some(SchemaType::Array) => vec![ValueKind::Array],
+            Some(SchemaType::Object) => vec![ValueKind::Object],
+            Some(_) => vec![ValueKind::Primitive],
+            None => vec![ValueKind::Primitive, ValueKind::Array, ValueKind::Object],
+        }
+    }
+
+    fn matches_kind(&self, raw: &str, kind: ValueKind) -> bool {
+        let name = encode(&self.name);
+        let explode = self.effective_explode();
+        let style = self.effective_style();
+
+        let has_even_items = |list: &str, sep: &str| list.split(sep).count() % 2 != 1;
+        let all_pairs = |list: &str, sep: &str| {
+            list.split(sep)
+                .all(|pair| matches!(pair.split_once('='), Some((key, _)) if !key.is_empty()))
+        };
+
+        match style {
+            ParameterStyle::Matrix => {
+                let parts = match raw.strip_prefix(';') {
+                    Some(parts) => parts.split(';').collect::<Vec<_>>(),
+                    None => return false,
+                };
+
+                fn named_value<'a>(part: &'a str, name: &str) -> Option<&'a str> {
+                    match part.split_once('=') {
+                        Some((key, val)) => (key == name).then_some(val),
+                        None => (part == name).then_some(""),
+                    }
+                }
+
+                match (kind, explode, parts.as_slice()) {
+                    (ValueKind::Primitive, _, [part]) => {
+                        matches!(named_value(part, &name), Some(val) if !val.contains(','))
+                    }
+                    (ValueKind::Array, false, [part]) => named_value(part, &name).is_some(),
+                    (ValueKind::Array, true, parts) => parts
+                        .iter()
+                        .all(|part| part.contains('=') && named_value(part, &name).is_some()),
+                    (ValueKind::Object, false, [part]) => {
+                        matches!(named_value(part, &name), Some(val) if has_even_items(val, ","))
+                    }
+                    (ValueKind::Object, true, parts) => all_pairs(&parts.join(";"), ";"),
+                    _ => false,
+                }
+            }
+
+            ParameterStyle::Label => {
+                let val = match raw.strip_prefix('.') {
+                    Some(val) => val,
+                    None => return false,
+                };
+
+                match (kind, explode) {
+                    (ValueKind::Primitive, _) => !val.contains(['.', ',']),
+                    (ValueKind::Array, false) => !val.contains('.'),
+                    (ValueKind::Array, true) => !val.contains(','),
+                    (ValueKind::Object, false) => !val.contains('.') && has_even_items(val, ","),
+                    (ValueKind::Object, true) => !val.contains(',') && all_pairs(val, "."),
+                }
+            }
+
+            ParameterStyle::Simple => match (kind, explode) {
+                (ValueKind::Primitive, _) => !raw.contains(','),
+                (ValueKind::Array, _) => true,
+                (ValueKind::Object, false) => has_even_items(raw, ","),
+                (ValueKind::Object, true) => all_pairs(raw, ","),
+            },
+
+            ParameterStyle::DeepObject => {
+                kind == ValueKind::Object
+                    && raw.split('&').all(|pair| {
+                        let key = pair.split_once('=').map(|(key, _)| key);
+
+                        matches!(
+                            key.and_then(|key| key.strip_prefix(name.as_str())),
+                            Some(sub) if sub.len() > 2 && sub.starts_with('[') && sub.ends_with(']')
+                        )
+                    })
+            }
+
+            ParameterStyle::Form
+            | ParameterStyle::SpaceDelimited
+            | ParameterStyle::PipeDelimited => {
+                let pairs = raw
+                    .split('&')
+                    .map(|pair| pair.split_once('='))
+                    .collect::<Option<Vec<_>>>();
+
+                let pairs = match pairs {
+                    Some(pairs) => pairs,
+                    None => return false,
+                };
+
+                let single_value = match pairs.as_slice() {
+                    [(key, val)] if *key == name => Some(*val),
+                    _ => None,
+                };
+
+                match (kind, explode) {
+                    (ValueKind::Primitive, _) => {
+                        matches!(single_value, Some(val) if !val.contains(delimiter(style)))
+                    }
+                    (ValueKind::Array, false) => single_value.is_some(),
+                    (ValueKind::Array, true) => pairs.iter().all(|(key, _)| *key == name),
+                    (ValueKind::Object, false) => {
+                        matches!(single_value, Some(val) if has_even_items(val, delimiter(style)))
+                    }
+                    (ValueKind::Object, true) => {
+                        style == ParameterStyle::Form
+                            && pairs.iter().all(|(key, _)| !key.is_empty())
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn kind(val: &JsonValue) -> ValueKind {
+    match val {
+        JsonValue::Array(_) => ValueKind::Array,
+        JsonValue::Object(_) => ValueKind::Object,
+        _ => ValueKind::Primitive,
+    }
+}
+
+/// Array item delimiter of the query parameter styles.
+fn delimiter(style: ParameterStyle) -> &'static str {
+    match style {
+        ParameterStyle::SpaceDelimited => "%20",
+        ParameterStyle::PipeDelimited => "|",
+        _ => ",",
+    }
+}
+
+/// Serializes a single item value, percent-encoding it.
+fn primitive(val: &JsonValue) -> String {
+    match val {
+        JsonValue::Null => String::new(),
+        JsonValue::String(val) => encode(val),
+        val => encode(&val.to_string()),
+    }
+}
+
+/// Decodes percent-encoded bytes, keeping invalid escapes as they are.
+fn decode(val: &str) -> String {
+    let bytes = val.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+
+    while idx < bytes.len() {
+        let escaped = match bytes.get(idx..idx + 3) {
+            Some([b'%', hi, lo]) => std::str::from_utf8(&[*hi, *lo])
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+            _ => None,
+        };
+
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                idx += 3;
+            }
+            None => {
+                decoded.push(bytes[idx]);
+                idx += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Percent-encodes all but unreserved characters.
+pub(crate) fn encode(val: &str) -> String {
+    val.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            byte => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn param(location: &str, style: Option<ParameterStyle>, explode: Option<bool>) -> Parameter {
+        Parameter {
+            name: "color".to_owned(),
+            location: location.to_owned(),
+            style,
+            explode,
+            ..Parameter::default()
+        }
+    }
+
+    #[test]
+    fn serialize_values() {
+        let primitive = json!("blue");
+        let array = json!(["blue", "black"]);
+        let object = json!({ "R": 100, "G": 200 });
+
+        let cases = [
+            (
+                param("path", Some(ParameterStyle::Matrix), None),
+                &array,
+                ";color=blue,black",
+            ),
+            (
+                param("path", Some(ParameterStyle::Matrix), Some(true)),
+                &array,
+                ";color=blue;color=black",
+            ),
+            (
+                param("path", Some(ParameterStyle::Label), None),
+                &primitive,
+                ".blue",
+            ),
+            (
+                param("path", Some(ParameterStyle::Label), Some(true)),
+                &object,
+                ".G=200.R=100",
+            ),
+            (param("path", None, None), &object, "G,200,R,100"),
+            (param("path", None, Some(true)), &object, "G=200,R=100"),
+            (param("query", None, None), &array, "color=blue&color=black"),
+            (
+                param("query", None, Some(false)),
+                &array,
+                "color=blue,black",
+            ),
+            (param("query", None, None), &object, "G=200&R=100"),
+            (
+                param("query", Some(ParameterStyle::SpaceDelimited), Some(false)),
+                &array,
+                "color=blue%20black",
+            ),
+            (
+                param("query", Some(ParameterStyle::PipeDelimited), Some(false)),
+                &array,
+                "color=blue|black",
+            ),
+            (
+                param("query", Some(ParameterStyle::DeepObject), Some(true)),
+                &object,
+                "color[G]=200&color[R]=100",
+            ),
+            (
+                param("query", None, None),
+                &json!("a b&c"),
+                "color=a%20b%26c",
+            ),
+        ];
+
+        for (param, val, expected) in cases {
+            let serialized = param.serialize_value(val);
+            assert_eq!(serialized, expected, "{:?} {}", param.style, val);
+            assert!(param.matches_serialized(&serialized), "{}", serialized);
+        }
+    }
+
+    #[test]
+    fn deserialize_values() {
+        let typed = |mut param: Parameter, type_: &str| {
+            param.schema = Some(ObjectOrReference::Object(
+                serde_json::from_value(json!({ "type": type_ })).unwrap(),
+            ));
+            param
+        };
+
+        let primitive = json!("a b&c");
+        let array = json!(["blue", "black"]);
+        let object = json!({ "G": "200", "R": "100" });
+
+        let cases = [
+            (
+                param("path", Some(ParameterStyle::Matrix), None),
+                &primitive,
+            ),
+            (param("path", Some(ParameterStyle::Matrix), None), &array),
+            (
+                param("path", Some(ParameterStyle::Matrix), Some(true)),
+                &array,
+            ),
+            (
+                param("path", Some(ParameterStyle::Label), Some(true)),
+                &array,
+            ),
+            (
+                typed(param("path", Some(ParameterStyle::Label), None), "object"),
+                &object,
+            ),
+            (param("path", None, None), &array),
+            (typed(param("path", None, Some(true)), "object"), &object),
+            (param("query", None, None), &primitive),
+            (param("query", None, None), &array),
+            (typed(param("query", None, None), "object"), &object),
+            (
+                param("query", Some(ParameterStyle::PipeDelimited), Some(false)),
+                &array,
+            ),
+            (
+                param("query", Some(ParameterStyle::DeepObject), Some(true)),
+                &object,
+            ),
+        ];
+
+        for (param, val) in cases {
+            let serialized = param.serialize_value(val);
+            assert_eq!(
+                param.deserialize_value(&serialized).as_ref(),
+                Some(val),
+                "{}",
+                serialized
+            );
+        }
+
+        // without a schema, the first matching kind of value is returned
+        assert_eq!(
+            param("path", None, None).deserialize_value("G,200,R,100"),
+            Some(json!(["G", "200", "R", "100"]))
+        );
+        assert_eq!(
+            param("query", None, Some(false)).deserialize_value("size=5"),
+            None
+        );
+    }
+
+    #[test]
+    fn matches_serialized() {
+        let matrix = param("path", Some(ParameterStyle::Matrix), None);
+        assert!(matrix.matches_serialized(";color"));
+        assert!(!matrix.matches_serialized(";size=5"));
+        assert!(!matrix.matches_serialized("color=blue"));
+
+        let label = param("path", Some(ParameterStyle::Label), None);
+        assert!(!label.matches_serialized("blue"));
+
+        let form = param("query", None, Some(false));
+        assert!(form.matches_serialized("color=blue"));
+        assert!(!form.matches_serialized("size=5"));
+        assert!(!form.matches_serialized("color"));
+
+        let deep = param("query", Some(ParameterStyle::DeepObject), Some(true));
+        assert!(!deep.matches_serialized("color=blue"));
+        assert!(!deep.matches_serialized("color[]=blue"));
+
+        // an inline schema restricts the kinds of values considered
+        let mut exploded = param("query", None, None);
+        assert!(exploded.matches_serialized("size=5"));
+
+        exploded.schema = Some(ObjectOrReference::Object(
+            serde_json::from_value(json!({ "type": "array" })).unwrap(),
+        ));
+        assert!(exploded.matches_serialized("color=blue&color=black"));
+        assert!(!exploded.matches_serialized("size=5"));
+    }
+}
@@ -1,7 +1,11 @@
 use http::Method;
+use log::error;
 use serde::{Deserialize, Serialize};
 
-use super::{ObjectOrReference, Operation, Parameter, Server};
+use super::{
+    r#ref, Error, FromRef, ObjectOrReference, Operation, Parameter, Ref, RefError, RefType, Server,
+    Spec,
+};
 
 /// Describes the operations available on a single path.
 ///
@@ -106,4 +110,224 @@ impl PathItem {
 
         methods
     }
+
+    /// Returns this path item's operations for mutation, in the same order as
+    /// [`methods`](Self::methods).
+    pub fn methods_mut(&mut self) -> impl IntoIterator<Item = (Method, &mut Operation)> {
+        let mut methods = vec![];
+
+        macro_rules! push_method {
+            ($field:ident, $method:ident) => {{
+                if let Some(ref mut op) = self.$field {
+                    methods.push((Method::$method, op))
+                }
+            }};
+        }
+
+        push_method!(get, GET);
+        push_method!(put, PUT);
+        push_method!(post, POST);
+        push_method!(delete, DELETE);
+        push_method!(options, OPTIONS);
+        push_method!(head, HEAD);
+        push_method!(patch, PATCH);
+        push_method!(trace, TRACE);
+
+        methods
+    }
+
+    /// Resolves this path item's `$ref`, if it has one.
+    ///
+    /// The `summary` and `description` of this path item take precedence over the referenced
+    /// path item's, matching the override rules of a Reference Object. Path items without a
+    /// `$ref` are returned as-is.
+    ///
+    /// Returns [`Error::ReferenceCycle`] if the references lead back to a path item already
+    /// followed.
+    pub fn resolve(&self, spec: &Spec) -> Result<PathItem, Error> {
+        self.resolve_followed(spec, &mut vec![])
+    }
+
+    /// Resolves this path item like [`PathItem::resolve`], given the `$ref` paths already
+    /// followed.
+    fn resolve_followed(&self, spec: &Spec, followed: &mut Vec<String>) -> Result<PathItem, Error> {
+        let ref_path = match self.reference {
+            Some(ref ref_path) => ref_path,
+            None => return Ok(self.clone()),
+        };
+
+        let target =
+            PathItem::follow(spec, ref_path, followed)?.resolve_followed(spec, followed)?;
+
+        Ok(PathItem {
+            reference: None,
+            summary: self.summary.clone().or(target.summary),
+            description: self.description.clone().or(target.description),
+            ..target
+        })
+    }
+
+    /// Returns the resolved list of parameters applicable to all operations on this path.
+    pub fn parameters(&self, spec: &Spec) -> Result<Vec<Parameter>, Error> {
+        let params = self
+            .parameters
+            .iter()
+            // TODO: find better error solution, maybe vec<result<_>>
+            .filter_map(|oor| oor.resolve(spec).map_err(|err| error!("{}", err)).ok())
+            .collect();
+
+        Ok(params)
+    }
+
+    /// Returns the path item component at `ref_path`, following references between components.
+    fn follow<'a>(
+        spec: &'a Spec,
+        ref_path: &str,
+        followed: &mut Vec<String>,
+    ) -> Result<&'a PathItem, Error> {
+        r#ref::follow_ref(followed, ref_path).map_err(Error::ReferenceCycle)?;
+
+        match PathItem::component(spec, ref_path)? {
+            ObjectOrReference::Object(path_item) => Ok(path_item),
+            ObjectOrReference::Ref { ref_path, .. } => PathItem::follow(spec, ref_path, followed),
+        }
+    }
+
+    fn component<'a>(
+        spec: &'a Spec,
+        path: &str,
+    ) -> Result<&'a ObjectOrReference<PathItem>, RefError> {
+        let refpath = path.parse::<Ref>()?;
+
+        match refpath.kind {
+            RefType::PathItem => spec
+                .components
+                .as_ref()
+                .and_then(|cs| cs.path_items.get(&refpath.name))
+                .ok_or_else(|| RefError::Unresolvable(path.to_owned())),
+
+            typ => Err(RefError::MismatchedType(typ, RefType::PathItem)),
+        }
+    }
+}
+
+impl FromRef for PathItem {
+    /// Looks up a path item component, following references between components. A cycle of
+    /// such references is unresolvable.
+    fn from_ref(spec: &Spec, path: &str) -> Result<Self, RefError> {
+        match PathItem::follow(spec, path, &mut vec![]) {
+            Ok(path_item) => Ok(path_item.clone()),
+            Err(Error::Ref(err)) => Err(err),
+            Err(_) => Err(RefError::Unresolvable(path.to_owned())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const SPEC: &str = r#"openapi: "3.1.0"
+info:
+  title: Test API
+  version: "0.1"
+paths:
+  /pets:
+    $ref: '#/components/pathItems/Pets'
+    summary: Local summary
+components:
+  parameters:
+    limit:
+      name: limit
+      in: query
+  pathItems:
+    Pets:
+      summary: Shared summary
+      description: Shared description
+      parameters:
+        - $ref: '#/components/parameters/limit'
+      get:
+        operationId: listPets
+        responses: {}
+"#;
+
+    #[test]
+    fn resolve_ref_path_item() {
+        let spec = crate::from_reader(SPEC.as_bytes()).unwrap();
+
+        let item = spec.resolve_path_item("/pets").unwrap();
+        assert_eq!(item.reference, None);
+        assert_eq!(item.summary.as_deref(), Some("Local summary"));
+        assert_eq!(item.description.as_deref(), Some("Shared description"));
+        assert_eq!(item.get.unwrap().operation_id.as_deref(), Some("listPets"));
+
+        let item = spec.resolve_path_item("/pets").unwrap();
+        let params = item.parameters(&spec).unwrap();
+        assert_eq!(params.len(), 1);
+        assert_eq!(params[0].name, "limit");
+
+        assert!(matches!(
+            spec.resolve_path_item("/nope"),
+            Err(Error::PathNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn ref_path_item_cycles() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /self: { $ref: "#/components/pathItems/Self" }
+  /loop: { $ref: "#/components/pathItems/A" }
+components:
+  pathItems:
+    Self: { $ref: "#/components/pathItems/Self" }
+    A: { $ref: "#/components/pathItems/B", summary: A }
+    B: { $ref: "#/components/pathItems/A", summary: B }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        match spec.resolve_path_item("/self") {
+            Err(Error::ReferenceCycle(cycle)) => assert_eq!(
+                cycle,
+                ["#/components/pathItems/Self", "#/components/pathItems/Self"]
+            ),
+            res => panic!("unexpected result: {:?}", res),
+        }
+
+        match spec.resolve_path_item("/loop") {
+            Err(Error::ReferenceCycle(cycle)) => assert_eq!(
+                cycle,
+                [
+                    "#/components/pathItems/A",
+                    "#/components/pathItems/B",
+                    "#/components/pathItems/A"
+                ]
+            ),
+            res => panic!("unexpected result: {:?}", res),
+        }
+
+        assert_eq!(
+            PathItem::from_ref(&spec, "#/components/pathItems/Self"),
+            Err(RefError::Unresolvable(
+                "#/components/pathItems/Self".to_owned()
+            ))
+        );
+    }
+
+    #[test]
+    fn ref_path_item_round_trip() {
+        let spec = crate::from_reader(SPEC.as_bytes()).unwrap();
+
+        let yaml: serde_yaml::Value = serde_yaml::from_str(SPEC).unwrap();
+        let expected: serde_json::Value = serde_yaml::from_value(yaml).unwrap();
+
+        assert_eq!(serde_json::to_value(&spec).unwrap(), expected);
+    }
 }
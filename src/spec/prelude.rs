@@ -0,0 +1,14 @@
+//! Commonly used spec types.
+//!
+//! ```
+//! use oas3::spec::prelude::*;
+//!
+//! fn operation_count(spec: &Spec) -> usize {
+//!     spec.operations().count()
+//! }
+//! ```
+
+pub use super::{
+    Components, FromRef, Header, MediaType, ObjectOrReference, Operation, Parameter, PathItem,
+    RequestBody, Response, Schema, SchemaType, SecurityScheme, Server, Spec,
+};
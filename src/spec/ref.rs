@@ -1,4 +1,7 @@
-use std::str::FromStr;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    str::FromStr,
+};
 
 use derive_more::{Display, Error};
 use log::trace;
@@ -7,6 +10,7 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 
 use super::Spec;
+use crate::json_pointer::pointer;
 
 static RE_REF: Lazy<Regex> = Lazy::new(|| {
     Regex::new("^(?P<source>[^#]*)#/components/(?P<type>[^/]+)/(?P<name>.+)$").unwrap()
@@ -18,6 +22,14 @@ pub enum ObjectOrReference<T> {
     Ref {
         #[serde(rename = "$ref")]
         ref_path: String,
+
+        /// A summary overriding that of the referenced component (OpenAPI 3.1).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        summary: Option<String>,
+
+        /// A description overriding that of the referenced component (OpenAPI 3.1).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
     },
     Object(T),
 }
@@ -29,11 +41,28 @@ where
     pub fn resolve(&self, spec: &Spec) -> Result<T, RefError> {
         match self {
             Self::Object(component) => Ok(component.clone()),
-            Self::Ref { ref_path } => T::from_ref(spec, ref_path),
+            Self::Ref { ref_path, .. } => T::from_ref(spec, ref_path),
         }
     }
 }
 
+impl<T> ObjectOrReference<T> {
+    /// Creates a reference to `ref_path` without a summary or description.
+    pub fn new_ref(ref_path: impl Into<String>) -> Self {
+        Self::Ref {
+            ref_path: ref_path.into(),
+            summary: None,
+            description: None,
+        }
+    }
+}
+
+impl<T> From<T> for ObjectOrReference<T> {
+    fn from(obj: T) -> Self {
+        Self::Object(obj)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Display, Error)]
 pub enum RefError {
     #[display(fmt = "Invalid type: {}", _0)]
@@ -58,6 +87,7 @@ pub enum RefType {
     SecurityScheme,
     Link,
     Callback,
+    PathItem,
 }
 
 impl FromStr for RefType {
@@ -74,6 +104,7 @@ impl FromStr for RefType {
             "securitySchemes" => Self::SecurityScheme,
             "links" => Self::Link,
             "callbacks" => Self::Callback,
+            "pathItems" => Self::PathItem,
             typ => return Err(RefError::InvalidType(typ.to_owned())),
         })
     }
@@ -90,7 +121,9 @@ impl FromStr for Ref {
     type Err = RefError;
 
     fn from_str(path: &str) -> Result<Self, Self::Err> {
-        let parts = RE_REF.captures(path).unwrap();
+        let parts = RE_REF
+            .captures(path)
+            .ok_or_else(|| RefError::Unresolvable(path.to_owned()))?;
 
         trace!("creating Ref: {}/{}", &parts["type"], &parts["name"]);
 
@@ -105,3 +138,414 @@ impl FromStr for Ref {
 pub trait FromRef: Clone {
     fn from_ref(spec: &Spec, path: &str) -> Result<Self, RefError>;
 }
+
+/// A `$ref` that could not be resolved.
+#[derive(Debug, Clone, PartialEq, Display)]
+#[display(fmt = "{} at {}", error, location)]
+pub struct DanglingRef {
+    /// JSON pointer to the location of the `$ref` in the spec.
+    pub location: String,
+
+    /// The unresolvable reference path.
+    pub ref_path: String,
+
+    /// Why the reference could not be resolved.
+    pub error: RefError,
+}
+
+impl RefType {
+    /// Checks that a reference of this type resolves, without keeping the result.
+    pub(crate) fn check(self, spec: &Spec, path: &str) -> Result<(), RefError> {
+        use super::{
+            Callback, Example, Header, Link, Parameter, PathItem, RequestBody, Response, Schema,
+            SecurityScheme,
+        };
+
+        match self {
+            RefType::Schema => Schema::from_ref(spec, path).map(drop),
+            RefType::Response => Response::from_ref(spec, path).map(drop),
+            RefType::Parameter => Parameter::from_ref(spec, path).map(drop),
+            RefType::Example => Example::from_ref(spec, path).map(drop),
+            RefType::RequestBody => RequestBody::from_ref(spec, path).map(drop),
+            RefType::Header => Header::from_ref(spec, path).map(drop),
+            RefType::SecurityScheme => SecurityScheme::from_ref(spec, path).map(drop),
+            RefType::Link => Link::from_ref(spec, path).map(drop),
+            RefType::Callback => Callback::from_ref(spec, path).map(drop),
+            RefType::PathItem => PathItem::from_ref(spec, path).map(drop),
+        }
+    }
+}
+
+/// What a node of a serialized spec holds, which decides where `$ref`s can appear within it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Node {
+    /// An OpenAPI object, such as an operation, a response or the document root.
+    Object,
+
+    /// A map or list of OpenAPI objects with user-chosen keys, e.g. `paths` or `content`.
+    Objects,
+
+    /// A schema.
+    Schema,
+
+    /// A map or list of schemas, e.g. `properties` or `allOf`.
+    Schemas,
+
+    /// Literal data, such as examples, defaults and extensions.
+    Literal,
+}
+
+impl Node {
+    /// Returns what the value at `key` within a node of this kind holds.
+    ///
+    /// The key alone is only meaningful within objects and schemas; the entries of maps are
+    /// always objects or schemas, whatever their names, so e.g. a schema property named
+    /// `content` is still a schema.
+    fn child(self, key: &str) -> Node {
+        match self {
+            Node::Object => match key {
+                "schema" => Node::Schema,
+                "schemas" => Node::Schemas,
+                "example" | "value" | "default" | "enum" | "const" => Node::Literal,
+                "paths" | "webhooks" | "responses" | "parameters" | "examples"
+                | "requestBodies" | "headers" | "securitySchemes" | "links" | "callbacks"
+                | "pathItems" | "content" | "encoding" | "variables" => Node::Objects,
+                key if key.starts_with("x-") => Node::Literal,
+                _ => Node::Object,
+            },
+
+            Node::Objects => Node::Object,
+
+            Node::Schema => match key {
+                "properties" | "patternProperties" | "dependentSchemas" | "$defs"
+                | "definitions" | "allOf" | "anyOf" | "oneOf" | "prefixItems" => Node::Schemas,
+                "items"
+                | "additionalItems"
+                | "additionalProperties"
+                | "not"
+                | "contains"
+                | "if"
+                | "then"
+                | "else"
+                | "propertyNames"
+                | "unevaluatedItems"
+                | "unevaluatedProperties"
+                | "contentSchema" => Node::Schema,
+                // the schemas of an `items` array
+                key if key.parse::<usize>().is_ok() => Node::Schema,
+                _ => Node::Literal,
+            },
+
+            Node::Schemas => Node::Schema,
+
+            Node::Literal => Node::Literal,
+        }
+    }
+
+    /// Returns what the value at the local JSON pointer `location` of a spec holds.
+    fn at(location: &str) -> Node {
+        location
+            .trim_start_matches('#')
+            .split('/')
+            .skip(1)
+            .fold(Node::Object, |node, segment| {
+                node.child(&crate::json_pointer::unescape(segment))
+            })
+    }
+
+    /// Returns true if a `$ref` key within a node of this kind is a reference, rather than a
+    /// name.
+    fn has_refs(self) -> bool {
+        matches!(self, Node::Object | Node::Schema)
+    }
+}
+
+/// Finds all `$ref`s in a serialized spec, returning their JSON pointer locations and reference
+/// paths.
+///
+/// Literal data, such as examples, defaults and extensions, is skipped.
+pub(crate) fn find_refs(val: &serde_json::Value) -> Vec<(String, String)> {
+    find_refs_at(val, "#")
+}
+
+/// Finds all `$ref`s in part of a serialized spec, given the JSON pointer `location` of that
+/// part, e.g. `#/components/schemas/Pet`.
+///
+/// Returned locations include `location`. See [`find_refs`].
+pub(crate) fn find_refs_at(val: &serde_json::Value, location: &str) -> Vec<(String, String)> {
+    fn walk(
+        val: &serde_json::Value,
+        node: Node,
+        segments: &mut Vec<String>,
+        refs: &mut Vec<(String, String)>,
+    ) {
+        match val {
+            serde_json::Value::Object(obj) => {
+                for (key, val) in obj {
+                    segments.push(key.clone());
+
+                    match val {
+                        serde_json::Value::String(ref_path) if key == "$ref" && node.has_refs() => {
+                            refs.push((pointer(&*segments), ref_path.clone()))
+                        }
+                        val => walk(val, node.child(key), segments, refs),
+                    }
+
+                    segments.pop();
+                }
+            }
+
+            serde_json::Value::Array(items) => {
+                for (idx, item) in items.iter().enumerate() {
+                    let idx = idx.to_string();
+                    let child = node.child(&idx);
+                    segments.push(idx);
+                    walk(item, child, segments, refs);
+                    segments.pop();
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    let node = Node::at(location);
+
+    if node == Node::Literal {
+        return vec![];
+    }
+
+    let mut segments = location
+        .trim_start_matches('#')
+        .split('/')
+        .skip(1)
+        .map(crate::json_pointer::unescape)
+        .collect();
+
+    let mut refs = vec![];
+    walk(val, node, &mut segments, &mut refs);
+    refs
+}
+
+/// Rewrites the `$ref`s and link `operationRef`s in a serialized spec for which `rewrite`
+/// returns a replacement, returning true if any were changed.
+///
+/// Literal data is skipped in the same way as in [`find_refs`].
+pub(crate) fn rewrite_refs(
+    val: &mut serde_json::Value,
+    rewrite: &mut dyn FnMut(&str) -> Option<String>,
+) -> bool {
+    rewrite_refs_at(val, "#", rewrite)
+}
+
+/// Rewrites the `$ref`s and link `operationRef`s in part of a serialized spec, given the JSON
+/// pointer `location` of that part. See [`rewrite_refs`].
+pub(crate) fn rewrite_refs_at(
+    val: &mut serde_json::Value,
+    location: &str,
+    rewrite: &mut dyn FnMut(&str) -> Option<String>,
+) -> bool {
+    fn walk(
+        val: &mut serde_json::Value,
+        node: Node,
+        rewrite: &mut dyn FnMut(&str) -> Option<String>,
+    ) -> bool {
+        let mut changed = false;
+
+        match val {
+            serde_json::Value::Object(obj) => {
+                for (key, val) in obj.iter_mut() {
+                    match val {
+                        serde_json::Value::String(ref_path)
+                            if node.has_refs()
+                                && (key == "$ref"
+                                    || (key == "operationRef" && node == Node::Object)) =>
+                        {
+                            if let Some(new_path) = rewrite(ref_path) {
+                                *ref_path = new_path;
+                                changed = true;
+                            }
+                        }
+                        val => changed |= walk(val, node.child(key), rewrite),
+                    }
+                }
+            }
+
+            serde_json::Value::Array(items) => {
+                for (idx, item) in items.iter_mut().enumerate() {
+                    changed |= walk(item, node.child(&idx.to_string()), rewrite);
+                }
+            }
+
+            _ => {}
+        }
+
+        changed
+    }
+
+    match Node::at(location) {
+        Node::Literal => false,
+        node => walk(val, node, rewrite),
+    }
+}
+
+/// Returns the JSON pointers and values of the schema objects in part of a serialized spec,
+/// given the JSON pointer `location` of that part, each before its subschemas.
+///
+/// Literal data is skipped in the same way as in [`find_refs`].
+pub(crate) fn find_schemas_at<'a>(
+    val: &'a serde_json::Value,
+    location: &str,
+) -> Vec<(String, &'a serde_json::Map<String, serde_json::Value>)> {
+    fn walk<'a>(
+        val: &'a serde_json::Value,
+        node: Node,
+        location: String,
+        schemas: &mut Vec<(String, &'a serde_json::Map<String, serde_json::Value>)>,
+    ) {
+        match val {
+            serde_json::Value::Object(obj) => {
+                if node == Node::Schema {
+                    schemas.push((location.clone(), obj));
+                }
+
+                for (key, val) in obj {
+                    let child = format!("{}/{}", location, crate::json_pointer::escape(key));
+                    walk(val, node.child(key), child, schemas);
+                }
+            }
+
+            serde_json::Value::Array(items) => {
+                for (idx, item) in items.iter().enumerate() {
+                    let idx = idx.to_string();
+                    walk(
+                        item,
+                        node.child(&idx),
+                        format!("{}/{}", location, idx),
+                        schemas,
+                    );
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    let mut schemas = vec![];
+    walk(val, Node::at(location), location.to_owned(), &mut schemas);
+    schemas
+}
+
+/// Calls `f` with each schema object in part of a serialized spec, given the JSON pointer
+/// `location` of that part, before any of its subschemas.
+///
+/// Literal data is skipped in the same way as in [`find_refs`].
+pub(crate) fn for_each_schema_at(
+    val: &mut serde_json::Value,
+    location: &str,
+    f: &mut dyn FnMut(&mut serde_json::Map<String, serde_json::Value>),
+) {
+    fn walk(
+        val: &mut serde_json::Value,
+        node: Node,
+        f: &mut dyn FnMut(&mut serde_json::Map<String, serde_json::Value>),
+    ) {
+        match val {
+            serde_json::Value::Object(obj) => {
+                if node == Node::Schema {
+                    f(obj);
+                }
+
+                for (key, val) in obj.iter_mut() {
+                    walk(val, node.child(key), f);
+                }
+            }
+
+            serde_json::Value::Array(items) => {
+                for (idx, item) in items.iter_mut().enumerate() {
+                    walk(item, node.child(&idx.to_string()), f);
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    walk(val, Node::at(location), f)
+}
+
+/// Returns the pointer to the component or path item containing a local JSON pointer, e.g.
+/// `#/components/schemas/Pet` for `#/components/schemas/Pet/properties/name`.
+///
+/// Returns `None` for external references and pointers outside of those sections.
+pub(crate) fn containing_node(ptr: &str) -> Option<String> {
+    let segments = ptr.strip_prefix("#/")?.split('/').collect::<Vec<_>>();
+
+    let len = match segments.first() {
+        Some(&"components") => 3,
+        Some(&"paths") | Some(&"webhooks") => 2,
+        _ => return None,
+    };
+
+    if segments.len() < len {
+        return None;
+    }
+
+    Some(format!("#/{}", segments[..len].join("/")))
+}
+
+/// Finds a cycle in a directed graph, given as the set of successors of each node.
+///
+/// The cycle is returned as the nodes followed, starting and ending with the same node.
+pub(crate) fn find_cycle(edges: &BTreeMap<String, BTreeSet<String>>) -> Option<Vec<String>> {
+    fn visit(
+        node: &str,
+        edges: &BTreeMap<String, BTreeSet<String>>,
+        stack: &mut Vec<String>,
+        acyclic: &mut BTreeSet<String>,
+    ) -> Option<Vec<String>> {
+        if let Some(pos) = stack.iter().position(|n| n == node) {
+            let mut cycle = stack[pos..].to_vec();
+            cycle.push(node.to_owned());
+            return Some(cycle);
+        }
+
+        if acyclic.contains(node) {
+            return None;
+        }
+
+        stack.push(node.to_owned());
+
+        for next in edges.get(node).into_iter().flatten() {
+            if let Some(cycle) = visit(next, edges, stack, acyclic) {
+                return Some(cycle);
+            }
+        }
+
+        stack.pop();
+        acyclic.insert(node.to_owned());
+
+        None
+    }
+
+    let mut acyclic = BTreeSet::new();
+
+    edges
+        .keys()
+        .find_map(|node| visit(node, edges, &mut vec![], &mut acyclic))
+}
+
+/// Records that `ref_path` is being followed.
+///
+/// Returns the reference cycle if `ref_path` was already followed, as the `$ref` paths from its
+/// first occurrence up to and including `ref_path` again.
+pub(crate) fn follow_ref(followed: &mut Vec<String>, ref_path: &str) -> Result<(), Vec<String>> {
+    if let Some(pos) = followed.iter().position(|path| path == ref_path) {
+        let mut cycle = followed[pos..].to_vec();
+        cycle.push(ref_path.to_owned());
+        return Err(cycle);
+    }
+
+    followed.push(ref_path.to_owned());
+    Ok(())
+}
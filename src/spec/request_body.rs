@@ -2,7 +2,7 @@ use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
-use super::{FromRef, MediaType, Ref, RefError, RefType, Spec};
+use super::{media_type_essence, FromRef, MediaType, Ref, RefError, RefType, Spec};
 
 /// Describes a single request body.
 ///
@@ -43,3 +43,73 @@ impl FromRef for RequestBody {
         }
     }
 }
+
+impl RequestBody {
+    /// Returns the declared media type that applies to a request with the given `Content-Type`.
+    ///
+    /// The most specific match wins: an exact match, then a declared `type/suffix` for a
+    /// content type with a structured syntax suffix (e.g. `application/json` for
+    /// `application/merge-patch+json`), then `type/*`, then `*/*`. Parameters like `charset`
+    /// and case are ignored. Returns `None` if no declared media type matches, in which case a
+    /// server would respond with `415 Unsupported Media Type`.
+    pub fn media_type(&self, content_type: &str) -> Option<(&str, &MediaType)> {
+        let incoming = media_type_essence(content_type);
+        let (typ, subtype) = incoming.split_once('/')?;
+        let suffix = subtype.rsplit_once('+').map(|(_, suffix)| suffix);
+
+        self.content
+            .iter()
+            .filter_map(|(declared, media_type)| {
+                let specificity = match media_type_essence(declared).split_once('/')? {
+                    (t, s) if t == typ && s == subtype => 4,
+                    (t, s) if t == typ && Some(s) == suffix => 3,
+                    (t, "*") if t == typ => 2,
+                    ("*", "*") => 1,
+                    _ => return None,
+                };
+
+                Some((specificity, declared.as_str(), media_type))
+            })
+            .max_by_key(|(specificity, _, _)| *specificity)
+            .map(|(_, declared, media_type)| (declared, media_type))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn media_type_negotiation() {
+        let body = RequestBody {
+            content: ["application/json", "application/*", "text/plain", "*/*"]
+                .into_iter()
+                .map(|media_type| (media_type.to_owned(), MediaType::default()))
+                .collect(),
+            ..RequestBody::default()
+        };
+
+        let matched = |content_type| body.media_type(content_type).map(|(key, _)| key);
+
+        assert_eq!(matched("application/json"), Some("application/json"));
+        assert_eq!(
+            matched("Application/JSON; charset=utf-8"),
+            Some("application/json")
+        );
+        assert_eq!(
+            matched("application/merge-patch+json"),
+            Some("application/json")
+        );
+        assert_eq!(matched("application/xml"), Some("application/*"));
+        assert_eq!(matched("text/plain"), Some("text/plain"));
+        assert_eq!(matched("image/png"), Some("*/*"));
+        assert_eq!(matched("garbage"), None);
+
+        let body = RequestBody {
+            content: BTreeMap::from([("application/xml".to_owned(), MediaType::default())]),
+            ..RequestBody::default()
+        };
+        assert!(body.media_type("application/atom+xml").is_some());
+        assert!(body.media_type("application/json").is_none());
+    }
+}
@@ -1,8 +1,66 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, fmt, str::FromStr};
 
+use http::StatusCode;
 use serde::{Deserialize, Serialize};
 
-use super::{FromRef, Header, Link, MediaType, ObjectOrReference, Ref, RefError, RefType, Spec};
+use super::{
+    Error, FromRef, Header, Link, MediaType, ObjectOrReference, Ref, RefError, RefType, Spec,
+};
+
+/// A key of an operation's `responses` map.
+///
+/// Keys are ordered from most to least specific: explicit status codes, then ranges, then
+/// `default`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ResponseKey {
+    /// An explicit status code, e.g. `200`.
+    Status(StatusCode),
+
+    /// A range of status codes by their first digit, e.g. `2` for `2XX`.
+    Range(u8),
+
+    /// The `default` response, covering all codes not declared individually.
+    Default,
+}
+
+impl ResponseKey {
+    /// Returns true if a response with the given status is described by this key.
+    pub fn matches(&self, status: StatusCode) -> bool {
+        match self {
+            ResponseKey::Status(code) => *code == status,
+            ResponseKey::Range(class) => status.as_u16() / 100 == u16::from(*class),
+            ResponseKey::Default => true,
+        }
+    }
+}
+
+impl FromStr for ResponseKey {
+    type Err = Error;
+
+    fn from_str(key: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidResponseKey(key.to_owned());
+
+        if key == "default" {
+            return Ok(ResponseKey::Default);
+        }
+
+        match key.as_bytes() {
+            [class @ b'1'..=b'5', b'X' | b'x', b'X' | b'x'] => Ok(ResponseKey::Range(class - b'0')),
+            [b'1'..=b'5', _, _] => key.parse().map(ResponseKey::Status).map_err(|_| invalid()),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for ResponseKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResponseKey::Status(code) => write!(f, "{}", code.as_u16()),
+            ResponseKey::Range(class) => write!(f, "{}XX", class),
+            ResponseKey::Default => f.write_str("default"),
+        }
+    }
+}
 
 /// Describes a single response from an API Operation, including design-time, static `links`
 /// to operations based on the response.
@@ -55,3 +113,30 @@ impl FromRef for Response {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn response_keys() {
+        let key = "201".parse::<ResponseKey>().unwrap();
+        assert_eq!(key, ResponseKey::Status(StatusCode::CREATED));
+        assert!(key.matches(StatusCode::CREATED));
+        assert!(!key.matches(StatusCode::OK));
+
+        let key = "2XX".parse::<ResponseKey>().unwrap();
+        assert_eq!(key, ResponseKey::Range(2));
+        assert_eq!(key.to_string(), "2XX");
+        assert!(key.matches(StatusCode::NO_CONTENT));
+        assert!(!key.matches(StatusCode::NOT_FOUND));
+
+        assert_eq!(
+            "default".parse::<ResponseKey>().unwrap(),
+            ResponseKey::Default
+        );
+        assert!("6XX".parse::<ResponseKey>().is_err());
+        assert!("20".parse::<ResponseKey>().is_err());
+        assert!("2+0".parse::<ResponseKey>().is_err());
+    }
+}
@@ -1,11 +1,17 @@
 //! Schema specification for [OpenAPI 3.0.1](https://github.com/OAI/OpenAPI-Specification/blob/HEAD/versions/3.1.0.md)
 
-use std::collections::BTreeMap;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
 
 use derive_more::{Display, Error};
+use log::warn;
 use serde::{Deserialize, Serialize};
 
-use crate::spec::{FromRef, ObjectOrReference, Ref, RefError, RefType, Spec};
+use crate::spec::{
+    spec_extensions, Discriminator, FromRef, ObjectOrReference, Ref, RefError, RefType, Spec,
+};
 
 /// Schema Errors
 #[derive(Debug, Clone, PartialEq, Display, Error)]
@@ -18,6 +24,12 @@ pub enum Error {
 
     #[display(fmt = "Required fields specified on a non-object schema")]
     RequiredSpecifiedOnNonObject,
+
+    #[display(fmt = "Enum value {} can not be deserialized: {}", value, reason)]
+    InvalidEnumValue {
+        value: serde_json::Value,
+        reason: String,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -29,6 +41,117 @@ pub enum Type {
     String,
     Array,
     Object,
+    Null,
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(type_name(*self))
+    }
+}
+
+/// The value of a schema's `type` keyword.
+///
+/// OpenAPI 3.0 only allows a single type. OpenAPI 3.1 also allows a list of types, which is how
+/// nullable schemas are written there (e.g. `["string", "null"]`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TypeSet {
+    Single(Type),
+    Multiple(Vec<Type>),
+}
+
+impl TypeSet {
+    /// Returns all types in this set.
+    pub fn types(&self) -> &[Type] {
+        match self {
+            TypeSet::Single(type_) => std::slice::from_ref(type_),
+            TypeSet::Multiple(types) => types,
+        }
+    }
+
+    /// Returns true if `type_` is part of this set.
+    pub fn contains(&self, type_: Type) -> bool {
+        self.types().contains(&type_)
+    }
+
+    /// Returns the only non-null type of this set, if there is exactly one.
+    pub fn single_non_null(&self) -> Option<Type> {
+        let mut non_null = self.types().iter().filter(|type_| **type_ != Type::Null);
+
+        match (non_null.next(), non_null.next()) {
+            (Some(type_), None) => Some(*type_),
+            _ => None,
+        }
+    }
+}
+
+impl From<Type> for TypeSet {
+    fn from(type_: Type) -> Self {
+        TypeSet::Single(type_)
+    }
+}
+
+/// The direction in which data described by a schema is sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Context {
+    /// Data sent by the client, where `readOnly` properties are not sent.
+    Request,
+
+    /// Data sent by the server, where `writeOnly` properties are not sent.
+    Response,
+}
+
+/// A JSON Schema keyword value that can be either a boolean or a schema.
+///
+/// A `true` boolean schema accepts any value and `false` accepts none.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum BooleanOrSchema {
+    Boolean(bool),
+    Schema(Box<ObjectOrReference<Schema>>),
+}
+
+/// An `exclusiveMinimum` or `exclusiveMaximum` keyword value.
+///
+/// In OpenAPI 3.0 it is a boolean that makes `minimum` or `maximum` exclusive. In 3.1 it is a
+/// number, the exclusive bound itself.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum BooleanOrNumber {
+    Boolean(bool),
+    Number(serde_json::Number),
+}
+
+/// Numeric range constraints of a schema, normalized to their OpenAPI 3.1 (JSON Schema 2020-12)
+/// forms.
+///
+/// In OpenAPI 3.0, `exclusiveMinimum` and `exclusiveMaximum` are booleans that modify `minimum`
+/// and `maximum`. In 3.1 they are numbers in their own right, so a bound that is exclusive is
+/// reported only in the corresponding `exclusive_*` field.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct NumericBounds {
+    /// Inclusive lower bound.
+    pub minimum: Option<serde_json::Number>,
+
+    /// Inclusive upper bound.
+    pub maximum: Option<serde_json::Number>,
+
+    /// Exclusive lower bound.
+    pub exclusive_minimum: Option<serde_json::Number>,
+
+    /// Exclusive upper bound.
+    pub exclusive_maximum: Option<serde_json::Number>,
+
+    /// Value must be a multiple of this number.
+    pub multiple_of: Option<serde_json::Number>,
+}
+
+impl NumericBounds {
+    /// Returns true if no numeric constraints are set.
+    pub fn is_unconstrained(&self) -> bool {
+        *self == Self::default()
+    }
 }
 
 // FIXME: Verify against OpenAPI 3.0
@@ -53,12 +176,20 @@ pub struct Schema {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
 
+    /// URI of the JSON Schema dialect of this schema, overriding the spec's
+    /// `jsonSchemaDialect`.
+    ///
+    /// See [`Schema::dialect`].
+    #[serde(rename = "$schema")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_dialect: Option<String>,
+
     //
     // type
     //
     #[serde(rename = "type")]
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub schema_type: Option<Type>,
+    pub schema_type: Option<TypeSet>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub nullable: Option<bool>,
@@ -73,6 +204,15 @@ pub struct Schema {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub items: Option<Box<ObjectOrReference<Schema>>>,
 
+    /// Schemas for the leading items of an array, by position. Items past the end of the list
+    /// are validated against `items`.
+    ///
+    /// See <https://json-schema.org/draft/2020-12/json-schema-core#name-prefixitems>.
+    #[serde(rename = "prefixItems")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub prefix_items: Vec<ObjectOrReference<Schema>>,
+
     #[serde(default)]
     #[serde(skip_serializing_if = "BTreeMap::is_empty")]
     pub properties: BTreeMap<String, ObjectOrReference<Schema>>,
@@ -84,7 +224,27 @@ pub struct Schema {
     /// See <https://github.com/OAI/OpenAPI-Specification/blob/HEAD/versions/3.1.0.md#properties>.
     #[serde(rename = "additionalProperties")]
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub additional_properties: Option<Box<ObjectOrReference<Schema>>>,
+    pub additional_properties: Option<BooleanOrSchema>,
+
+    /// Applies to object properties not evaluated by `properties`, `additionalProperties` or
+    /// any in-place applicator such as `allOf`.
+    ///
+    /// Validation currently only enforces `false` on `allOf` compositions, where it rejects
+    /// properties not declared by any member. Schema values are preserved but not validated.
+    ///
+    /// See <https://json-schema.org/draft/2020-12/json-schema-core#name-unevaluatedproperties>.
+    #[serde(rename = "unevaluatedProperties")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unevaluated_properties: Option<BooleanOrSchema>,
+
+    /// Applies to array items not evaluated by `items` or any in-place applicator.
+    ///
+    /// This keyword is preserved but not validated.
+    ///
+    /// See <https://json-schema.org/draft/2020-12/json-schema-core#name-unevaluateditems>.
+    #[serde(rename = "unevaluatedItems")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unevaluated_items: Option<BooleanOrSchema>,
 
     //
     // additional metadata
@@ -95,16 +255,28 @@ pub struct Schema {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub example: Option<serde_json::Value>,
 
+    /// Example values, the OpenAPI 3.1 (JSON Schema 2020-12) replacement for `example`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub examples: Vec<serde_json::Value>,
+
     //
     // validation requirements
     //
     #[serde(skip_serializing_if = "Option::is_none")]
     pub format: Option<String>,
 
-    #[serde(default)]
+    /// Allowed values.
+    ///
+    /// An empty `enum` is kept as `Some`, so that it can be told apart from a missing one.
     #[serde(rename = "enum")]
-    #[serde(skip_serializing_if = "Vec::is_empty")]
-    pub enum_values: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enum_values: Option<Vec<serde_json::Value>>,
+
+    /// The only allowed value. Equivalent to a single-value `enum`.
+    #[serde(rename = "const")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub const_value: Option<serde_json::Value>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pattern: Option<String>,
@@ -118,14 +290,14 @@ pub struct Schema {
 
     #[serde(rename = "exclusiveMaximum")]
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub exclusive_maximum: Option<bool>,
+    pub exclusive_maximum: Option<BooleanOrNumber>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub maximum: Option<serde_json::Number>,
 
     #[serde(rename = "exclusiveMinimum")]
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub exclusive_minimum: Option<bool>,
+    pub exclusive_minimum: Option<BooleanOrNumber>,
 
     #[serde(rename = "minLength")]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -163,6 +335,10 @@ pub struct Schema {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub write_only: Option<bool>,
 
+    /// Indicates that the schema is deprecated and applications should refrain from using it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<bool>,
+
     //
     // composition
     //
@@ -180,6 +356,875 @@ pub struct Schema {
     #[serde(rename = "anyOf")]
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub any_of: Vec<ObjectOrReference<Schema>>,
+
+    /// Adds support for polymorphism.
+    ///
+    /// See <https://github.com/OAI/OpenAPI-Specification/blob/HEAD/versions/3.1.0.md#discriminatorObject>.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discriminator: Option<Discriminator>,
+
+    /// Specification extensions.
+    ///
+    /// Only "x-" prefixed keys are collected, and the prefix is stripped.
+    ///
+    /// See <https://github.com/OAI/OpenAPI-Specification/blob/HEAD/versions/3.1.0.md#specificationExtensions>.
+    #[serde(flatten, with = "spec_extensions")]
+    pub extensions: BTreeMap<String, serde_json::Value>,
+}
+
+/// Returns the `type` keyword value of a type.
+fn type_name(type_: Type) -> &'static str {
+    match type_ {
+        Type::Boolean => "boolean",
+        Type::Integer => "integer",
+        Type::Number => "number",
+        Type::String => "string",
+        Type::Array => "array",
+        Type::Object => "object",
+        Type::Null => "null",
+    }
+}
+
+/// Maximum nesting depth followed when generating example values.
+const EXAMPLE_MAX_DEPTH: usize = 8;
+
+/// Maximum depth of composition members followed, e.g. when collecting declared properties.
+pub(crate) const COMPOSITION_MAX_DEPTH: usize = 16;
+
+impl Schema {
+    /// Generates an example value for this schema.
+    ///
+    /// The first of `example`, `examples`, `default`, `const` or the first `enum` value is used
+    /// if present. Otherwise a placeholder is built from the schema's type, recursing into object
+    /// properties, array items and composition members (merging `allOf` objects and taking the
+    /// first `oneOf` or `anyOf` variant that is not cut off). Strings with a `binary` format get
+    /// a placeholder and those with a `byte` format get valid base64.
+    ///
+    /// A `$ref` back to a schema that is already being generated, as in
+    /// [recursive](Self::is_recursive) schemas, is cut off, as is nesting deeper than a fixed
+    /// limit. Cut off array items are left out, leaving an empty array, as are cut off optional
+    /// properties; cut off required properties are `null`.
+    pub fn generate_example(&self, spec: &Spec) -> serde_json::Value {
+        self.generate_example_inner(spec, 0, &mut vec![])
+    }
+
+    fn generate_example_inner(
+        &self,
+        spec: &Spec,
+        depth: usize,
+        expanding: &mut Vec<String>,
+    ) -> serde_json::Value {
+        use serde_json::Value;
+
+        if let Some(example) = self
+            .example
+            .as_ref()
+            .or(self.examples.first())
+            .or(self.default.as_ref())
+        {
+            return example.clone();
+        }
+
+        if let Some(val) = self
+            .const_value
+            .as_ref()
+            .or(self.enum_values.iter().flatten().next())
+        {
+            return val.clone();
+        }
+
+        // returns `None` for subschemas that are cut off
+        let mut generate = |oor: &ObjectOrReference<Schema>| match oor {
+            _ if depth >= EXAMPLE_MAX_DEPTH => None,
+            ObjectOrReference::Ref { ref_path, .. } if expanding.contains(ref_path) => None,
+            ObjectOrReference::Ref { ref_path, .. } => {
+                expanding.push(ref_path.clone());
+                let example = oor
+                    .resolve(spec)
+                    .map(|schema| schema.generate_example_inner(spec, depth + 1, expanding))
+                    .unwrap_or(Value::Null);
+                expanding.pop();
+                Some(example)
+            }
+            ObjectOrReference::Object(schema) => {
+                Some(schema.generate_example_inner(spec, depth + 1, expanding))
+            }
+        };
+
+        let schema_type = self.schema_type.as_ref().map(|types| {
+            types
+                .types()
+                .iter()
+                .copied()
+                .find(|type_| *type_ != Type::Null)
+                .unwrap_or(Type::Null)
+        });
+
+        match schema_type {
+            Some(Type::Null) => Value::Null,
+            Some(Type::Boolean) => Value::Bool(true),
+            Some(Type::Integer) | Some(Type::Number) => Value::from(0),
+            Some(Type::String) => match self.format.as_deref() {
+                Some("binary") => Value::String("<binary>".to_owned()),
+                // base64 encoding of "string"
+                Some("byte") => Value::String("c3RyaW5n".to_owned()),
+                _ => Value::String("string".to_owned()),
+            },
+
+            Some(Type::Array) => Value::Array(
+                self.items
+                    .iter()
+                    .filter_map(|items| generate(items))
+                    .collect(),
+            ),
+
+            Some(Type::Object) => Value::Object(
+                self.properties
+                    .iter()
+                    .filter_map(|(name, prop)| match generate(prop) {
+                        Some(val) => Some((name.clone(), val)),
+                        None if self.required.contains(name) => Some((name.clone(), Value::Null)),
+                        None => None,
+                    })
+                    .collect(),
+            ),
+
+            None if !self.all_of.is_empty() => {
+                let mut obj = serde_json::Map::new();
+
+                for member in &self.all_of {
+                    match generate(member) {
+                        Some(Value::Object(props)) => obj.extend(props),
+                        Some(other) => return other,
+                        None => {}
+                    }
+                }
+
+                Value::Object(obj)
+            }
+
+            None => self
+                .one_of
+                .iter()
+                .chain(&self.any_of)
+                .find_map(generate)
+                .unwrap_or(Value::Null),
+        }
+    }
+
+    /// Returns the natural zero value for this schema's type.
+    ///
+    /// Unlike [`Schema::generate_example`], the value is minimal rather than illustrative: `""`,
+    /// `0`, `false`, `[]` or `{}`, or `null` for nullable and type-less schemas. A `default` is
+    /// used if set, otherwise the `const` or the first `enum` value, so the value stays valid.
+    pub fn zero_value(&self) -> serde_json::Value {
+        use serde_json::Value;
+
+        let allowed = self
+            .const_value
+            .as_ref()
+            .or_else(|| self.enum_values.as_ref()?.first());
+
+        if let Some(val) = self.default.as_ref().or(allowed) {
+            return val.clone();
+        }
+
+        let types = match self.schema_type {
+            Some(ref types) => types,
+            None => return Value::Null,
+        };
+
+        if self.nullable == Some(true) || types.contains(Type::Null) {
+            return Value::Null;
+        }
+
+        match types.types().first() {
+            Some(Type::Boolean) => Value::Bool(false),
+            Some(Type::Integer) | Some(Type::Number) => Value::from(0),
+            Some(Type::String) => Value::String(String::new()),
+            Some(Type::Array) => Value::Array(vec![]),
+            Some(Type::Object) => Value::Object(serde_json::Map::new()),
+            Some(Type::Null) | None => Value::Null,
+        }
+    }
+
+    /// Fills in the `default` of each property missing from an object `value`, in place.
+    ///
+    /// Properties declared by `allOf` members are included. Present values are never
+    /// overridden, but nested objects, including inserted defaults, are filled recursively using
+    /// their property's schema. Values other than objects, and properties whose schemas can not be
+    /// resolved, are left as-is. In [recursive](Self::is_recursive) schemas, a default is not
+    /// inserted for a `$ref` back to a schema that is already being applied, so inserted defaults
+    /// are not expanded indefinitely.
+    pub fn apply_defaults(&self, value: &mut serde_json::Value, spec: &Spec) {
+        self.apply_defaults_inner(value, spec, &mut vec![])
+    }
+
+    fn apply_defaults_inner(
+        &self,
+        value: &mut serde_json::Value,
+        spec: &Spec,
+        expanding: &mut Vec<String>,
+    ) {
+        let obj = match value.as_object_mut() {
+            Some(obj) => obj,
+            None => return,
+        };
+
+        for (name, oor) in &self.properties {
+            let ref_path = match oor {
+                ObjectOrReference::Ref { ref_path, .. } => Some(ref_path),
+                ObjectOrReference::Object(_) => None,
+            };
+
+            let is_cycle = matches!(ref_path, Some(ref_path) if expanding.contains(ref_path));
+
+            if is_cycle && !obj.contains_key(name) {
+                continue;
+            }
+
+            let prop = match oor.resolve(spec) {
+                Ok(prop) => prop,
+                Err(_) => continue,
+            };
+
+            if !obj.contains_key(name) {
+                match prop.default {
+                    Some(ref default) => obj.insert(name.clone(), default.clone()),
+                    None => continue,
+                };
+            }
+
+            // present values are finite, so references back are still followed into them
+            if let Some(prop_value) = obj.get_mut(name) {
+                match ref_path {
+                    Some(ref_path) if !is_cycle => {
+                        expanding.push(ref_path.clone());
+                        prop.apply_defaults_inner(prop_value, spec, expanding);
+                        expanding.pop();
+                    }
+                    _ => prop.apply_defaults_inner(prop_value, spec, expanding),
+                }
+            }
+        }
+
+        for member in &self.all_of {
+            match member {
+                ObjectOrReference::Ref { ref_path, .. } if expanding.contains(ref_path) => {}
+                ObjectOrReference::Ref { ref_path, .. } => {
+                    if let Ok(member) = member.resolve(spec) {
+                        expanding.push(ref_path.clone());
+                        member.apply_defaults_inner(value, spec, expanding);
+                        expanding.pop();
+                    }
+                }
+                ObjectOrReference::Object(member) => {
+                    member.apply_defaults_inner(value, spec, expanding)
+                }
+            }
+        }
+    }
+
+    /// Returns true if this schema is a free-form object, which accepts any properties, such as
+    /// `{ type: object }` or `{ type: object, additionalProperties: true }`.
+    ///
+    /// Free-form objects have no `properties`, no composition members and an
+    /// `additionalProperties` that is missing, `true` or an empty schema. Code generators
+    /// typically map them to a dynamic value or map type. A map with constrained values, where
+    /// `additionalProperties` is a non-empty schema, is not free-form.
+    pub fn is_free_form(&self) -> bool {
+        let is_object = matches!(
+            self.schema_type.as_ref().map(TypeSet::single_non_null),
+            Some(Some(Type::Object))
+        );
+
+        let accepts_any_property = match self.additional_properties {
+            None | Some(BooleanOrSchema::Boolean(true)) => true,
+            Some(BooleanOrSchema::Schema(ref oor)) => {
+                matches!(**oor, ObjectOrReference::Object(ref schema) if *schema == Schema::default())
+            }
+            Some(BooleanOrSchema::Boolean(false)) => false,
+        };
+
+        is_object
+            && accepts_any_property
+            && self.properties.is_empty()
+            && self.all_of.is_empty()
+            && self.one_of.is_empty()
+            && self.any_of.is_empty()
+    }
+
+    /// Returns true if this schema is marked `readOnly`, meaning it is only sent in responses.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only == Some(true)
+    }
+
+    /// Returns true if this schema is marked `writeOnly`, meaning it is only sent in requests.
+    pub fn is_write_only(&self) -> bool {
+        self.write_only == Some(true)
+    }
+
+    /// Returns a copy of this schema with the properties that are not sent in `context` removed.
+    ///
+    /// `readOnly` properties are removed for requests and `writeOnly` properties are removed for
+    /// responses, from both `properties` and `required`. Properties declared by `allOf` members
+    /// are also dropped from this schema's `required`. Subschemas are adjusted recursively, with
+    /// referenced subschemas replaced by adjusted copies of their targets. References to
+    /// schemas that are already being adjusted, as in recursive schemas, are left as-is.
+    pub fn for_context(&self, spec: &Spec, context: Context) -> Schema {
+        self.for_context_inner(spec, context, &mut vec![])
+    }
+
+    fn for_context_inner(
+        &self,
+        spec: &Spec,
+        context: Context,
+        expanding: &mut Vec<String>,
+    ) -> Schema {
+        let is_excluded = |prop: &ObjectOrReference<Schema>| match (context, prop.resolve(spec)) {
+            (Context::Request, Ok(prop)) => prop.is_read_only(),
+            (Context::Response, Ok(prop)) => prop.is_write_only(),
+            (_, Err(_)) => false,
+        };
+
+        let mut schema = self.clone();
+
+        let member_props = schema
+            .all_of
+            .iter()
+            .filter_map(|member| member.resolve(spec).ok())
+            .flat_map(|member| member.properties.into_iter())
+            .filter(|(_, prop)| is_excluded(prop))
+            .map(|(name, _)| name);
+
+        let excluded = schema
+            .properties
+            .iter()
+            .filter(|(_, prop)| is_excluded(prop))
+            .map(|(name, _)| name.clone())
+            .chain(member_props)
+            .collect::<BTreeSet<_>>();
+
+        schema.properties.retain(|name, _| !excluded.contains(name));
+        schema.required.retain(|name| !excluded.contains(name));
+
+        for subschema in schema.subschemas_mut() {
+            match subschema {
+                ObjectOrReference::Object(inline) => {
+                    *inline = inline.for_context_inner(spec, context, expanding);
+                }
+                ObjectOrReference::Ref { ref_path, .. } if !expanding.contains(ref_path) => {
+                    let ref_path = ref_path.clone();
+                    let target = match subschema.resolve(spec) {
+                        Ok(target) => target,
+                        Err(_) => continue,
+                    };
+
+                    expanding.push(ref_path);
+                    let adjusted = target.for_context_inner(spec, context, expanding);
+                    expanding.pop();
+
+                    *subschema = ObjectOrReference::Object(adjusted);
+                }
+                ObjectOrReference::Ref { .. } => {}
+            }
+        }
+
+        schema
+    }
+
+    /// Returns the names listed in `required` that no property definition covers.
+    ///
+    /// Properties declared by `allOf`, `oneOf` and `anyOf` members count as definitions, as does
+    /// any `additionalProperties` schema, which allows arbitrary property names.
+    pub fn required_but_not_in_properties(&self, spec: &Spec) -> Vec<String> {
+        if self.required.is_empty() {
+            return vec![];
+        }
+
+        let mut declared = BTreeSet::new();
+
+        if !self.collect_declared_properties(spec, 0, &mut declared) {
+            return vec![];
+        }
+
+        self.required
+            .iter()
+            .filter(|name| !declared.contains(name.as_str()))
+            .cloned()
+            .collect()
+    }
+
+    /// Adds the property names declared by this schema and its composition members to
+    /// `declared`, returning false if arbitrary property names are allowed.
+    fn collect_declared_properties(
+        &self,
+        spec: &Spec,
+        depth: usize,
+        declared: &mut BTreeSet<String>,
+    ) -> bool {
+        let allows_any_name = !matches!(
+            self.additional_properties,
+            None | Some(BooleanOrSchema::Boolean(false))
+        );
+
+        if allows_any_name || depth > COMPOSITION_MAX_DEPTH {
+            return false;
+        }
+
+        declared.extend(self.properties.keys().cloned());
+
+        self.all_of
+            .iter()
+            .chain(&self.one_of)
+            .chain(&self.any_of)
+            .filter_map(|member| member.resolve(spec).ok())
+            .all(|member| member.collect_declared_properties(spec, depth + 1, declared))
+    }
+
+    /// Returns this schema's direct subschemas, inline or referenced, along with the JSON pointer
+    /// segments leading to each of them, e.g. `["properties", "name"]`.
+    ///
+    /// Covers the same keywords as [`Schema::inline_subschemas_mut`].
+    pub(crate) fn subschemas(&self) -> Vec<(Vec<String>, &ObjectOrReference<Schema>)> {
+        let mut subschemas = vec![];
+
+        if let Some(items) = self.items.as_deref() {
+            subschemas.push((vec!["items".to_owned()], items));
+        }
+
+        for (idx, item) in self.prefix_items.iter().enumerate() {
+            subschemas.push((vec!["prefixItems".to_owned(), idx.to_string()], item));
+        }
+
+        for (key, bos) in [
+            ("additionalProperties", &self.additional_properties),
+            ("unevaluatedProperties", &self.unevaluated_properties),
+            ("unevaluatedItems", &self.unevaluated_items),
+        ] {
+            if let Some(BooleanOrSchema::Schema(oor)) = bos {
+                subschemas.push((vec![key.to_owned()], &**oor));
+            }
+        }
+
+        for (name, prop) in &self.properties {
+            subschemas.push((vec!["properties".to_owned(), name.clone()], prop));
+        }
+
+        for (key, members) in [
+            ("allOf", &self.all_of),
+            ("oneOf", &self.one_of),
+            ("anyOf", &self.any_of),
+        ] {
+            for (idx, member) in members.iter().enumerate() {
+                subschemas.push((vec![key.to_owned(), idx.to_string()], member));
+            }
+        }
+
+        subschemas
+    }
+
+    /// Returns this schema's direct inline subschemas along with the JSON pointer segments
+    /// leading to each of them.
+    ///
+    /// See [`Schema::subschemas`].
+    pub(crate) fn inline_subschemas(&self) -> Vec<(Vec<String>, &Schema)> {
+        self.subschemas()
+            .into_iter()
+            .filter_map(|(segments, oor)| match oor {
+                ObjectOrReference::Object(schema) => Some((segments, schema)),
+                ObjectOrReference::Ref { .. } => None,
+            })
+            .collect()
+    }
+
+    /// Returns the `$ref` paths used by this schema and its inline subschemas.
+    fn nested_refs(&self) -> Vec<&str> {
+        self.subschemas()
+            .into_iter()
+            .flat_map(|(_, oor)| match oor {
+                ObjectOrReference::Ref { ref_path, .. } => vec![ref_path.as_str()],
+                ObjectOrReference::Object(schema) => schema.nested_refs(),
+            })
+            .collect()
+    }
+
+    /// Returns true if following `$ref`s from this schema leads into a cycle, meaning that fully
+    /// dereferencing it would never terminate.
+    ///
+    /// [`Schema::generate_example`] cuts such cycles off where a schema refers back to itself.
+    pub fn is_recursive(&self, spec: &Spec) -> bool {
+        self.is_recursive_with_cycle(spec).is_some()
+    }
+
+    /// Returns the first reference cycle reachable from this schema, if any.
+    ///
+    /// The cycle is given as the `$ref` paths followed, starting and ending with the same path,
+    /// e.g. `["#/components/schemas/Node", "#/components/schemas/Node"]`. Unresolvable
+    /// references are ignored.
+    pub fn is_recursive_with_cycle(&self, spec: &Spec) -> Option<Vec<String>> {
+        fn find_cycle(
+            ref_path: &str,
+            spec: &Spec,
+            stack: &mut Vec<String>,
+            acyclic: &mut BTreeSet<String>,
+        ) -> Option<Vec<String>> {
+            if let Some(pos) = stack.iter().position(|path| path == ref_path) {
+                let mut cycle = stack[pos..].to_vec();
+                cycle.push(ref_path.to_owned());
+                return Some(cycle);
+            }
+
+            if acyclic.contains(ref_path) {
+                return None;
+            }
+
+            let schema = Schema::from_ref(spec, ref_path).ok()?;
+
+            stack.push(ref_path.to_owned());
+
+            for next in schema.nested_refs() {
+                if let Some(cycle) = find_cycle(next, spec, stack, acyclic) {
+                    return Some(cycle);
+                }
+            }
+
+            stack.pop();
+            acyclic.insert(ref_path.to_owned());
+
+            None
+        }
+
+        let mut acyclic = BTreeSet::new();
+
+        self.nested_refs()
+            .into_iter()
+            .find_map(|ref_path| find_cycle(ref_path, spec, &mut vec![], &mut acyclic))
+    }
+
+    /// Returns the names of all component schemas this schema depends on, directly or through
+    /// other referenced schemas.
+    ///
+    /// Follows `$ref`s in subschemas and composition members as well as discriminator mapping
+    /// values. References to anything other than local component schemas are ignored, as are
+    /// references that can not be resolved.
+    pub fn referenced_components(&self, spec: &Spec) -> BTreeSet<String> {
+        let mut found = BTreeSet::new();
+        let mut queue = self.dependency_refs();
+
+        while let Some(ref_path) = queue.pop() {
+            let name = match ref_path.strip_prefix(super::COMPONENT_SCHEMAS_REF_PREFIX) {
+                Some(name) => crate::json_pointer::unescape(name),
+                None => continue,
+            };
+
+            if !found.insert(name) {
+                continue;
+            }
+
+            if let Ok(schema) = Schema::from_ref(spec, &ref_path) {
+                queue.extend(schema.dependency_refs());
+            }
+        }
+
+        found
+    }
+
+    /// Returns the `$ref` paths used by this schema and its inline subschemas, including
+    /// discriminator mapping values.
+    pub(super) fn dependency_refs(&self) -> Vec<String> {
+        let mut refs = self
+            .discriminator
+            .iter()
+            .flat_map(|discriminator| discriminator.mapping.values())
+            .map(|target| {
+                // mapping values may be bare schema names instead of references
+                if target.contains('/') || target.starts_with('#') {
+                    target.clone()
+                } else {
+                    format!("{}{}", super::COMPONENT_SCHEMAS_REF_PREFIX, target)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        for (_, oor) in self.subschemas() {
+            match oor {
+                ObjectOrReference::Ref { ref_path, .. } => refs.push(ref_path.clone()),
+                ObjectOrReference::Object(schema) => refs.extend(schema.dependency_refs()),
+            }
+        }
+
+        refs
+    }
+
+    /// Returns this schema's direct inline subschemas.
+    ///
+    /// Covers `items`, `properties`, `additionalProperties`, `unevaluatedProperties`,
+    /// `unevaluatedItems` and composition members. Referenced subschemas are skipped.
+    pub(crate) fn inline_subschemas_mut(&mut self) -> Vec<&mut Schema> {
+        self.subschemas_mut()
+            .into_iter()
+            .filter_map(|oor| match oor {
+                ObjectOrReference::Object(schema) => Some(schema),
+                ObjectOrReference::Ref { .. } => None,
+            })
+            .collect()
+    }
+
+    /// Returns this schema's direct subschemas, inline or referenced.
+    ///
+    /// See [`Schema::inline_subschemas_mut`].
+    fn subschemas_mut(&mut self) -> Vec<&mut ObjectOrReference<Schema>> {
+        let boolean_or_schemas = [
+            &mut self.additional_properties,
+            &mut self.unevaluated_properties,
+            &mut self.unevaluated_items,
+        ]
+        .into_iter()
+        .filter_map(|bos| match bos {
+            Some(BooleanOrSchema::Schema(oor)) => Some(&mut **oor),
+            _ => None,
+        });
+
+        self.items
+            .as_deref_mut()
+            .into_iter()
+            .chain(self.prefix_items.iter_mut())
+            .chain(boolean_or_schemas)
+            .chain(self.properties.values_mut())
+            .chain(self.all_of.iter_mut())
+            .chain(self.one_of.iter_mut())
+            .chain(self.any_of.iter_mut())
+            .collect()
+    }
+
+    /// Returns a copy of this schema with the references among its direct subschemas replaced
+    /// by their targets.
+    ///
+    /// Exactly one level is resolved: referenced `items`, `prefixItems`, `properties`,
+    /// `additionalProperties`, `unevaluatedProperties`, `unevaluatedItems` and `allOf`, `oneOf`
+    /// and `anyOf` members are inlined, but references within the inlined targets, and within
+    /// subschemas that were already inline, are left intact. This keeps recursive schemas
+    /// finite. A reference to this schema itself is resolved with [`ObjectOrReference::resolve`].
+    pub fn resolve_one_level(&self, spec: &Spec) -> Result<Schema, RefError> {
+        let mut schema = self.clone();
+
+        for oor in schema.subschemas_mut() {
+            if let ObjectOrReference::Ref { .. } = oor {
+                *oor = ObjectOrReference::Object(oor.resolve(spec)?);
+            }
+        }
+
+        Ok(schema)
+    }
+
+    /// Returns the `description` of this schema, falling back to that of the referenced schema
+    /// if this schema only wraps a reference in `allOf`.
+    ///
+    /// The first `allOf` member with a description is used, so `allOf: [$ref: Pet]` is
+    /// described by `Pet`. A bare reference is described with
+    /// [`ObjectOrReference::resolved_description`]. Unresolvable references are skipped.
+    pub fn resolved_description(&self, spec: &Spec) -> Option<String> {
+        self.resolved_description_inner(spec, 0)
+    }
+
+    fn resolved_description_inner(&self, spec: &Spec, depth: usize) -> Option<String> {
+        if self.description.is_some() || depth > COMPOSITION_MAX_DEPTH {
+            return self.description.clone();
+        }
+
+        self.all_of.iter().find_map(|member| {
+            member
+                .resolve(spec)
+                .ok()?
+                .resolved_description_inner(spec, depth + 1)
+        })
+    }
+
+    /// Returns a human readable label for this schema.
+    ///
+    /// Uses `title` if set. Otherwise the label describes the schema's type, e.g.
+    /// `array of Pet` or `string (date-time) | null`, with referenced schemas named by their
+    /// component name. Type-less compositions join their members' labels.
+    pub fn display_name(&self) -> String {
+        fn oor_name(oor: &ObjectOrReference<Schema>) -> String {
+            match oor {
+                ObjectOrReference::Ref { ref_path, .. } => ref_path
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or(ref_path.as_str())
+                    .to_owned(),
+                ObjectOrReference::Object(schema) => schema.display_name(),
+            }
+        }
+
+        fn join(members: &[ObjectOrReference<Schema>], sep: &str) -> String {
+            members.iter().map(oor_name).collect::<Vec<_>>().join(sep)
+        }
+
+        if let Some(title) = self.title.as_deref() {
+            return title.to_owned();
+        }
+
+        match self.schema_type.as_ref() {
+            Some(types) => types
+                .types()
+                .iter()
+                .map(|type_| match (type_, &self.items, self.format.as_deref()) {
+                    (Type::Array, Some(items), _) => format!("array of {}", oor_name(items)),
+                    (Type::String, _, Some(format)) => format!("string ({})", format),
+                    (type_, _, _) => type_name(*type_).to_owned(),
+                })
+                .collect::<Vec<_>>()
+                .join(" | "),
+
+            None if !self.all_of.is_empty() => join(&self.all_of, " & "),
+            None if !self.one_of.is_empty() => join(&self.one_of, " | "),
+            None if !self.any_of.is_empty() => join(&self.any_of, " | "),
+            None => "any".to_owned(),
+        }
+    }
+
+    /// Returns all numeric range constraints of this schema in one place.
+    ///
+    /// See [`NumericBounds`] for how the exclusive bounds are normalized.
+    pub fn numeric_bounds(&self) -> NumericBounds {
+        let normalize = |bound: &Option<serde_json::Number>, exclusive| match exclusive {
+            Some(&BooleanOrNumber::Boolean(true)) => (None, bound.clone()),
+            Some(BooleanOrNumber::Number(exclusive)) => (bound.clone(), Some(exclusive.clone())),
+            _ => (bound.clone(), None),
+        };
+
+        let (minimum, exclusive_minimum) =
+            normalize(&self.minimum, self.exclusive_minimum.as_ref());
+        let (maximum, exclusive_maximum) =
+            normalize(&self.maximum, self.exclusive_maximum.as_ref());
+
+        NumericBounds {
+            minimum,
+            maximum,
+            exclusive_minimum,
+            exclusive_maximum,
+            multiple_of: self.multiple_of.clone(),
+        }
+    }
+
+    /// Deserializes each of the `enum` values into `T`, in order.
+    ///
+    /// Useful for checking that a Rust type mirroring the enum can represent all of its values.
+    /// Returns [`Error::InvalidEnumValue`] for the first value that `T` can not represent.
+    pub fn deserialize_enum_values<T: serde::de::DeserializeOwned>(&self) -> Result<Vec<T>, Error> {
+        self.enum_values
+            .iter()
+            .flatten()
+            .map(|value| {
+                serde_json::from_value(value.clone()).map_err(|err| Error::InvalidEnumValue {
+                    value: value.clone(),
+                    reason: err.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    /// Returns identifier names for each of the `enum` values, if provided.
+    ///
+    /// Reads the commonly used `x-enum-varnames` and `x-enumNames` extensions. Returns `None`
+    /// if neither extension is present, if it is not a list of strings, or if its length does
+    /// not match the `enum` list.
+    pub fn enum_variant_names(&self) -> Option<Vec<String>> {
+        let names = ["enum-varnames", "enumNames"]
+            .iter()
+            .find_map(|key| self.extensions.get(*key))?;
+
+        let names = names
+            .as_array()?
+            .iter()
+            .map(|name| name.as_str().map(ToOwned::to_owned))
+            .collect::<Option<Vec<_>>>()?;
+
+        let len = self.enum_values.as_ref().map_or(0, Vec::len);
+
+        if names.len() != len {
+            warn!(
+                "enum variant names length ({}) does not match enum length ({})",
+                names.len(),
+                len
+            );
+            return None;
+        }
+
+        Some(names)
+    }
+
+    /// Returns the type name a code generator should use for this schema, if one is given.
+    ///
+    /// Checks the [`TYPE_NAME_EXTENSIONS`] in order, then `title`. See
+    /// [`preferred_type_name_with`](Self::preferred_type_name_with) for details.
+    pub fn preferred_type_name(&self) -> Option<String> {
+        self.preferred_type_name_with(TYPE_NAME_EXTENSIONS)
+    }
+
+    /// Returns the type name a code generator should use for this schema, preferring the given
+    /// extension keys (without their `x-` prefix) and then `title`.
+    ///
+    /// The first non-empty candidate is sanitized into a PascalCase identifier:
+    ///
+    /// - non-ASCII-alphanumeric characters separate words and are removed,
+    /// - the first letter of each word is uppercased and the rest of the word kept as-is, so
+    ///   `pet_store`, `pet store` and `petStore` all become `PetStore`,
+    /// - a leading digit is prefixed with `_`.
+    pub fn preferred_type_name_with(&self, extension_keys: &[&str]) -> Option<String> {
+        extension_keys
+            .iter()
+            .filter_map(|key| self.extensions.get(*key).and_then(|val| val.as_str()))
+            .chain(self.title.as_deref())
+            .find_map(sanitize_type_name)
+    }
+}
+
+/// Extensions checked by [`Schema::preferred_type_name`], without their `x-` prefix.
+pub const TYPE_NAME_EXTENSIONS: &[&str] = &["rust-type", "go-type", "type-name"];
+
+fn sanitize_type_name(name: &str) -> Option<String> {
+    let mut ident = name
+        .split(|ch: char| !ch.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().unwrap().to_ascii_uppercase();
+            std::iter::once(first).chain(chars).collect::<String>()
+        })
+        .collect::<String>();
+
+    if ident.is_empty() {
+        return None;
+    }
+
+    if ident.starts_with(|ch: char| ch.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+
+    Some(ident)
+}
+
+impl ObjectOrReference<Schema> {
+    /// Returns the description of this schema, or of its target if it is a reference.
+    ///
+    /// A `description` next to the `$ref` takes precedence over that of the target. See
+    /// [`Schema::resolved_description`].
+    pub fn resolved_description(&self, spec: &Spec) -> Option<String> {
+        if let ObjectOrReference::Ref {
+            description: Some(description),
+            ..
+        } = self
+        {
+            return Some(description.clone());
+        }
+
+        self.resolve(spec)
+            .ok()
+            .and_then(|schema| schema.resolved_description(spec))
+    }
 }
 
 impl FromRef for Schema {
@@ -198,3 +1243,633 @@ impl FromRef for Schema {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn resolved_description() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths: {}
+components:
+  schemas:
+    Pet:
+      description: A pet.
+      properties:
+        owner: { $ref: "#/components/schemas/Person" }
+        friend: { $ref: "#/components/schemas/Pet", description: Local. }
+        parent:
+          allOf: [{ $ref: "#/components/schemas/Pet" }]
+        sibling:
+          description: Another pet.
+          allOf: [{ $ref: "#/components/schemas/Pet" }]
+        name: { type: string }
+        missing: { $ref: "#/components/schemas/Missing" }
+    Person: { $ref: "#/components/schemas/Human" }
+    Human: { description: A human. }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let pet = Schema::from_ref(&spec, "#/components/schemas/Pet").unwrap();
+        let description = |name: &str| pet.properties[name].resolved_description(&spec);
+
+        assert_eq!(description("owner").as_deref(), Some("A human."));
+        assert_eq!(description("friend").as_deref(), Some("Local."));
+        assert_eq!(description("parent").as_deref(), Some("A pet."));
+        assert_eq!(description("sibling").as_deref(), Some("Another pet."));
+        assert_eq!(description("name"), None);
+        assert_eq!(description("missing"), None);
+    }
+
+    #[test]
+    fn deserialize_enum_values() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        #[serde(rename_all = "lowercase")]
+        enum Status {
+            Available,
+            Sold,
+        }
+
+        let schema: Schema =
+            serde_json::from_value(json!({ "type": "string", "enum": ["available", "sold"] }))
+                .unwrap();
+        assert_eq!(
+            schema.deserialize_enum_values::<Status>().unwrap(),
+            [Status::Available, Status::Sold]
+        );
+
+        let schema: Schema =
+            serde_json::from_value(json!({ "type": "string", "enum": ["available", "pending"] }))
+                .unwrap();
+        match schema.deserialize_enum_values::<Status>().unwrap_err() {
+            Error::InvalidEnumValue { value, .. } => assert_eq!(value, json!("pending")),
+            err => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn enum_variant_names() {
+        let schema: Schema = serde_json::from_str(
+            r#"{
+              "type": "integer",
+              "enum": [1, 2],
+              "x-enum-varnames": ["One", "Two"]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            schema.enum_variant_names(),
+            Some(vec!["One".to_owned(), "Two".to_owned()])
+        );
+
+        let schema: Schema = serde_json::from_str(
+            r#"{ "type": "string", "enum": ["a", "b"], "x-enumNames": ["A"] }"#,
+        )
+        .unwrap();
+        assert_eq!(schema.enum_variant_names(), None);
+
+        let schema: Schema =
+            serde_json::from_str(r#"{ "type": "string", "enum": ["a"] }"#).unwrap();
+        assert_eq!(schema.enum_variant_names(), None);
+    }
+
+    #[test]
+    fn zero_value() {
+        let zero =
+            |json: serde_json::Value| serde_json::from_value::<Schema>(json).unwrap().zero_value();
+
+        assert_eq!(zero(json!({ "type": "string" })), json!(""));
+        assert_eq!(zero(json!({ "type": "number" })), json!(0));
+        assert_eq!(zero(json!({ "type": "boolean" })), json!(false));
+        assert_eq!(zero(json!({ "type": "array" })), json!([]));
+        assert_eq!(zero(json!({ "type": "object" })), json!({}));
+        assert_eq!(zero(json!({ "type": ["integer", "null"] })), json!(null));
+        assert_eq!(
+            zero(json!({ "type": "string", "nullable": true })),
+            json!(null)
+        );
+        assert_eq!(
+            zero(json!({ "type": "string", "default": "a" })),
+            json!("a")
+        );
+        assert_eq!(
+            zero(json!({ "type": "string", "enum": ["b", "a"] })),
+            json!("b")
+        );
+        assert_eq!(zero(json!({ "type": "integer", "const": 7 })), json!(7));
+        assert_eq!(zero(json!({ "type": "string", "enum": [] })), json!(""));
+        assert_eq!(zero(json!({})), json!(null));
+    }
+
+    #[test]
+    fn apply_defaults() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths: {}
+components:
+  schemas:
+    Base:
+      properties:
+        retries: { type: integer, default: 3 }
+    Config:
+      type: object
+      allOf:
+        - $ref: "#/components/schemas/Base"
+      properties:
+        name: { type: string, default: app }
+        level: { type: string, default: info }
+        server:
+          type: object
+          default: {}
+          properties:
+            port: { type: integer, default: 8080 }
+            host: { type: string, default: localhost }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let schema = Schema::from_ref(&spec, "#/components/schemas/Config").unwrap();
+
+        let mut value = json!({ "level": "debug" });
+        schema.apply_defaults(&mut value, &spec);
+        assert_eq!(
+            value,
+            json!({
+                "name": "app",
+                "level": "debug",
+                "retries": 3,
+                "server": { "port": 8080, "host": "localhost" },
+            })
+        );
+
+        let mut value = json!({ "server": { "port": 80 } });
+        schema.apply_defaults(&mut value, &spec);
+        assert_eq!(value["server"], json!({ "port": 80, "host": "localhost" }));
+
+        let mut value = json!("config");
+        schema.apply_defaults(&mut value, &spec);
+        assert_eq!(value, json!("config"));
+    }
+
+    #[test]
+    fn apply_defaults_recursive() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths: {}
+components:
+  schemas:
+    Node:
+      type: object
+      default: {}
+      properties:
+        name: { type: string, default: node }
+        child: { $ref: "#/components/schemas/Node" }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let schema = Schema::from_ref(&spec, "#/components/schemas/Node").unwrap();
+
+        let mut value = json!({});
+        schema.apply_defaults(&mut value, &spec);
+        assert_eq!(
+            value,
+            json!({ "name": "node", "child": { "name": "node" } })
+        );
+
+        // defaults are still filled into present values
+        let mut value = json!({ "child": { "child": {} } });
+        schema.apply_defaults(&mut value, &spec);
+        assert_eq!(
+            value,
+            json!({
+                "name": "node",
+                "child": { "name": "node", "child": { "name": "node" } },
+            })
+        );
+    }
+
+    #[test]
+    fn context_filtering() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths: {}
+components:
+  schemas:
+    Id: { type: integer, readOnly: true }
+    User:
+      type: object
+      required: [id, name, password]
+      properties:
+        id: { $ref: "#/components/schemas/Id" }
+        name: { type: string }
+        password: { type: string, writeOnly: true }
+    Users:
+      type: array
+      items: { $ref: "#/components/schemas/User" }
+    Node:
+      type: object
+      required: [id]
+      properties:
+        id: { $ref: "#/components/schemas/Id" }
+        children:
+          type: array
+          items: { $ref: "#/components/schemas/Node" }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let id = Schema::from_ref(&spec, "#/components/schemas/Id").unwrap();
+        assert!(id.is_read_only());
+        assert!(!id.is_write_only());
+        assert!(!Schema::default().is_read_only());
+        assert!(!Schema::default().is_write_only());
+
+        let user = Schema::from_ref(&spec, "#/components/schemas/User").unwrap();
+
+        let request = user.for_context(&spec, Context::Request);
+        assert_eq!(
+            request.properties.keys().collect::<Vec<_>>(),
+            ["name", "password"]
+        );
+        assert_eq!(request.required, ["name", "password"]);
+
+        let response = user.for_context(&spec, Context::Response);
+        assert_eq!(
+            response.properties.keys().collect::<Vec<_>>(),
+            ["id", "name"]
+        );
+        assert_eq!(response.required, ["id", "name"]);
+
+        let users = Schema::from_ref(&spec, "#/components/schemas/Users").unwrap();
+        let request = users.for_context(&spec, Context::Request);
+        match request.items.as_deref() {
+            Some(ObjectOrReference::Object(user)) => {
+                assert_eq!(user.required, ["name", "password"])
+            }
+            items => panic!("unexpected items: {:?}", items),
+        }
+
+        // recursion stops at the first reference back to a schema being adjusted
+        let node = Schema::from_ref(&spec, "#/components/schemas/Node").unwrap();
+        let request = node.for_context(&spec, Context::Request);
+        assert!(request.required.is_empty());
+        let children = match request.properties["children"].resolve(&spec).unwrap().items {
+            Some(items) => *items,
+            None => panic!("missing items"),
+        };
+        match children {
+            ObjectOrReference::Object(child) => {
+                assert!(child.required.is_empty());
+                assert!(matches!(
+                    child.properties["children"]
+                        .resolve(&spec)
+                        .unwrap()
+                        .items
+                        .as_deref(),
+                    Some(ObjectOrReference::Ref { .. })
+                ));
+            }
+            items => panic!("unexpected items: {:?}", items),
+        }
+    }
+
+    #[test]
+    fn numeric_bounds() {
+        let schema = Schema::default();
+        assert!(schema.numeric_bounds().is_unconstrained());
+
+        let schema: Schema = serde_json::from_str(
+            r#"{
+              "type": "number",
+              "minimum": 1,
+              "maximum": 10.5,
+              "exclusiveMaximum": true,
+              "multipleOf": 0.5
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            schema.numeric_bounds(),
+            NumericBounds {
+                minimum: Some(1.into()),
+                maximum: None,
+                exclusive_minimum: None,
+                exclusive_maximum: Some(serde_json::Number::from_f64(10.5).unwrap()),
+                multiple_of: Some(serde_json::Number::from_f64(0.5).unwrap()),
+            }
+        );
+
+        let schema: Schema = serde_json::from_str(
+            r#"{ "type": "integer", "minimum": 1, "exclusiveMinimum": 0, "exclusiveMaximum": 10 }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            schema.exclusive_maximum,
+            Some(BooleanOrNumber::Number(10.into()))
+        );
+        assert_eq!(
+            schema.numeric_bounds(),
+            NumericBounds {
+                minimum: Some(1.into()),
+                exclusive_minimum: Some(0.into()),
+                exclusive_maximum: Some(10.into()),
+                ..NumericBounds::default()
+            }
+        );
+    }
+
+    #[test]
+    fn unevaluated_round_trip() {
+        let json = serde_json::json!({
+            "type": "object",
+            "unevaluatedProperties": false,
+            "unevaluatedItems": { "$ref": "#/components/schemas/Item" },
+        });
+
+        let schema: Schema = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(
+            schema.unevaluated_properties,
+            Some(BooleanOrSchema::Boolean(false))
+        );
+        assert!(matches!(
+            schema.unevaluated_items,
+            Some(BooleanOrSchema::Schema(_))
+        ));
+
+        assert_eq!(serde_json::to_value(&schema).unwrap(), json);
+    }
+
+    #[test]
+    fn extensions_round_trip() {
+        let json = serde_json::json!({
+            "type": "string",
+            "x-enumNames": ["A"],
+        });
+
+        let schema: Schema = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(schema.extensions.len(), 1);
+        assert!(schema.extensions.contains_key("enumNames"));
+
+        assert_eq!(serde_json::to_value(&schema).unwrap(), json);
+    }
+
+    #[test]
+    fn referenced_components() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths: {}
+components:
+  schemas:
+    Pet:
+      oneOf:
+        - $ref: "#/components/schemas/Dog"
+        - type: object
+          properties:
+            owner: { $ref: "#/components/schemas/Person" }
+      discriminator:
+        propertyName: kind
+        mapping:
+          cat: Cat
+    Dog:
+      properties:
+        tags: { type: array, items: { $ref: "#/components/schemas/Tag" } }
+    Cat: { type: object }
+    Person:
+      properties:
+        pet: { $ref: "#/components/schemas/Pet" }
+    Tag: { type: string }
+    Unused: { type: integer }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let pet = Schema::from_ref(&spec, "#/components/schemas/Pet").unwrap();
+        assert_eq!(
+            pet.referenced_components(&spec),
+            BTreeSet::from(["Cat", "Dog", "Person", "Pet", "Tag"].map(ToOwned::to_owned))
+        );
+
+        let tag = Schema::from_ref(&spec, "#/components/schemas/Tag").unwrap();
+        assert!(tag.referenced_components(&spec).is_empty());
+    }
+
+    #[test]
+    fn generate_recursive_example() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths: {}
+components:
+  schemas:
+    Node:
+      type: object
+      properties:
+        name: { type: string }
+        children:
+          type: array
+          items: { $ref: "#/components/schemas/Node" }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let node = Schema::from_ref(&spec, "#/components/schemas/Node").unwrap();
+        assert_eq!(
+            node.generate_example(&spec),
+            json!({
+                "name": "string",
+                "children": [{ "name": "string", "children": [] }],
+            })
+        );
+    }
+
+    #[test]
+    fn recursion_detection() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths: {}
+components:
+  schemas:
+    Node:
+      type: object
+      properties:
+        children:
+          type: array
+          items: { $ref: "#/components/schemas/Node" }
+    Tree:
+      type: object
+      properties:
+        root: { $ref: "#/components/schemas/Node" }
+    Person:
+      properties:
+        pet: { $ref: "#/components/schemas/Pet" }
+    Pet:
+      properties:
+        owner: { $ref: "#/components/schemas/Person" }
+        name: { $ref: "#/components/schemas/Name" }
+    Name:
+      type: string
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let schema = |name: &str| {
+            Schema::from_ref(&spec, &format!("#/components/schemas/{}", name)).unwrap()
+        };
+
+        assert!(!schema("Name").is_recursive(&spec));
+        assert!(schema("Node").is_recursive(&spec));
+        assert!(schema("Tree").is_recursive(&spec));
+
+        assert_eq!(
+            schema("Person").is_recursive_with_cycle(&spec).unwrap(),
+            [
+                "#/components/schemas/Pet",
+                "#/components/schemas/Person",
+                "#/components/schemas/Pet",
+            ]
+        );
+    }
+
+    #[test]
+    fn preferred_type_name() {
+        let schema = serde_json::from_value::<Schema>(json!({
+            "title": "pet store",
+            "x-go-type": "pet_record"
+        }))
+        .unwrap();
+
+        assert_eq!(schema.preferred_type_name().unwrap(), "PetRecord");
+        assert_eq!(schema.preferred_type_name_with(&[]).unwrap(), "PetStore");
+
+        let schema = serde_json::from_value::<Schema>(json!({
+            "title": "2xx response",
+            "x-rust-type": "--"
+        }))
+        .unwrap();
+
+        assert_eq!(schema.preferred_type_name().unwrap(), "_2xxResponse");
+        assert_eq!(Schema::default().preferred_type_name(), None);
+    }
+
+    #[test]
+    fn display_name() {
+        let name = |val: serde_json::Value| {
+            serde_json::from_value::<Schema>(val)
+                .unwrap()
+                .display_name()
+        };
+
+        assert_eq!(
+            name(serde_json::json!({ "title": "Pet", "type": "object" })),
+            "Pet"
+        );
+        assert_eq!(
+            name(serde_json::json!({
+                "type": "array",
+                "items": { "$ref": "#/components/schemas/Pet" }
+            })),
+            "array of Pet"
+        );
+        assert_eq!(
+            name(serde_json::json!({ "type": ["string", "null"], "format": "date-time" })),
+            "string (date-time) | null"
+        );
+        assert_eq!(
+            name(serde_json::json!({
+                "oneOf": [{ "$ref": "#/components/schemas/Cat" }, { "type": "integer" }]
+            })),
+            "Cat | integer"
+        );
+        assert_eq!(name(serde_json::json!({})), "any");
+    }
+
+    #[test]
+    fn resolve_one_level() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths: {}
+components:
+  schemas:
+    Node:
+      properties:
+        value: { type: string }
+        children:
+          type: array
+          items: { $ref: "#/components/schemas/Node" }
+        parent: { $ref: "#/components/schemas/Node" }
+      allOf:
+        - $ref: "#/components/schemas/Base"
+    Base: { properties: { id: { type: integer } } }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let node = spec.components.as_ref().unwrap().schemas["Node"]
+            .resolve(&spec)
+            .unwrap();
+        let resolved = node.resolve_one_level(&spec).unwrap();
+
+        let parent = match resolved.properties["parent"] {
+            ObjectOrReference::Object(ref parent) => parent,
+            ObjectOrReference::Ref { .. } => panic!("parent should be resolved"),
+        };
+        assert!(matches!(
+            parent.properties["parent"],
+            ObjectOrReference::Ref { .. }
+        ));
+        assert!(matches!(resolved.all_of[0], ObjectOrReference::Object(_)));
+
+        let children = resolved.properties["children"].resolve(&spec).unwrap();
+        assert!(matches!(
+            children.items.as_deref(),
+            Some(ObjectOrReference::Ref { .. })
+        ));
+    }
+
+    #[test]
+    fn free_form_objects() {
+        let schema = |yaml: &str| serde_yaml::from_str::<Schema>(yaml).unwrap();
+
+        assert!(schema("type: object").is_free_form());
+        assert!(schema("{ type: object, additionalProperties: true }").is_free_form());
+        assert!(schema("{ type: object, additionalProperties: {} }").is_free_form());
+
+        assert!(!schema("{ type: object, additionalProperties: false }").is_free_form());
+        assert!(!schema("{ type: object, additionalProperties: { type: string } }").is_free_form());
+        assert!(!schema("{ type: object, properties: { id: { type: integer } } }").is_free_form());
+        assert!(!schema("type: string").is_free_form());
+        assert!(!schema("{}").is_free_form());
+    }
+}
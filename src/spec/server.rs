@@ -24,6 +24,17 @@ pub struct Server {
     pub variables: BTreeMap<String, ServerVariable>,
 }
 
+impl Server {
+    /// Returns the server URL with all variables substituted with their default values.
+    pub fn url_with_defaults(&self) -> String {
+        self.variables
+            .iter()
+            .fold(self.url.clone(), |url, (name, var)| {
+                url.replace(&["{", name, "}"].concat(), &var.default)
+            })
+    }
+}
+
 /// An object representing a Server Variable for server URL template substitution.
 ///
 /// See <https://github.com/OAI/OpenAPI-Specification/blob/HEAD/versions/3.1.0.md#serverVariableObject>.
@@ -35,10 +46,11 @@ pub struct ServerVariable {
 
     /// An enumeration of string values to be used if the substitution options are from a limited
     /// set.
-    #[serde(default)]
+    ///
+    /// An empty `enum` is kept as `Some`, so that it can be told apart from a missing one.
     #[serde(rename = "enum")]
-    #[serde(skip_serializing_if = "Vec::is_empty")]
-    pub substitutions_enum: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub substitutions_enum: Option<Vec<String>>,
 
     /// An optional description for the server variable. [CommonMark] syntax MAY be used for rich
     /// text representation.
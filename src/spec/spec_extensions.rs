@@ -0,0 +1,43 @@
+//! (De)serialization helpers for [Specification Extensions].
+//!
+//! Extensions are stored without their `x-` prefix; it is stripped on deserialization and
+//! re-added on serialization. Any non-extension fields caught by the `flatten` are discarded.
+//!
+//! [Specification Extensions]: https://github.com/OAI/OpenAPI-Specification/blob/HEAD/versions/3.1.0.md#specificationExtensions
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+const EXTENSION_PREFIX: &str = "x-";
+
+pub(crate) fn deserialize<'de, D>(
+    deserializer: D,
+) -> Result<BTreeMap<String, serde_json::Value>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let map = BTreeMap::<String, serde_json::Value>::deserialize(deserializer)?;
+
+    Ok(map
+        .into_iter()
+        .filter_map(|(key, value)| {
+            key.strip_prefix(EXTENSION_PREFIX)
+                .map(|key| (key.to_owned(), value))
+        })
+        .collect())
+}
+
+pub(crate) fn serialize<S>(
+    extensions: &BTreeMap<String, serde_json::Value>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    extensions
+        .iter()
+        .map(|(key, value)| (format!("{}{}", EXTENSION_PREFIX, key), value))
+        .collect::<BTreeMap<_, _>>()
+        .serialize(serializer)
+}
@@ -1,5 +1,9 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
+use super::Spec;
+
 /// Adds metadata to a single tag that is used by the
 /// [Operation Object](https://github.com/OAI/OpenAPI-Specification/blob/HEAD/versions/3.1.0.md#operationObject).
 /// It is not mandatory to have a Tag Object per tag defined in the Operation Object instances.
@@ -21,3 +25,201 @@ pub struct Tag {
 
     // TODO: Add "Specification Extensions" https://github.com/OAI/OpenAPI-Specification/blob/HEAD/versions/3.1.0.md#specificationExtensions}
 }
+
+/// Statistics on how operations are categorized by tags, returned by [`Spec::tag_coverage`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagCoverage {
+    /// Total number of operations.
+    pub total_operations: usize,
+
+    /// Number of operations carrying each declared tag, including tags carried by none.
+    pub operations_by_tag: BTreeMap<String, usize>,
+
+    /// Number of operations without any tags.
+    pub untagged_operations: usize,
+
+    /// Declared tags that no operation carries, in declaration order.
+    pub unused_tags: Vec<String>,
+
+    /// Tags carried by operations that are not declared in the spec's `tags`.
+    pub undeclared_tags: Vec<String>,
+}
+
+impl Spec {
+    /// Returns the number of operations carrying each tag used by any operation.
+    pub fn operations_count_by_tag(&self) -> BTreeMap<&str, usize> {
+        let mut counts = BTreeMap::new();
+
+        for op in self.operations() {
+            for tag in &op.operation().tags {
+                *counts.entry(tag.as_str()).or_default() += 1;
+            }
+        }
+
+        counts
+    }
+
+    /// Reports how well the operations of this spec are covered by its declared tags.
+    pub fn tag_coverage(&self) -> TagCoverage {
+        let mut counts = self.operations_count_by_tag();
+
+        let operations_by_tag = self
+            .tags
+            .iter()
+            .map(|tag| {
+                (
+                    tag.name.clone(),
+                    counts.remove(tag.name.as_str()).unwrap_or(0),
+                )
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let unused_tags = self
+            .tags
+            .iter()
+            .filter(|tag| operations_by_tag[&tag.name] == 0)
+            .map(|tag| tag.name.clone())
+            .collect();
+
+        TagCoverage {
+            total_operations: self.operations().count(),
+            untagged_operations: self.operations().filter(|op| op.tags.is_empty()).count(),
+            operations_by_tag,
+            unused_tags,
+            undeclared_tags: counts.into_keys().map(str::to_owned).collect(),
+        }
+    }
+
+    /// Adds `tags` to this spec's tags, merging tags with the same name.
+    ///
+    /// Existing tags keep their position and new tags are appended in order. When a name
+    /// appears more than once, including among the existing tags, the first tag is kept and
+    /// takes a description from the later one only if it has none (or an empty one) itself.
+    /// Returns the names of tags whose descriptions conflicted, in which case the first
+    /// description was kept.
+    pub fn merge_tags(&mut self, tags: Vec<Tag>) -> Vec<String> {
+        fn is_blank(description: &Option<String>) -> bool {
+            description
+                .as_deref()
+                .map(str::trim)
+                .unwrap_or_default()
+                .is_empty()
+        }
+
+        let mut merged = Vec::<Tag>::new();
+        let mut conflicts = vec![];
+
+        for tag in std::mem::take(&mut self.tags).into_iter().chain(tags) {
+            let existing = match merged.iter_mut().find(|existing| existing.name == tag.name) {
+                Some(existing) => existing,
+                None => {
+                    merged.push(tag);
+                    continue;
+                }
+            };
+
+            if is_blank(&existing.description) {
+                existing.description = tag.description;
+            } else if !is_blank(&tag.description)
+                && existing.description != tag.description
+                && !conflicts.contains(&tag.name)
+            {
+                conflicts.push(tag.name);
+            }
+        }
+
+        self.tags = merged;
+        conflicts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(name: &str, description: Option<&str>) -> Tag {
+        Tag {
+            name: name.to_owned(),
+            description: description.map(str::to_owned),
+        }
+    }
+
+    #[test]
+    fn tag_coverage() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /pets:
+    get: { tags: [pets], responses: {} }
+    post: { tags: [pets, admin], responses: {} }
+  /health:
+    get: { responses: {} }
+tags:
+  - { name: pets }
+  - { name: users }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            spec.operations_count_by_tag(),
+            BTreeMap::from([("admin", 1), ("pets", 2)])
+        );
+
+        let coverage = spec.tag_coverage();
+        assert_eq!(
+            coverage,
+            TagCoverage {
+                total_operations: 3,
+                operations_by_tag: BTreeMap::from([
+                    ("pets".to_owned(), 2),
+                    ("users".to_owned(), 0)
+                ]),
+                untagged_operations: 1,
+                unused_tags: vec!["users".to_owned()],
+                undeclared_tags: vec!["admin".to_owned()],
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(&coverage).unwrap()["untaggedOperations"],
+            1
+        );
+    }
+
+    #[test]
+    fn merge_tags() {
+        let mut spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths: {}
+tags:
+  - { name: pets }
+  - { name: users, description: Users. }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let conflicts = spec.merge_tags(vec![
+            tag("orders", None),
+            tag("pets", Some("Pets.")),
+            tag("users", Some("People.")),
+            tag("users", None),
+        ]);
+
+        assert_eq!(conflicts, ["users"]);
+        assert_eq!(
+            spec.tags,
+            [
+                tag("pets", Some("Pets.")),
+                tag("users", Some("Users.")),
+                tag("orders", None),
+            ]
+        );
+    }
+}
@@ -0,0 +1,309 @@
+//! Reverse lookup of the operations that use a component schema.
+
+use std::collections::BTreeMap;
+
+use http::Method;
+
+use super::{
+    FromRef, MediaType, ObjectOrReference, Operation, Parameter, Schema, Spec,
+    COMPONENT_SCHEMAS_REF_PREFIX,
+};
+use crate::json_pointer::{escape, pointer};
+
+/// Where an operation uses a schema, as returned by [`Spec::operations_using_schema`].
+///
+/// Each location is a JSON pointer to the schema position in the operation, e.g.
+/// `#/paths/~1pets/post/requestBody/content/application~1json/schema`. Referenced parameters,
+/// request bodies, responses and headers are followed as if they were inline, so the pointer
+/// describes where the schema appears to clients rather than where it is defined.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaUsage {
+    /// Used by the schema or content of a parameter.
+    Parameter { location: String },
+
+    /// Used by the request body.
+    RequestBody { location: String },
+
+    /// Used by the body or a header of the response with the given status, e.g. `200` or
+    /// `default`.
+    Response { status: String, location: String },
+}
+
+impl SchemaUsage {
+    /// Returns the JSON pointer to the schema position using the schema.
+    pub fn location(&self) -> &str {
+        match self {
+            SchemaUsage::Parameter { location }
+            | SchemaUsage::RequestBody { location }
+            | SchemaUsage::Response { location, .. } => location,
+        }
+    }
+}
+
+impl Spec {
+    /// Returns every use of the component schema `component_name` by an operation, as (path,
+    /// method, usage) triples in path order.
+    ///
+    /// Schemas that reference the component directly or through other component schemas, e.g. a
+    /// response schema of `array of Order` where `Order` has a `Pet` property, count as uses of
+    /// `Pet`. Path item parameters are attributed to each operation they are not overridden by.
+    /// Webhooks and callbacks are not included.
+    pub fn operations_using_schema(
+        &self,
+        component_name: &str,
+    ) -> Vec<(String, Method, SchemaUsage)> {
+        let target = format!("{}{}", COMPONENT_SCHEMAS_REF_PREFIX, escape(component_name));
+
+        let uses = |schema: &ObjectOrReference<Schema>| match schema {
+            ObjectOrReference::Ref { ref_path, .. } if *ref_path == target => true,
+            ObjectOrReference::Ref { ref_path, .. } => Schema::from_ref(self, ref_path)
+                .map(|schema| schema.referenced_components(self).contains(component_name))
+                .unwrap_or(false),
+            ObjectOrReference::Object(schema) => {
+                schema.referenced_components(self).contains(component_name)
+            }
+        };
+
+        let mut found = vec![];
+
+        for (path, path_item) in &self.paths {
+            for (method, op) in path_item.methods() {
+                let method_name = method.as_str().to_ascii_lowercase();
+                let op_location = ["paths", path.as_str(), &method_name];
+                let mut push = |usage| found.push((path.clone(), method.clone(), usage));
+
+                let op_params = resolved(&op.parameters, self);
+                let path_params = resolved(&path_item.parameters, self)
+                    .into_iter()
+                    .filter(|(_, param)| {
+                        !op_params.iter().any(|(_, other)| {
+                            other.name == param.name && other.location == param.location
+                        })
+                    })
+                    .collect::<Vec<_>>();
+
+                for (parent, params) in [
+                    (&["paths", path.as_str()][..], path_params),
+                    (&op_location[..], op_params),
+                ] {
+                    for (idx, param) in params {
+                        let idx = idx.to_string();
+                        let param_location = parent.iter().copied().chain(["parameters", &idx]);
+
+                        for location in
+                            schema_locations(param.schema.as_ref(), &param.content, &uses)
+                        {
+                            push(SchemaUsage::Parameter {
+                                location: pointer(
+                                    param_location
+                                        .clone()
+                                        .chain(location.iter().map(String::as_str)),
+                                ),
+                            });
+                        }
+                    }
+                }
+
+                if let Some(body) = op
+                    .request_body
+                    .as_ref()
+                    .and_then(|body| body.resolve(self).ok())
+                {
+                    for location in schema_locations(None, &body.content, &uses) {
+                        push(SchemaUsage::RequestBody {
+                            location: pointer(
+                                op_location
+                                    .iter()
+                                    .copied()
+                                    .chain(["requestBody"])
+                                    .chain(location.iter().map(String::as_str)),
+                            ),
+                        });
+                    }
+                }
+
+                for (status, res) in response_uses(op, self, &uses) {
+                    push(SchemaUsage::Response {
+                        location: pointer(
+                            op_location
+                                .iter()
+                                .copied()
+                                .chain(["responses", &status])
+                                .chain(res.iter().map(String::as_str)),
+                        ),
+                        status,
+                    });
+                }
+            }
+        }
+
+        found
+    }
+}
+
+/// Resolves `params`, keeping their indices and skipping parameters that can not be resolved.
+fn resolved(params: &[ObjectOrReference<Parameter>], spec: &Spec) -> Vec<(usize, Parameter)> {
+    params
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, param)| Some((idx, param.resolve(spec).ok()?)))
+        .collect()
+}
+
+/// Returns the pointer segments, relative to their parent, of the schemas in `schema` and
+/// `content` for which `uses` is true.
+fn schema_locations(
+    schema: Option<&ObjectOrReference<Schema>>,
+    content: &BTreeMap<String, MediaType>,
+    uses: &impl Fn(&ObjectOrReference<Schema>) -> bool,
+) -> Vec<Vec<String>> {
+    let mut locations = vec![];
+
+    if matches!(schema, Some(schema) if uses(schema)) {
+        locations.push(vec!["schema".to_owned()]);
+    }
+
+    for (media_type, media_spec) in content {
+        if matches!(media_spec.schema, Some(ref schema) if uses(schema)) {
+            locations.push(vec![
+                "content".to_owned(),
+                media_type.clone(),
+                "schema".to_owned(),
+            ]);
+        }
+    }
+
+    locations
+}
+
+/// Returns the statuses and pointer segments, relative to the response, of the response body
+/// and header schemas of `op` for which `uses` is true.
+fn response_uses(
+    op: &Operation,
+    spec: &Spec,
+    uses: &impl Fn(&ObjectOrReference<Schema>) -> bool,
+) -> Vec<(String, Vec<String>)> {
+    let mut found = vec![];
+
+    for (status, res) in op.responses(spec) {
+        for location in schema_locations(None, &res.content, uses) {
+            found.push((status.clone(), location));
+        }
+
+        for (name, header) in &res.headers {
+            let schema = header
+                .resolve(spec)
+                .ok()
+                .and_then(|header| header.schema)
+                .map(ObjectOrReference::Object);
+
+            if matches!(schema, Some(ref schema) if uses(schema)) {
+                found.push((
+                    status.clone(),
+                    vec!["headers".to_owned(), name.clone(), "schema".to_owned()],
+                ));
+            }
+        }
+    }
+
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operations_using_schema() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /pets:
+    parameters:
+      - { name: filter, in: query, schema: { $ref: "#/components/schemas/Pet" } }
+    get:
+      parameters:
+        - { name: filter, in: query, schema: { type: string } }
+      responses:
+        "200":
+          description: pets
+          content:
+            application/json:
+              schema: { type: array, items: { $ref: "#/components/schemas/Pet" } }
+    post:
+      requestBody: { $ref: "#/components/requestBodies/NewPet" }
+      responses:
+        default: { description: error }
+  /orders:
+    get:
+      responses:
+        "200":
+          description: orders
+          content:
+            application/json:
+              schema: { $ref: "#/components/schemas/Order" }
+  /users:
+    get:
+      responses: {}
+components:
+  schemas:
+    Pet: { type: object }
+    Order: { properties: { pet: { $ref: "#/components/schemas/Pet" } } }
+  requestBodies:
+    NewPet:
+      content:
+        application/json:
+          schema: { $ref: "#/components/schemas/Pet" }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let uses = spec
+            .operations_using_schema("Pet")
+            .into_iter()
+            .map(|(path, method, usage)| (format!("{} {}", method, path), usage))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            uses,
+            [
+                (
+                    "GET /orders".to_owned(),
+                    SchemaUsage::Response {
+                        status: "200".to_owned(),
+                        location:
+                            "#/paths/~1orders/get/responses/200/content/application~1json/schema"
+                                .to_owned(),
+                    }
+                ),
+                (
+                    "GET /pets".to_owned(),
+                    SchemaUsage::Response {
+                        status: "200".to_owned(),
+                        location:
+                            "#/paths/~1pets/get/responses/200/content/application~1json/schema"
+                                .to_owned(),
+                    }
+                ),
+                (
+                    "POST /pets".to_owned(),
+                    SchemaUsage::Parameter {
+                        location: "#/paths/~1pets/parameters/0/schema".to_owned(),
+                    }
+                ),
+                (
+                    "POST /pets".to_owned(),
+                    SchemaUsage::RequestBody {
+                        location:
+                            "#/paths/~1pets/post/requestBody/content/application~1json/schema"
+                                .to_owned(),
+                    }
+                ),
+            ]
+        );
+        assert!(spec.operations_using_schema("Missing").is_empty());
+    }
+}
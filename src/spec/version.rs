@@ -0,0 +1,490 @@
+//! Conversion of specifications between OpenAPI minor versions.
+
+use derive_more::Display;
+
+use serde_json::Number;
+
+use super::{
+    schema::{Type, TypeSet},
+    visit, BooleanOrNumber, Error, ObjectOrReference, Schema, Spec,
+};
+
+/// An OpenAPI minor version that a specification can be converted to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub enum OpenApiVersion {
+    #[display(fmt = "3.0")]
+    V3_0,
+
+    #[display(fmt = "3.1")]
+    V3_1,
+}
+
+impl OpenApiVersion {
+    /// Returns the `openapi` field value written for this version.
+    pub fn spec_version(self) -> &'static str {
+        match self {
+            OpenApiVersion::V3_0 => "3.0.3",
+            OpenApiVersion::V3_1 => "3.1.0",
+        }
+    }
+}
+
+impl Spec {
+    /// Returns a copy of this spec converted to the given OpenAPI version.
+    ///
+    /// Every inline schema is converted with [`Schema::to_3_0`] or [`Schema::to_3_1`]. When
+    /// targeting 3.0, the 3.1-only `info.summary` and `license.identifier` fields are dropped and
+    /// `webhooks` or `components.pathItems` cause an [`Error::Unconvertible`].
+    pub fn to_version(&self, version: OpenApiVersion) -> Result<Spec, Error> {
+        let mut spec = self.clone();
+        spec.openapi = version.spec_version().to_owned();
+
+        match version {
+            OpenApiVersion::V3_0 => {
+                if !spec.webhooks.is_empty() {
+                    return Err(unconvertible_to_3_0("webhooks"));
+                }
+
+                if matches!(&spec.components, Some(components) if !components.path_items.is_empty())
+                {
+                    return Err(unconvertible_to_3_0("components.pathItems"));
+                }
+
+                spec.info.summary = None;
+
+                if let Some(license) = spec.info.license.as_mut() {
+                    license.identifier = None;
+                }
+
+                visit::schemas_mut(&mut spec, |schema| {
+                    *schema = schema.to_3_0()?;
+                    Ok(())
+                })?;
+            }
+
+            OpenApiVersion::V3_1 => {
+                visit::schemas_mut(&mut spec, |schema| {
+                    *schema = schema.to_3_1();
+                    Ok(())
+                })?;
+            }
+        }
+
+        Ok(spec)
+    }
+}
+
+impl Schema {
+    /// Returns a copy of this schema, and its inline subschemas, in OpenAPI 3.0 form.
+    ///
+    /// - A type list containing `null` becomes a single type with `nullable: true`. Lists of
+    ///   several non-null types become an `anyOf` of single-type schemas, each taking the
+    ///   keywords that only apply to its type, e.g. `maxLength` for `string`.
+    /// - A numeric `exclusiveMaximum` or `exclusiveMinimum` becomes a `maximum` or `minimum`
+    ///   with a `true` flag, unless an inclusive bound that is more restrictive is also set.
+    /// - `const` becomes a single-value `enum`.
+    /// - The first of `examples` becomes `example`, unless one is already set.
+    ///
+    /// Returns [`Error::Unconvertible`] for a schema whose only type is `null`, for several types
+    /// alongside an existing `anyOf`, for a `const` outside of its `enum`, and for the
+    /// `prefixItems`, `unevaluatedProperties` and `unevaluatedItems` keywords.
+    pub fn to_3_0(&self) -> Result<Schema, Error> {
+        let mut schema = self.clone();
+
+        if let Some(types) = schema.schema_type.take() {
+            if types.contains(Type::Null) {
+                schema.nullable = Some(true);
+            }
+
+            match types.single_non_null() {
+                Some(type_) => schema.schema_type = Some(TypeSet::Single(type_)),
+
+                None if types.types().iter().all(|type_| *type_ == Type::Null) => {
+                    return Err(unconvertible_to_3_0("`null` type"));
+                }
+
+                None if !schema.any_of.is_empty() => {
+                    return Err(unconvertible_to_3_0("multiple types alongside `anyOf`"));
+                }
+
+                None => {
+                    schema.any_of = types
+                        .types()
+                        .iter()
+                        .filter(|type_| **type_ != Type::Null)
+                        .map(|type_| ObjectOrReference::Object(schema.type_keywords(*type_)))
+                        .collect();
+
+                    schema.clear_type_keywords();
+                }
+            }
+        }
+
+        let (maximum, exclusive_maximum) = bound_to_3_0(
+            schema.maximum.take(),
+            schema.exclusive_maximum.take(),
+            |exclusive, inclusive| exclusive <= inclusive,
+        );
+        let (minimum, exclusive_minimum) = bound_to_3_0(
+            schema.minimum.take(),
+            schema.exclusive_minimum.take(),
+            |exclusive, inclusive| exclusive >= inclusive,
+        );
+        schema.maximum = maximum;
+        schema.exclusive_maximum = exclusive_maximum;
+        schema.minimum = minimum;
+        schema.exclusive_minimum = exclusive_minimum;
+
+        if let Some(val) = schema.const_value.take() {
+            if matches!(schema.enum_values, Some(ref values) if !values.contains(&val)) {
+                return Err(unconvertible_to_3_0("`const` not in `enum`"));
+            }
+
+            schema.enum_values = Some(vec![val]);
+        }
+
+        let examples = std::mem::take(&mut schema.examples);
+        if schema.example.is_none() {
+            schema.example = examples.into_iter().next();
+        }
+
+        if !schema.prefix_items.is_empty() {
+            return Err(unconvertible_to_3_0("`prefixItems`"));
+        }
+
+        if schema.unevaluated_properties.is_some() {
+            return Err(unconvertible_to_3_0("`unevaluatedProperties`"));
+        }
+
+        if schema.unevaluated_items.is_some() {
+            return Err(unconvertible_to_3_0("`unevaluatedItems`"));
+        }
+
+        for subschema in schema.inline_subschemas_mut() {
+            *subschema = subschema.to_3_0()?;
+        }
+
+        Ok(schema)
+    }
+
+    /// Returns a copy of this schema, and its inline subschemas, in OpenAPI 3.1 form.
+    ///
+    /// - `nullable: true` adds `null` to the type list, and `nullable` is removed.
+    /// - A `maximum` or `minimum` with a `true` `exclusiveMaximum` or `exclusiveMinimum` flag
+    ///   becomes a numeric `exclusiveMaximum` or `exclusiveMinimum`, and `false` flags are
+    ///   removed.
+    /// - `example` is moved to the front of `examples`.
+    pub fn to_3_1(&self) -> Schema {
+        let mut schema = self.clone();
+
+        if schema.nullable.take() == Some(true) {
+            schema.schema_type = match schema.schema_type.take() {
+                Some(types) if !types.contains(Type::Null) => {
+                    let mut types = types.types().to_vec();
+                    types.push(Type::Null);
+                    Some(TypeSet::Multiple(types))
+                }
+                types => types,
+            };
+        }
+
+        let (maximum, exclusive_maximum) =
+            bound_to_3_1(schema.maximum.take(), schema.exclusive_maximum.take());
+        let (minimum, exclusive_minimum) =
+            bound_to_3_1(schema.minimum.take(), schema.exclusive_minimum.take());
+        schema.maximum = maximum;
+        schema.exclusive_maximum = exclusive_maximum;
+        schema.minimum = minimum;
+        schema.exclusive_minimum = exclusive_minimum;
+
+        if let Some(example) = schema.example.take() {
+            schema.examples.insert(0, example);
+        }
+
+        for subschema in schema.inline_subschemas_mut() {
+            *subschema = subschema.to_3_1();
+        }
+
+        schema
+    }
+
+    /// Returns a schema of the single type `type_` with the keywords of this schema that only
+    /// apply to instances of that type.
+    fn type_keywords(&self, type_: Type) -> Schema {
+        let mut branch = Schema {
+            schema_type: Some(TypeSet::Single(type_)),
+            ..Schema::default()
+        };
+
+        match type_ {
+            Type::String => {
+                branch.min_length = self.min_length;
+                branch.max_length = self.max_length;
+                branch.pattern = self.pattern.clone();
+            }
+
+            Type::Integer | Type::Number => {
+                branch.minimum = self.minimum.clone();
+                branch.maximum = self.maximum.clone();
+                branch.exclusive_minimum = self.exclusive_minimum.clone();
+                branch.exclusive_maximum = self.exclusive_maximum.clone();
+                branch.multiple_of = self.multiple_of.clone();
+            }
+
+            Type::Array => {
+                branch.items = self.items.clone();
+                branch.min_items = self.min_items;
+                branch.max_items = self.max_items;
+                branch.unique_items = self.unique_items;
+            }
+
+            Type::Object => {
+                branch.properties = self.properties.clone();
+                branch.additional_properties = self.additional_properties.clone();
+                branch.required = self.required.clone();
+                branch.min_properties = self.min_properties;
+                branch.max_properties = self.max_properties;
+            }
+
+            Type::Boolean | Type::Null => {}
+        }
+
+        branch
+    }
+
+    /// Removes the keywords moved to the branches by [`Schema::type_keywords`].
+    fn clear_type_keywords(&mut self) {
+        *self = Schema {
+            min_length: None,
+            max_length: None,
+            pattern: None,
+            minimum: None,
+            maximum: None,
+            exclusive_minimum: None,
+            exclusive_maximum: None,
+            multiple_of: None,
+            items: None,
+            min_items: None,
+            max_items: None,
+            unique_items: None,
+            properties: Default::default(),
+            additional_properties: None,
+            required: vec![],
+            min_properties: None,
+            max_properties: None,
+            ..std::mem::take(self)
+        };
+    }
+}
+
+/// Converts a `maximum` or `minimum` and its exclusive keyword to the OpenAPI 3.0 form, keeping
+/// the numeric exclusive bound if it is at least as restrictive as the inclusive one.
+fn bound_to_3_0(
+    inclusive: Option<Number>,
+    exclusive: Option<BooleanOrNumber>,
+    more_restrictive: impl Fn(Option<f64>, Option<f64>) -> bool,
+) -> (Option<Number>, Option<BooleanOrNumber>) {
+    match (inclusive, exclusive) {
+        (None, Some(BooleanOrNumber::Number(exclusive))) => {
+            (Some(exclusive), Some(BooleanOrNumber::Boolean(true)))
+        }
+        (Some(inclusive), Some(BooleanOrNumber::Number(exclusive))) => {
+            if more_restrictive(exclusive.as_f64(), inclusive.as_f64()) {
+                (Some(exclusive), Some(BooleanOrNumber::Boolean(true)))
+            } else {
+                (Some(inclusive), None)
+            }
+        }
+        (inclusive, exclusive) => (inclusive, exclusive),
+    }
+}
+
+/// Converts a `maximum` or `minimum` and its exclusive keyword to the OpenAPI 3.1 form.
+fn bound_to_3_1(
+    inclusive: Option<Number>,
+    exclusive: Option<BooleanOrNumber>,
+) -> (Option<Number>, Option<BooleanOrNumber>) {
+    match (inclusive, exclusive) {
+        (Some(inclusive), Some(BooleanOrNumber::Boolean(true))) => {
+            (None, Some(BooleanOrNumber::Number(inclusive)))
+        }
+        (inclusive, Some(BooleanOrNumber::Boolean(_))) => (inclusive, None),
+        (inclusive, exclusive) => (inclusive, exclusive),
+    }
+}
+
+fn unconvertible_to_3_0(feature: &str) -> Error {
+    Error::Unconvertible(OpenApiVersion::V3_0, feature.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn schema(val: serde_json::Value) -> Schema {
+        serde_json::from_value(val).unwrap()
+    }
+
+    #[test]
+    fn schema_to_3_0() {
+        let converted = schema(json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": ["string", "null"], "examples": ["foo", "bar"] },
+                "kind": { "const": "pet" },
+                "id": { "type": ["integer", "string"] },
+                "count": {
+                    "type": ["integer", "string"],
+                    "description": "A count.",
+                    "exclusiveMinimum": 0,
+                    "maxLength": 5
+                },
+                "ratio": { "type": "number", "maximum": 1, "exclusiveMaximum": 2 }
+            }
+        }))
+        .to_3_0()
+        .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(converted).unwrap(),
+            json!({
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string", "nullable": true, "example": "foo" },
+                    "kind": { "enum": ["pet"] },
+                    "id": { "anyOf": [{ "type": "integer" }, { "type": "string" }] },
+                    "count": {
+                        "description": "A count.",
+                        "anyOf": [
+                            { "type": "integer", "minimum": 0, "exclusiveMinimum": true },
+                            { "type": "string", "maxLength": 5 }
+                        ]
+                    },
+                    "ratio": { "type": "number", "maximum": 1 }
+                }
+            })
+        );
+
+        let err = schema(json!({ "type": "null" })).to_3_0().unwrap_err();
+        assert!(matches!(err, Error::Unconvertible(OpenApiVersion::V3_0, _)));
+
+        let err = schema(json!({ "items": { "unevaluatedItems": false } }))
+            .to_3_0()
+            .unwrap_err();
+        assert!(matches!(err, Error::Unconvertible(OpenApiVersion::V3_0, _)));
+    }
+
+    #[test]
+    fn schema_to_3_1() {
+        let converted = schema(json!({
+            "type": "array",
+            "items": { "type": "string", "nullable": true, "example": "foo" },
+            "maxItems": 5
+        }))
+        .to_3_1();
+
+        assert_eq!(
+            serde_json::to_value(converted).unwrap(),
+            json!({
+                "type": "array",
+                "items": { "type": ["string", "null"], "examples": ["foo"] },
+                "maxItems": 5
+            })
+        );
+
+        let converted = schema(json!({
+            "type": "number",
+            "maximum": 5,
+            "exclusiveMaximum": true,
+            "minimum": 0,
+            "exclusiveMinimum": false
+        }))
+        .to_3_1();
+
+        assert_eq!(
+            serde_json::to_value(converted).unwrap(),
+            json!({ "type": "number", "exclusiveMaximum": 5, "minimum": 0 })
+        );
+    }
+
+    #[test]
+    fn spec_to_version() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info:
+  title: Test
+  summary: Test API
+  version: "1"
+paths:
+  /pets:
+    get:
+      parameters:
+        - name: limit
+          in: query
+          schema: { type: [integer, "null"] }
+      responses:
+        "200":
+          description: pets
+          content:
+            application/json:
+              schema: { $ref: "#/components/schemas/Pet" }
+      callbacks:
+        onPet:
+          "{$request.query.url}":
+            post:
+              requestBody:
+                content:
+                  application/json:
+                    schema: { type: [string, "null"] }
+              responses:
+                "200":
+                  description: ok
+components:
+  schemas:
+    Pet:
+      type: object
+      properties:
+        tag: { type: [string, "null"] }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let converted = spec.to_version(OpenApiVersion::V3_0).unwrap();
+        assert_eq!(converted.openapi, "3.0.3");
+        assert_eq!(converted.info.summary, None);
+
+        let param = converted.paths["/pets"].get.as_ref().unwrap().parameters[0]
+            .resolve(&converted)
+            .unwrap();
+        let param_schema = param.schema.unwrap().resolve(&converted).unwrap();
+        assert_eq!(
+            param_schema.schema_type,
+            Some(TypeSet::Single(Type::Integer))
+        );
+        assert_eq!(param_schema.nullable, Some(true));
+
+        let callback = &converted.paths["/pets"].get.as_ref().unwrap().callbacks["onPet"];
+        assert_eq!(
+            callback.0["{$request.query.url}"]["post"]["requestBody"]["content"]
+                ["application/json"]["schema"],
+            json!({ "type": "string", "nullable": true })
+        );
+
+        let pet = converted.components.as_ref().unwrap().schemas["Pet"]
+            .resolve(&converted)
+            .unwrap();
+        let tag = pet.properties["tag"].resolve(&converted).unwrap();
+        assert_eq!(tag.nullable, Some(true));
+
+        let round_trip = converted.to_version(OpenApiVersion::V3_1).unwrap();
+        assert_eq!(round_trip.openapi, "3.1.0");
+        assert_eq!(
+            round_trip.components.unwrap().schemas["Pet"],
+            spec.components.unwrap().schemas["Pet"]
+        );
+    }
+}
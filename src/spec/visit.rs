@@ -0,0 +1,376 @@
+//! Traversal of the schemas embedded throughout a specification.
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::json_pointer::pointer;
+
+use super::{
+    Callback, Error, Header, MediaType, ObjectOrReference, Operation, Parameter, PathItem,
+    RequestBody, Response, Schema, Spec,
+};
+
+/// Returns every inline schema in `spec`, including nested subschemas, along with a JSON pointer
+/// to its location.
+///
+/// Referenced schemas are skipped since their targets are visited as components.
+pub(crate) fn schemas(spec: &Spec) -> Vec<(String, &Schema)> {
+    let mut visitor = Visitor::default();
+
+    visitor.enter(&["paths"], |v| {
+        for (path, path_item) in &spec.paths {
+            v.enter(&[path], |v| v.path_item(path_item));
+        }
+    });
+
+    visitor.enter(&["webhooks"], |v| {
+        for (name, path_item) in &spec.webhooks {
+            v.enter(&[name], |v| v.path_item(path_item));
+        }
+    });
+
+    if let Some(components) = spec.components.as_ref() {
+        visitor.enter(&["components"], |v| {
+            v.objects("schemas", &components.schemas, Visitor::schema);
+            v.objects("parameters", &components.parameters, Visitor::parameter);
+            v.objects("headers", &components.headers, Visitor::header);
+            v.objects("responses", &components.responses, Visitor::response);
+            v.objects(
+                "requestBodies",
+                &components.request_bodies,
+                Visitor::request_body,
+            );
+            v.objects("pathItems", &components.path_items, Visitor::path_item);
+        });
+    }
+
+    visitor.schemas
+}
+
+#[derive(Default)]
+struct Visitor<'a> {
+    path: Vec<String>,
+    schemas: Vec<(String, &'a Schema)>,
+}
+
+impl<'a> Visitor<'a> {
+    fn enter<S: AsRef<str>>(&mut self, segments: &[S], visit: impl FnOnce(&mut Self)) {
+        let len = self.path.len();
+        self.path
+            .extend(segments.iter().map(|segment| segment.as_ref().to_owned()));
+        visit(self);
+        self.path.truncate(len);
+    }
+
+    fn objects<T>(
+        &mut self,
+        key: &str,
+        map: &'a BTreeMap<String, ObjectOrReference<T>>,
+        visit: fn(&mut Self, &'a T),
+    ) {
+        for (name, oor) in map {
+            if let ObjectOrReference::Object(obj) = oor {
+                self.enter(&[key, name], |v| visit(v, obj));
+            }
+        }
+    }
+
+    fn schema(&mut self, schema: &'a Schema) {
+        self.schemas.push((pointer(&self.path), schema));
+
+        for (segments, subschema) in schema.inline_subschemas() {
+            self.enter(&segments, |v| v.schema(subschema));
+        }
+    }
+
+    fn path_item(&mut self, path_item: &'a PathItem) {
+        self.parameters(&path_item.parameters);
+
+        let operations = [
+            ("get", &path_item.get),
+            ("put", &path_item.put),
+            ("post", &path_item.post),
+            ("delete", &path_item.delete),
+            ("options", &path_item.options),
+            ("head", &path_item.head),
+            ("patch", &path_item.patch),
+            ("trace", &path_item.trace),
+        ];
+
+        for (method, op) in operations {
+            if let Some(op) = op {
+                self.enter(&[method], |v| v.operation(op));
+            }
+        }
+    }
+
+    fn operation(&mut self, op: &'a Operation) {
+        self.parameters(&op.parameters);
+
+        if let Some(ObjectOrReference::Object(body)) = &op.request_body {
+            self.enter(&["requestBody"], |v| v.request_body(body));
+        }
+
+        self.objects("responses", &op.responses, Visitor::response);
+    }
+
+    fn parameters(&mut self, params: &'a [ObjectOrReference<Parameter>]) {
+        for (idx, param) in params.iter().enumerate() {
+            if let ObjectOrReference::Object(param) = param {
+                self.enter(&["parameters".to_owned(), idx.to_string()], |v| {
+                    v.parameter(param)
+                });
+            }
+        }
+    }
+
+    fn parameter(&mut self, param: &'a Parameter) {
+        if let Some(ObjectOrReference::Object(schema)) = &param.schema {
+            self.enter(&["schema"], |v| v.schema(schema));
+        }
+
+        self.content(&param.content);
+    }
+
+    fn header(&mut self, header: &'a Header) {
+        if let Some(schema) = &header.schema {
+            self.enter(&["schema"], |v| v.schema(schema));
+        }
+    }
+
+    fn request_body(&mut self, body: &'a RequestBody) {
+        self.content(&body.content);
+    }
+
+    fn response(&mut self, response: &'a Response) {
+        self.objects("headers", &response.headers, Visitor::header);
+        self.content(&response.content);
+    }
+
+    fn content(&mut self, content: &'a BTreeMap<String, MediaType>) {
+        for (media_type, media) in content {
+            if let Some(ObjectOrReference::Object(schema)) = &media.schema {
+                self.enter(&["content", media_type, "schema"], |v| v.schema(schema));
+            }
+        }
+    }
+}
+
+/// Calls `visit` with every inline schema that appears directly in a schema position of `spec`,
+/// stopping at the first error.
+///
+/// Covers component schemas and the schemas of parameters, headers and media types in
+/// components, paths, webhooks and callbacks. Referenced schemas are skipped since their targets
+/// are visited as components, and nested subschemas are not included; see
+/// [`Schema::inline_subschemas_mut`].
+///
+/// Callbacks are stored untyped, so their path items are visited as typed copies that are
+/// written back only if `visit` changed them.
+pub(crate) fn schemas_mut(
+    spec: &mut Spec,
+    mut visit: impl FnMut(&mut Schema) -> Result<(), Error>,
+) -> Result<(), Error> {
+    for schema in typed_schemas_mut(spec) {
+        visit(schema)?;
+    }
+
+    let mut callbacks = vec![];
+
+    for path_item in spec.paths.values_mut().chain(spec.webhooks.values_mut()) {
+        path_item_callbacks(path_item, &mut callbacks);
+    }
+
+    if let Some(components) = spec.components.as_mut() {
+        callbacks.extend(components.callbacks.values_mut().filter_map(object_mut));
+
+        for path_item in components.path_items.values_mut().filter_map(object_mut) {
+            path_item_callbacks(path_item, &mut callbacks);
+        }
+    }
+
+    for callback in callbacks {
+        callback_schemas(callback, &mut visit)?;
+    }
+
+    Ok(())
+}
+
+fn typed_schemas_mut(spec: &mut Spec) -> Vec<&mut Schema> {
+    let mut schemas = vec![];
+
+    for path_item in spec.paths.values_mut().chain(spec.webhooks.values_mut()) {
+        path_item_schemas(path_item, &mut schemas);
+    }
+
+    if let Some(components) = spec.components.as_mut() {
+        schemas.extend(components.schemas.values_mut().filter_map(object_mut));
+
+        for param in components.parameters.values_mut().filter_map(object_mut) {
+            parameter_schemas(param, &mut schemas);
+        }
+
+        for header in components.headers.values_mut().filter_map(object_mut) {
+            header_schemas(header, &mut schemas);
+        }
+
+        for response in components.responses.values_mut().filter_map(object_mut) {
+            response_schemas(response, &mut schemas);
+        }
+
+        for body in components
+            .request_bodies
+            .values_mut()
+            .filter_map(object_mut)
+        {
+            request_body_schemas(body, &mut schemas);
+        }
+
+        for path_item in components.path_items.values_mut().filter_map(object_mut) {
+            path_item_schemas(path_item, &mut schemas);
+        }
+    }
+
+    schemas
+}
+
+fn object_mut<T>(oor: &mut ObjectOrReference<T>) -> Option<&mut T> {
+    match oor {
+        ObjectOrReference::Object(obj) => Some(obj),
+        ObjectOrReference::Ref { .. } => None,
+    }
+}
+
+/// Visits the schemas of a callback's path items, including those of nested callbacks.
+fn callback_schemas(
+    callback: &mut Callback,
+    visit: &mut dyn FnMut(&mut Schema) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let expressions = match callback.0.as_object_mut() {
+        Some(expressions) => expressions,
+        None => return Ok(()),
+    };
+
+    for (expression, val) in expressions.iter_mut() {
+        if expression.starts_with("x-") {
+            continue;
+        }
+
+        let original = match PathItem::deserialize(&*val) {
+            Ok(path_item) => path_item,
+            Err(_) => continue,
+        };
+
+        let mut path_item = original.clone();
+        let mut schemas = vec![];
+        path_item_schemas(&mut path_item, &mut schemas);
+
+        for schema in schemas {
+            visit(schema)?;
+        }
+
+        let mut callbacks = vec![];
+        path_item_callbacks(&mut path_item, &mut callbacks);
+
+        for callback in callbacks {
+            callback_schemas(callback, visit)?;
+        }
+
+        if path_item != original {
+            *val = serde_json::to_value(&path_item).unwrap();
+        }
+    }
+
+    Ok(())
+}
+
+fn path_item_callbacks<'a>(path_item: &'a mut PathItem, callbacks: &mut Vec<&'a mut Callback>) {
+    let PathItem {
+        get,
+        put,
+        post,
+        delete,
+        options,
+        head,
+        patch,
+        trace,
+        ..
+    } = path_item;
+
+    for op in [get, put, post, delete, options, head, patch, trace] {
+        if let Some(op) = op.as_mut() {
+            callbacks.extend(op.callbacks.values_mut());
+        }
+    }
+}
+
+fn path_item_schemas<'a>(path_item: &'a mut PathItem, schemas: &mut Vec<&'a mut Schema>) {
+    let PathItem {
+        parameters,
+        get,
+        put,
+        post,
+        delete,
+        options,
+        head,
+        patch,
+        trace,
+        ..
+    } = path_item;
+
+    for param in parameters.iter_mut().filter_map(object_mut) {
+        parameter_schemas(param, schemas);
+    }
+
+    for op in [get, put, post, delete, options, head, patch, trace] {
+        if let Some(op) = op.as_mut() {
+            operation_schemas(op, schemas);
+        }
+    }
+}
+
+fn operation_schemas<'a>(op: &'a mut Operation, schemas: &mut Vec<&'a mut Schema>) {
+    for param in op.parameters.iter_mut().filter_map(object_mut) {
+        parameter_schemas(param, schemas);
+    }
+
+    if let Some(body) = op.request_body.as_mut().and_then(object_mut) {
+        request_body_schemas(body, schemas);
+    }
+
+    for response in op.responses.values_mut().filter_map(object_mut) {
+        response_schemas(response, schemas);
+    }
+}
+
+fn parameter_schemas<'a>(param: &'a mut Parameter, schemas: &mut Vec<&'a mut Schema>) {
+    schemas.extend(param.schema.as_mut().and_then(object_mut));
+
+    for media_type in param.content.values_mut() {
+        media_type_schemas(media_type, schemas);
+    }
+}
+
+fn header_schemas<'a>(header: &'a mut Header, schemas: &mut Vec<&'a mut Schema>) {
+    schemas.extend(header.schema.as_mut());
+}
+
+fn request_body_schemas<'a>(body: &'a mut RequestBody, schemas: &mut Vec<&'a mut Schema>) {
+    for media_type in body.content.values_mut() {
+        media_type_schemas(media_type, schemas);
+    }
+}
+
+fn response_schemas<'a>(response: &'a mut Response, schemas: &mut Vec<&'a mut Schema>) {
+    for header in response.headers.values_mut().filter_map(object_mut) {
+        header_schemas(header, schemas);
+    }
+
+    for media_type in response.content.values_mut() {
+        media_type_schemas(media_type, schemas);
+    }
+}
+
+fn media_type_schemas<'a>(media_type: &'a mut MediaType, schemas: &mut Vec<&'a mut Schema>) {
+    schemas.extend(media_type.schema.as_mut().and_then(object_mut));
+}
@@ -0,0 +1,95 @@
+use serde_json::Value as JsonValue;
+
+use super::{Error, ValidationErrors, ValidationOptions, ValidationTree};
+use crate::{Schema, Spec};
+
+/// A schema prepared for validating many values.
+///
+/// References are resolved, `pattern` regexes are compiled and `multipleOf` divisors are
+/// converted once, when the schema is compiled with [`Schema::compile`], so validating a value
+/// only does work that depends on that value. Compiled schemas can be shared between threads.
+#[derive(Debug)]
+pub struct CompiledSchema {
+    tree: ValidationTree,
+}
+
+impl CompiledSchema {
+    /// Validates `val`, returning the first error found.
+    pub fn validate(&self, val: &JsonValue) -> Result<(), Error> {
+        self.tree.validate(val)
+    }
+
+    /// Validates `val`, returning the errors found as configured by `options`.
+    pub fn validate_with(
+        &self,
+        val: &JsonValue,
+        options: &ValidationOptions,
+    ) -> Result<(), ValidationErrors> {
+        self.tree.validate_with(val, options)
+    }
+
+    /// Returns the underlying validation tree.
+    pub fn validation_tree(&self) -> &ValidationTree {
+        &self.tree
+    }
+}
+
+impl Schema {
+    /// Compiles this schema for repeated validation, applying the keyword semantics of its JSON
+    /// Schema dialect.
+    ///
+    /// Returns an [`Error::Schema`] if a reference can not be resolved or the dialect is not
+    /// supported.
+    pub fn compile(&self, spec: &Spec) -> Result<CompiledSchema, Error> {
+        let tree = ValidationTree::from_schema(self, spec).map_err(Error::Schema)?;
+        Ok(CompiledSchema { tree })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn compiled_schema() {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths: {}
+components:
+  schemas:
+    Code: { type: string, pattern: "^[A-Z]{3}$" }
+    Price:
+      type: object
+      properties:
+        code: { $ref: "#/components/schemas/Code" }
+        amount: { type: number, multipleOf: 0.01 }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let schema = spec.components.as_ref().unwrap().schemas["Price"]
+            .resolve(&spec)
+            .unwrap();
+        let compiled = schema.compile(&spec).unwrap();
+
+        fn assert_send_sync<T: Send + Sync>(_: &T) {}
+        assert_send_sync(&compiled);
+
+        compiled
+            .validate(&json!({ "code": "EUR", "amount": 9.99 }))
+            .unwrap();
+        assert!(matches!(
+            compiled.validate(&json!({ "code": "eur" })),
+            Err(Error::PatternMismatch(..))
+        ));
+        assert!(matches!(
+            compiled.validate(&json!({ "amount": 9.999 })),
+            Err(Error::NotMultipleOf(..))
+        ));
+    }
+}
@@ -2,10 +2,11 @@ use std::fmt;
 
 use derive_more::{Display, Error};
 use http::{Method, StatusCode};
+use serde::Serialize;
 use serde_json::Value as JsonValue;
 
 use super::Path;
-use crate::spec::{Error as SchemaError, SchemaType};
+use crate::spec::{Error as SchemaError, ParameterStyle, SchemaType};
 
 #[derive(Debug)]
 pub struct AggregateError {
@@ -57,6 +58,15 @@ pub enum Error {
     #[display(fmt = "{} is not a {:?}", _0, _1)]
     TypeMismatch(Path, SchemaType),
 
+    #[display(fmt = "{} is not a valid `{}` string", _0, _1)]
+    InvalidFormat(Path, &'static str),
+
+    #[display(fmt = "{} does not match pattern `{}`", _0, _1)]
+    PatternMismatch(Path, String),
+
+    #[display(fmt = "{} is not a multiple of {}", _0, _1)]
+    NotMultipleOf(Path, serde_json::Number),
+
     #[display(fmt = "Array item type mismatch: {}", _0)]
     ArrayItemTypeMismatch(JsonValue, #[error(source)] Box<Error>),
 
@@ -66,6 +76,12 @@ pub enum Error {
     #[display(fmt = "Status mismatch: expected {}; got {}", _0, _1)]
     StatusMismatch(StatusCode, StatusCode),
 
+    #[display(fmt = "Undocumented response status: {}", _0)]
+    UndocumentedStatus(#[error(not(source))] StatusCode),
+
+    #[display(fmt = "Undocumented media type: {}", _0)]
+    UndocumentedMediaType(#[error(not(source))] String),
+
     #[display(fmt = "Required field missing: {}", _0)]
     RequiredFieldMissing(#[error(not(source))] Path),
 
@@ -84,6 +100,155 @@ pub enum Error {
     #[display(fmt = "Parameter not found: {}", _0)]
     ParameterNotFound(#[error(not(source))] String),
 
+    #[display(fmt = "Value can not be serialized with the {:?} parameter style", _0)]
+    UnserializableParameter(#[error(not(source))] ParameterStyle),
+
     #[display(fmt = "Invalid parameter location: {}", _0)]
     InvalidParameterLocation(#[error(not(source))] String),
 }
+
+impl Error {
+    /// Returns the machine-readable kind of this error, for branching on without matching
+    /// messages, e.g. to translate them or to map them to structured API errors.
+    ///
+    /// An [`ArrayItemTypeMismatch`](Self::ArrayItemTypeMismatch) has the code of the item's
+    /// error.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Error::Schema(_) => ErrorCode::InvalidSchema,
+            Error::NotJson => ErrorCode::NotJson,
+            Error::TypeMismatch(..) => ErrorCode::TypeMismatch,
+            Error::InvalidFormat(..) => ErrorCode::FormatMismatch,
+            Error::PatternMismatch(..) => ErrorCode::PatternMismatch,
+            Error::NotMultipleOf(..) => ErrorCode::NotMultipleOf,
+            Error::ArrayItemTypeMismatch(_, err) => err.code(),
+            Error::UndocumentedField(_) => ErrorCode::UndocumentedField,
+            Error::StatusMismatch(..) => ErrorCode::StatusMismatch,
+            Error::UndocumentedStatus(_) => ErrorCode::UndocumentedStatus,
+            Error::UndocumentedMediaType(_) => ErrorCode::UndocumentedMediaType,
+            Error::RequiredFieldMissing(_) => ErrorCode::RequiredMissing,
+            Error::OneOfNoMatch(..) => ErrorCode::NoMatchingVariant,
+            Error::InvalidNull(_) => ErrorCode::InvalidNull,
+            Error::OperationNotFound(..) | Error::OperationIdNotFound(_) => {
+                ErrorCode::OperationNotFound
+            }
+            Error::ParameterNotFound(_) => ErrorCode::ParameterNotFound,
+            Error::UnserializableParameter(_) => ErrorCode::UnserializableParameter,
+            Error::InvalidParameterLocation(_) => ErrorCode::InvalidParameterLocation,
+        }
+    }
+}
+
+/// Machine-readable kind of a validation [`Error`], returned by [`Error::code`].
+///
+/// Serializes as its [`as_str`](Self::as_str) name, e.g. `"type_mismatch"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum ErrorCode {
+    /// The schema is invalid or could not be resolved.
+    InvalidSchema,
+
+    /// The body is not valid JSON.
+    NotJson,
+
+    /// The value is not of the schema's type.
+    TypeMismatch,
+
+    /// The string does not have the schema's `format`.
+    FormatMismatch,
+
+    /// The string does not match the schema's `pattern`.
+    PatternMismatch,
+
+    /// The number is not a multiple of the schema's `multipleOf`.
+    NotMultipleOf,
+
+    /// The object has a property that the schema does not allow.
+    UndocumentedField,
+
+    /// The response status is not the expected one.
+    StatusMismatch,
+
+    /// The response status is not declared by the operation.
+    UndocumentedStatus,
+
+    /// The media type is not declared by the request body or response.
+    UndocumentedMediaType,
+
+    /// The object is missing a `required` property.
+    RequiredMissing,
+
+    /// The value matched none of the `anyOf` or `oneOf` variants.
+    NoMatchingVariant,
+
+    /// The value is null, but the schema is not nullable.
+    InvalidNull,
+
+    /// The operation is not declared by the spec.
+    OperationNotFound,
+
+    /// The parameter is not declared by the operation.
+    ParameterNotFound,
+
+    /// The value can not be serialized with the parameter's style.
+    UnserializableParameter,
+
+    /// The parameter location is not a valid `in` value.
+    InvalidParameterLocation,
+}
+
+impl ErrorCode {
+    /// Returns the stable snake case name of this code, e.g. `type_mismatch`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::InvalidSchema => "invalid_schema",
+            ErrorCode::NotJson => "not_json",
+            ErrorCode::TypeMismatch => "type_mismatch",
+            ErrorCode::FormatMismatch => "format_mismatch",
+            ErrorCode::PatternMismatch => "pattern_mismatch",
+            ErrorCode::NotMultipleOf => "not_multiple_of",
+            ErrorCode::UndocumentedField => "undocumented_field",
+            ErrorCode::StatusMismatch => "status_mismatch",
+            ErrorCode::UndocumentedStatus => "undocumented_status",
+            ErrorCode::UndocumentedMediaType => "undocumented_media_type",
+            ErrorCode::RequiredMissing => "required_missing",
+            ErrorCode::NoMatchingVariant => "no_matching_variant",
+            ErrorCode::InvalidNull => "invalid_null",
+            ErrorCode::OperationNotFound => "operation_not_found",
+            ErrorCode::ParameterNotFound => "parameter_not_found",
+            ErrorCode::UnserializableParameter => "unserializable_parameter",
+            ErrorCode::InvalidParameterLocation => "invalid_parameter_location",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_codes() {
+        let path = Path::new('.');
+
+        let err = Error::RequiredFieldMissing(path.extend("id"));
+        assert_eq!(err.code(), ErrorCode::RequiredMissing);
+
+        let err = Error::ArrayItemTypeMismatch(
+            JsonValue::Null,
+            Box::new(Error::TypeMismatch(path, SchemaType::String)),
+        );
+        assert_eq!(err.code(), ErrorCode::TypeMismatch);
+
+        assert_eq!(
+            serde_json::to_value(ErrorCode::NoMatchingVariant).unwrap(),
+            ErrorCode::NoMatchingVariant.as_str()
+        );
+    }
+}
@@ -0,0 +1,236 @@
+use std::fmt;
+
+use serde_json::Value as JsonValue;
+
+use super::{exchange::coerce, Error, ValidationOptions, ValidationTree};
+use crate::spec::{MediaTypeExamples, Parameter, Schema, Spec};
+
+/// An invalid parameter example.
+#[derive(Debug)]
+pub struct ExampleError {
+    /// Name of the example, or `example` for a parameter's single `example` value.
+    pub key: String,
+
+    pub error: Error,
+}
+
+impl fmt::Display for ExampleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.key, self.error)
+    }
+}
+
+impl Parameter {
+    /// Validates this parameter's examples against its schema and serialization style.
+    ///
+    /// Examples must be valid instances of the parameter's schema, and must be serializable
+    /// with its `style` and `explode` settings, e.g. `deepObject` parameters only accept
+    /// objects. Empty arrays and objects are always serializable. Referenced examples that can not be resolved are skipped.
+    pub fn validate_examples(&self, spec: &Spec) -> Result<(), Vec<ExampleError>> {
+        self.validate_examples_with(spec, false)
+    }
+
+    /// Validates this parameter's examples like [`Parameter::validate_examples`], but first
+    /// converts string examples to the type declared by the schema.
+    ///
+    /// This accepts examples written as they appear in a request, e.g. `"5"` for an integer
+    /// query parameter.
+    pub fn validate_examples_coerced(&self, spec: &Spec) -> Result<(), Vec<ExampleError>> {
+        self.validate_examples_with(spec, true)
+    }
+
+    fn validate_examples_with(&self, spec: &Spec, coerced: bool) -> Result<(), Vec<ExampleError>> {
+        let examples = match self.examples {
+            Some(MediaTypeExamples::Example { ref example }) => {
+                vec![("example".to_owned(), example.clone())]
+            }
+            Some(ref examples) => examples
+                .resolve_all(spec)
+                .into_iter()
+                .filter_map(|(name, example)| Some((name, example.value?)))
+                .collect(),
+            None => vec![],
+        };
+
+        let schema = match self.effective_schema(spec) {
+            Ok(schema) => schema,
+            Err(err) => {
+                return Err(vec![ExampleError {
+                    key: self.name.clone(),
+                    error: Error::Schema(err),
+                }])
+            }
+        };
+
+        let validator = match schema {
+            Some(ref schema) => match ValidationTree::from_schema(schema, spec) {
+                Ok(validator) => Some(validator),
+                Err(err) => {
+                    return Err(vec![ExampleError {
+                        key: self.name.clone(),
+                        error: Error::Schema(err),
+                    }])
+                }
+            },
+            None => None,
+        };
+
+        let errors = examples
+            .into_iter()
+            .filter_map(|(key, val)| {
+                let val = match (&val, &schema) {
+                    (JsonValue::String(raw), Some(schema)) if coerced => {
+                        coerce(spec, schema, &[raw])
+                    }
+                    _ => val,
+                };
+
+                let result = match validator {
+                    Some(ref validator) => validator.validate(&val),
+                    None => Ok(()),
+                };
+
+                // empty arrays and objects serialize to nothing, so their form can not be checked
+                let is_empty = match val {
+                    JsonValue::Array(ref items) => items.is_empty(),
+                    JsonValue::Object(ref obj) => obj.is_empty(),
+                    _ => false,
+                };
+
+                let result = result.and_then(|_| {
+                    if is_empty || self.matches_serialized(&self.serialize_value(&val)) {
+                        Ok(())
+                    } else {
+                        Err(Error::UnserializableParameter(self.effective_style()))
+                    }
+                });
+
+                result.err().map(|error| ExampleError { key, error })
+            })
+            .collect::<Vec<_>>();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl Schema {
+    /// Validates each of this schema's own `examples` against the schema, returning the indices
+    /// of the invalid examples along with all errors found in each.
+    ///
+    /// An empty result means every example is valid.
+    pub fn validate_examples(&self, spec: &Spec) -> Vec<(usize, Vec<Error>)> {
+        let options = ValidationOptions {
+            collect_all: true,
+            ..ValidationOptions::default()
+        };
+
+        self.examples
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, example)| {
+                self.validate_with(spec, example, &options)
+                    .err()
+                    .map(|errors| (idx, errors.errors))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spec::ParameterStyle;
+
+    fn param(yaml: &str) -> (Spec, Parameter) {
+        let spec = crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths: {}
+components:
+  examples:
+    Five: { value: "5" }
+"##
+            .as_bytes(),
+        )
+        .unwrap();
+
+        (spec, serde_yaml::from_str(yaml).unwrap())
+    }
+
+    fn keys(errors: Vec<ExampleError>) -> Vec<String> {
+        errors.into_iter().map(|err| err.key).collect()
+    }
+
+    #[test]
+    fn parameter_examples() {
+        let (spec, limit) = param(
+            r##"
+name: limit
+in: query
+schema: { type: integer }
+examples:
+  small: { value: 1 }
+  text: { $ref: "#/components/examples/Five" }
+"##,
+        );
+
+        assert_eq!(keys(limit.validate_examples(&spec).unwrap_err()), ["text"]);
+        limit.validate_examples_coerced(&spec).unwrap();
+
+        let (spec, filter) = param(
+            r##"
+name: filter
+in: query
+style: deepObject
+example: active
+"##,
+        );
+
+        let errors = filter.validate_examples(&spec).unwrap_err();
+        assert_eq!(errors[0].key, "example");
+        assert!(matches!(
+            errors[0].error,
+            Error::UnserializableParameter(ParameterStyle::DeepObject)
+        ));
+
+        let (spec, tags) = param(
+            r##"
+name: tags
+in: query
+schema: { type: array, items: { type: string } }
+example: []
+"##,
+        );
+
+        tags.validate_examples(&spec).unwrap();
+    }
+
+    #[test]
+    fn schema_examples() {
+        let spec = Spec::minimal_valid("Test", "1");
+        let schema = serde_yaml::from_str::<Schema>(
+            r##"
+type: object
+required: [name]
+properties:
+  name: { type: string }
+  age: { type: integer }
+examples:
+  - { name: Rex }
+  - { age: old }
+  - { name: Tom, age: 3 }
+"##,
+        )
+        .unwrap();
+
+        let invalid = schema.validate_examples(&spec);
+        assert_eq!(invalid.len(), 1);
+        assert_eq!(invalid[0].0, 1);
+        assert_eq!(invalid[0].1.len(), 2);
+    }
+}
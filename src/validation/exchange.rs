@@ -0,0 +1,896 @@
+//! Validation of complete HTTP request/response exchanges against a spec.
+
+use std::{collections::BTreeMap, fmt};
+
+use http::{HeaderMap, Method, StatusCode};
+use serde_json::Value as JsonValue;
+
+use super::{Error, ValidationTree};
+use crate::{
+    spec::{
+        encode, is_json_media_type, media_type_essence, Error as SchemaError, MediaType,
+        ObjectOrReference, Operation, Parameter, ParameterStyle, Response, SchemaContext,
+        SchemaType,
+    },
+    Schema, Spec,
+};
+
+/// An HTTP request to validate with [`validate_exchange`].
+#[derive(Debug, Clone, Default)]
+pub struct ExchangeRequest {
+    pub method: Method,
+
+    /// Request path, without query string, e.g. `/pets/42`. It is matched as-is, so
+    /// percent-encoded segments should be decoded beforehand.
+    pub path: String,
+
+    /// Decoded query string pairs, in request order.
+    pub query: Vec<(String, String)>,
+
+    pub headers: HeaderMap,
+
+    /// JSON request body, if any.
+    pub body: Option<JsonValue>,
+}
+
+/// An HTTP response to validate with [`validate_exchange`].
+#[derive(Debug, Clone, Default)]
+pub struct ExchangeResponse {
+    pub status: StatusCode,
+
+    pub headers: HeaderMap,
+
+    /// JSON response body, if any.
+    pub body: Option<JsonValue>,
+}
+
+/// A single problem found in an exchange.
+#[derive(Debug)]
+pub struct Finding {
+    /// Part of the exchange the problem was found in, e.g. `request.query.limit` or
+    /// `response.body`.
+    pub location: String,
+
+    pub error: Error,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.error)
+    }
+}
+
+/// Result of validating an exchange with [`validate_exchange`].
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    /// Path template of the matched operation, e.g. `/pets/{id}`.
+    pub path_template: Option<String>,
+
+    /// All problems found, in request and then response order.
+    pub findings: Vec<Finding>,
+}
+
+impl ValidationReport {
+    /// Returns true if no problems were found.
+    pub fn is_valid(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    fn push(&mut self, location: impl Into<String>, error: Error) {
+        self.findings.push(Finding {
+            location: location.into(),
+            error,
+        })
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let findings = self
+            .findings
+            .iter()
+            .map(|finding| format!("  => {}", finding))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        f.write_str(&findings)
+    }
+}
+
+/// Validates a request and the response it received against `spec`.
+///
+/// The request path is matched against the spec's path templates, preferring templates with
+/// more literal segments. Then the following are checked, with every problem found collected
+/// into the report:
+///
+/// - path, query, header and cookie parameters: required ones must be present and all values
+///   must match their schemas,
+/// - the request body: it must be present if required, and match the schema for its
+///   `Content-Type`,
+/// - the response status: it must be covered by a declared response, including `default`,
+/// - the response headers: required ones must be present and all values must match their
+///   schemas,
+/// - the response body: it must match the schema for its `Content-Type`.
+///
+/// Parameter values are deserialized as their `style` and `explode` settings describe, and
+/// parameter and header values are converted to the types their schema declares before
+/// validation. Bodies are only validated for JSON media types, and
+/// `readOnly`/`writeOnly` properties are handled according to the body's direction.
+pub fn validate_exchange(
+    spec: &Spec,
+    req: &ExchangeRequest,
+    res: &ExchangeResponse,
+) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    let (template, path_params) = match match_path(spec, &req.path) {
+        Some(matched) => matched,
+        None => {
+            report.push(
+                "request.path",
+                Error::OperationNotFound(req.method.clone(), req.path.clone()),
+            );
+            return report;
+        }
+    };
+
+    let op = match spec.operation(&req.method, template) {
+        Some(op) => op,
+        None => {
+            report.push(
+                "request.method",
+                Error::OperationNotFound(req.method.clone(), template.to_owned()),
+            );
+            return report;
+        }
+    };
+
+    report.path_template = Some(template.to_owned());
+
+    validate_parameters(spec, template, op, req, &path_params, &mut report);
+    validate_request_body(spec, op, req, &mut report);
+    validate_response(spec, op, res, &mut report);
+
+    report
+}
+
+/// Finds the path template matching `path`, returning it with the values of its path
+/// parameters.
+///
+/// Template segments may mix literals and parameters, e.g. `{name}.json`.
+fn match_path<'a>(spec: &'a Spec, path: &str) -> Option<(&'a str, BTreeMap<String, String>)> {
+    let segments = path.trim_end_matches('/').split('/').collect::<Vec<_>>();
+
+    spec.paths
+        .keys()
+        .filter_map(|template| {
+            let template_segments = template.trim_end_matches('/').split('/');
+
+            if template_segments.clone().count() != segments.len() {
+                return None;
+            }
+
+            let mut params = BTreeMap::new();
+            let mut literals = 0;
+
+            for (pattern, segment) in template_segments.zip(&segments) {
+                if !pattern.contains('{') {
+                    if pattern != *segment {
+                        return None;
+                    }
+
+                    literals += 1;
+                } else if !match_segment(pattern, segment, &mut params) {
+                    return None;
+                }
+            }
+
+            Some((literals, template.as_str(), params))
+        })
+        .max_by_key(|(literals, _, _)| *literals)
+        .map(|(_, template, params)| (template, params))
+}
+
+/// Matches a single path segment against a template segment, collecting the values of its
+/// parameters. Parameter values must not be empty.
+fn match_segment(pattern: &str, segment: &str, params: &mut BTreeMap<String, String>) -> bool {
+    let mut pattern = pattern;
+    let mut rest = segment;
+
+    while let Some(start) = pattern.find('{') {
+        rest = match rest.strip_prefix(&pattern[..start]) {
+            Some(rest) => rest,
+            None => return false,
+        };
+
+        let after = &pattern[start + 1..];
+        let end = match after.find('}') {
+            Some(end) => end,
+            None => return false,
+        };
+
+        let name = &after[..end];
+        pattern = &after[end + 1..];
+
+        // a trailing literal is matched at the end, any other one at its first occurrence
+        let len = match pattern.find('{') {
+            None if rest.ends_with(pattern) => rest.len() - pattern.len(),
+            None => return false,
+            Some(0) => return false,
+            Some(next) => match rest.find(&pattern[..next]) {
+                Some(len) => len,
+                None => return false,
+            },
+        };
+
+        if len == 0 {
+            return false;
+        }
+
+        params.insert(name.to_owned(), rest[..len].to_owned());
+        rest = &rest[len..];
+    }
+
+    rest == pattern
+}
+
+fn validate_parameters(
+    spec: &Spec,
+    template: &str,
+    op: &Operation,
+    req: &ExchangeRequest,
+    path_params: &BTreeMap<String, String>,
+    report: &mut ValidationReport,
+) {
+    // operation parameters override path item parameters with the same name and location
+    let mut params = BTreeMap::new();
+
+    let path_item_params = spec
+        .resolve_path_item(template)
+        .and_then(|item| item.parameters(spec))
+        .unwrap_or_default();
+
+    for param in path_item_params
+        .into_iter()
+        .chain(op.parameters(spec).unwrap_or_default())
+    {
+        params.insert((param.location.clone(), param.name.clone()), param);
+    }
+
+    let cookies = req
+        .headers
+        .get_all(http::header::COOKIE)
+        .iter()
+        .filter_map(|val| val.to_str().ok())
+        .flat_map(|val| val.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .collect::<Vec<_>>();
+
+    for ((location, name), param) in params {
+        let values = match location.as_str() {
+            "path" => path_params
+                .get(&name)
+                .map(|val| (name.as_str(), val.as_str()))
+                .into_iter()
+                .collect::<Vec<_>>(),
+            "query" => req
+                .query
+                .iter()
+                .filter(|(key, _)| is_query_key(spec, &param, key))
+                .map(|(key, val)| (key.as_str(), val.as_str()))
+                .collect(),
+            "header" => header_values(&req.headers, &name)
+                .into_iter()
+                .map(|val| (name.as_str(), val))
+                .collect(),
+            "cookie" => cookies
+                .iter()
+                .filter(|(key, _)| *key == name)
+                .copied()
+                .collect(),
+            _ => {
+                report.push(
+                    format!("request.{}.{}", location, name),
+                    Error::InvalidParameterLocation(location.clone()),
+                );
+                continue;
+            }
+        };
+
+        let report_location = format!("request.{}.{}", location, name);
+
+        if values.is_empty() {
+            if param.required == Some(true) || location == "path" {
+                report.push(report_location, Error::RequiredFieldMissing(path(&name)));
+            }
+
+            continue;
+        }
+
+        if let Err(err) = validate_parameter_values(spec, &param, &values) {
+            report.push(report_location, err);
+        }
+    }
+}
+
+/// Returns true if the query string `key` carries (part of) the value of `param`.
+fn is_query_key(spec: &Spec, param: &Parameter, key: &str) -> bool {
+    match (param.effective_style(), param.effective_explode()) {
+        (ParameterStyle::DeepObject, _) => matches!(
+            key.strip_prefix(param.name.as_str()),
+            Some(sub) if sub.len() > 2 && sub.starts_with('[') && sub.ends_with(']')
+        ),
+        // exploded form objects are sent as one pair per property
+        (ParameterStyle::Form, true) => {
+            key == param.name
+                || matches!(
+                    param.effective_schema(spec),
+                    Ok(Some(ref schema)) if schema.properties.contains_key(key)
+                )
+        }
+        _ => key == param.name,
+    }
+}
+
+/// Validates the values of a parameter, given as the (decoded) pairs of names and values they
+/// were sent as.
+fn validate_parameter_values(
+    spec: &Spec,
+    param: &Parameter,
+    pairs: &[(&str, &str)],
+) -> Result<(), Error> {
+    let schema = match param.effective_schema(spec).map_err(Error::Schema)? {
+        Some(schema) => schema,
+        None => return Ok(()),
+    };
+
+    // parameters with `content` are not serialized according to a style
+    if param.schema.is_none() {
+        let values = pairs.iter().map(|(_, val)| *val).collect::<Vec<_>>();
+        return validate_value(spec, &schema, &coerce(spec, &schema, &values));
+    }
+
+    // rebuild the raw value, escaping what deserialization would otherwise decode or split
+    let escape = |val: &str| val.replace('%', "%25").replace('&', "%26");
+    let raw = match param.location.as_str() {
+        "path" | "header" => pairs
+            .iter()
+            .map(|(_, val)| escape(val))
+            .collect::<Vec<_>>()
+            .join(","),
+        _ => pairs
+            .iter()
+            .map(|(key, val)| {
+                let key = match key.strip_prefix(param.name.as_str()) {
+                    Some(sub) => format!("{}{}", encode(&param.name), sub),
+                    None => encode(key),
+                };
+
+                format!("{}={}", key, escape(val))
+            })
+            .collect::<Vec<_>>()
+            .join("&"),
+    };
+
+    // the kinds of values considered follow the inline schema, so inline the resolved one
+    let typed = Parameter {
+        schema: Some(ObjectOrReference::Object(schema.clone())),
+        ..param.clone()
+    };
+
+    let val = match typed.deserialize_value(&raw) {
+        Some(val) => coerce_value(spec, &schema, val),
+        None => JsonValue::String(raw),
+    };
+
+    validate_value(spec, &schema, &val)
+}
+
+fn validate_request_body(
+    spec: &Spec,
+    op: &Operation,
+    req: &ExchangeRequest,
+    report: &mut ValidationReport,
+) {
+    let body = match op.request_body.as_ref() {
+        Some(_) => match op.request_body(spec) {
+            Ok(body) => body,
+            Err(err) => return report.push("request.body", Error::Schema(err)),
+        },
+        None => return,
+    };
+
+    let val = match req.body {
+        Some(ref val) => val,
+        None => {
+            if body.required == Some(true) {
+                report.push("request.body", Error::RequiredFieldMissing(path("body")));
+            }
+
+            return;
+        }
+    };
+
+    if let Err(err) = validate_body(
+        spec,
+        &body.content,
+        &req.headers,
+        val,
+        SchemaContext::Request,
+    ) {
+        report.push("request.body", err);
+    }
+}
+
+fn validate_response(
+    spec: &Spec,
+    op: &Operation,
+    res: &ExchangeResponse,
+    report: &mut ValidationReport,
+) {
+    let response = match op.response_for_status(res.status, spec) {
+        Some(response) => response,
+        None => return report.push("response.status", Error::UndocumentedStatus(res.status)),
+    };
+
+    validate_response_headers(spec, &response, &res.headers, report);
+
+    if let Some(ref val) = res.body {
+        if let Err(err) = validate_body(
+            spec,
+            &response.content,
+            &res.headers,
+            val,
+            SchemaContext::Response,
+        ) {
+            report.push("response.body", err);
+        }
+    }
+}
+
+fn validate_response_headers(
+    spec: &Spec,
+    response: &Response,
+    headers: &HeaderMap,
+    report: &mut ValidationReport,
+) {
+    for (name, header) in &response.headers {
+        if name.eq_ignore_ascii_case("content-type") {
+            continue;
+        }
+
+        let location = format!("response.header.{}", name);
+
+        let header = match header.resolve(spec) {
+            Ok(header) => header,
+            Err(err) => {
+                report.push(location, Error::Schema(SchemaError::Ref(err)));
+                continue;
+            }
+        };
+
+        let values = header_values(headers, name);
+
+        if values.is_empty() {
+            if header.required == Some(true) {
+                report.push(location, Error::RequiredFieldMissing(path(name)));
+            }
+
+            continue;
+        }
+
+        if let Some(ref schema) = header.schema {
+            if let Err(err) = validate_value(spec, schema, &coerce(spec, schema, &values)) {
+                report.push(location, err);
+            }
+        }
+    }
+}
+
+/// Validates a body against the schema of the media type given by the `Content-Type` header.
+fn validate_body(
+    spec: &Spec,
+    content: &BTreeMap<String, MediaType>,
+    headers: &HeaderMap,
+    val: &JsonValue,
+    context: SchemaContext,
+) -> Result<(), Error> {
+    let content_type = headers
+        .get(http::header::CONTENT_TYPE)
+        .and_then(|val| val.to_str().ok());
+
+    let (media_type, media) = match media_type_for(content, content_type) {
+        Some(media) => media,
+        None if content.is_empty() => return Ok(()),
+        None => {
+            return Err(Error::UndocumentedMediaType(
+                content_type.unwrap_or_default().to_owned(),
+            ))
+        }
+    };
+
+    if !is_json_media_type(media_type) {
+        return Ok(());
+    }
+
+    let schema = match media.schema_resolved(spec).map_err(Error::Schema)? {
+        Some(schema) => schema.for_context(spec, context),
+        None => return Ok(()),
+    };
+
+    validate_value(spec, &schema, val)
+}
+
+/// Picks the most specific media type matching `content_type`, or the first JSON media type if
+/// no content type is given.
+fn media_type_for<'a>(
+    content: &'a BTreeMap<String, MediaType>,
+    content_type: Option<&str>,
+) -> Option<(&'a str, &'a MediaType)> {
+    let content_type = match content_type {
+        Some(content_type) => content_type,
+        None => {
+            return content
+                .iter()
+                .find(|(media_type, _)| is_json_media_type(media_type))
+                .map(|(media_type, media)| (media_type.as_str(), media))
+        }
+    };
+
+    let essence = media_type_essence(content_type);
+
+    let range = essence
+        .split_once('/')
+        .map(|(type_, _)| format!("{}/*", type_))
+        .unwrap_or_default();
+
+    [essence.as_str(), range.as_str(), "*/*"]
+        .iter()
+        .find_map(|key| {
+            content
+                .iter()
+                .find(|(media_type, _)| media_type.eq_ignore_ascii_case(key))
+        })
+        .map(|(media_type, media)| (media_type.as_str(), media))
+}
+
+fn validate_value(spec: &Spec, schema: &Schema, val: &JsonValue) -> Result<(), Error> {
+    ValidationTree::from_schema(schema, spec)
+        .map_err(Error::Schema)?
+        .validate(val)
+}
+
+/// Converts raw parameter or header values to the type declared by `schema`.
+///
+/// Values that can not be converted are kept as strings so that validation reports them.
+pub(super) fn coerce(spec: &Spec, schema: &Schema, values: &[&str]) -> JsonValue {
+    let schema_type = schema
+        .schema_type
+        .as_ref()
+        .and_then(|types| types.single_non_null());
+
+    if schema_type == Some(SchemaType::Array) {
+        let items = schema
+            .items
+            .as_ref()
+            .and_then(|items| items.resolve(spec).ok());
+
+        let values = match values {
+            [val] => val.split(',').collect(),
+            values => values.to_vec(),
+        };
+
+        return values
+            .into_iter()
+            .map(|val| match items {
+                Some(ref items) => coerce(spec, items, &[val]),
+                None => JsonValue::String(val.to_owned()),
+            })
+            .collect();
+    }
+
+    let val = values[0];
+
+    let coerced = match schema_type {
+        Some(SchemaType::Integer) => val.parse::<i64>().ok().map(JsonValue::from),
+        Some(SchemaType::Number) => val.parse::<f64>().ok().map(JsonValue::from),
+        Some(SchemaType::Boolean) => val.parse::<bool>().ok().map(JsonValue::from),
+        _ => None,
+    };
+
+    coerced.unwrap_or_else(|| JsonValue::String(val.to_owned()))
+}
+
+/// Converts the strings within a deserialized parameter value to the types declared by `schema`.
+fn coerce_value(spec: &Spec, schema: &Schema, val: JsonValue) -> JsonValue {
+    match val {
+        JsonValue::String(val) => coerce(spec, schema, &[&val]),
+        JsonValue::Array(items) => {
+            let items_schema = schema
+                .items
+                .as_ref()
+                .and_then(|items| items.resolve(spec).ok());
+
+            items
+                .into_iter()
+                .map(|item| match items_schema {
+                    Some(ref items_schema) => coerce_value(spec, items_schema, item),
+                    None => item,
+                })
+                .collect()
+        }
+        JsonValue::Object(obj) => JsonValue::Object(
+            obj.into_iter()
+                .map(|(key, val)| {
+                    let property = schema
+                        .properties
+                        .get(&key)
+                        .and_then(|property| property.resolve(spec).ok());
+
+                    let val = match property {
+                        Some(ref property) => coerce_value(spec, property, val),
+                        None => val,
+                    };
+
+                    (key, val)
+                })
+                .collect(),
+        ),
+        val => val,
+    }
+}
+
+fn header_values<'a>(headers: &'a HeaderMap, name: &str) -> Vec<&'a str> {
+    headers
+        .get_all(name)
+        .iter()
+        .filter_map(|val| val.to_str().ok())
+        .collect()
+}
+
+fn path(name: &str) -> super::Path {
+    let mut path = super::Path::new('.');
+    path.push(name);
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn spec() -> Spec {
+        crate::from_reader(
+            r##"
+openapi: 3.1.0
+info: { title: Test, version: "1" }
+paths:
+  /pets:
+    post:
+      parameters:
+        - { name: X-Request-Id, in: header, required: true, schema: { type: string } }
+      requestBody:
+        required: true
+        content:
+          application/json:
+            schema: { $ref: "#/components/schemas/Pet" }
+      responses:
+        "201":
+          description: created
+          headers:
+            Location: { required: true, schema: { type: string } }
+          content:
+            application/json:
+              schema: { $ref: "#/components/schemas/Pet" }
+  /pets/{id}:
+    parameters:
+      - { name: id, in: path, required: true, schema: { type: integer } }
+    get:
+      parameters:
+        - { name: fields, in: query, schema: { type: array, items: { type: string } } }
+        - { name: limit, in: query, schema: { type: integer } }
+      responses:
+        "200":
+          description: pet
+          content:
+            application/json:
+              schema: { $ref: "#/components/schemas/Pet" }
+        4XX:
+          description: error
+  /pets/mine:
+    get:
+      responses:
+        "200":
+          description: pets
+  /files/{name}.json:
+    get:
+      parameters:
+        - { name: name, in: path, required: true, schema: { type: string, pattern: "^[a-z]+$" } }
+        - name: filter
+          in: query
+          style: deepObject
+          schema: { type: object, properties: { size: { type: integer } } }
+        - name: ids
+          in: query
+          explode: false
+          schema: { type: array, items: { type: integer } }
+      responses:
+        "200":
+          description: file
+components:
+  schemas:
+    Pet:
+      type: object
+      required: [id, name]
+      properties:
+        id: { type: integer, readOnly: true }
+        name: { type: string }
+"##
+            .as_bytes(),
+        )
+        .unwrap()
+    }
+
+    fn request(method: Method, path: &str) -> ExchangeRequest {
+        ExchangeRequest {
+            method,
+            path: path.to_owned(),
+            ..ExchangeRequest::default()
+        }
+    }
+
+    fn response(status: u16, body: Option<JsonValue>) -> ExchangeResponse {
+        ExchangeResponse {
+            status: StatusCode::from_u16(status).unwrap(),
+            body,
+            ..ExchangeResponse::default()
+        }
+    }
+
+    fn locations(report: &ValidationReport) -> Vec<&str> {
+        report
+            .findings
+            .iter()
+            .map(|finding| finding.location.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn valid_exchange() {
+        let spec = spec();
+
+        let mut req = request(Method::GET, "/pets/42");
+        req.query = vec![
+            ("fields".to_owned(), "id,name".to_owned()),
+            ("limit".to_owned(), "10".to_owned()),
+        ];
+
+        let report = validate_exchange(
+            &spec,
+            &req,
+            &response(200, Some(json!({ "id": 42, "name": "Rex" }))),
+        );
+        assert!(report.is_valid(), "{}", report);
+        assert_eq!(report.path_template.as_deref(), Some("/pets/{id}"));
+
+        let report = validate_exchange(&spec, &req, &response(404, None));
+        assert!(report.is_valid(), "{}", report);
+
+        let mut req = request(Method::POST, "/pets");
+        req.headers.insert("x-request-id", "abc".parse().unwrap());
+        req.body = Some(json!({ "name": "Rex" }));
+
+        let mut res = response(201, Some(json!({ "id": 1, "name": "Rex" })));
+        res.headers.insert("location", "/pets/1".parse().unwrap());
+
+        let report = validate_exchange(&spec, &req, &res);
+        assert!(report.is_valid(), "{}", report);
+    }
+
+    #[test]
+    fn literal_paths_take_precedence() {
+        let spec = spec();
+
+        let report = validate_exchange(
+            &spec,
+            &request(Method::GET, "/pets/mine"),
+            &response(200, None),
+        );
+        assert!(report.is_valid(), "{}", report);
+        assert_eq!(report.path_template.as_deref(), Some("/pets/mine"));
+    }
+
+    #[test]
+    fn parameter_styles() {
+        let spec = spec();
+
+        let mut req = request(Method::GET, "/files/report.json");
+        req.query = vec![
+            ("filter[size]".to_owned(), "10".to_owned()),
+            ("ids".to_owned(), "1,2".to_owned()),
+        ];
+
+        let report = validate_exchange(&spec, &req, &response(200, None));
+        assert!(report.is_valid(), "{}", report);
+        assert_eq!(report.path_template.as_deref(), Some("/files/{name}.json"));
+
+        let mut req = request(Method::GET, "/files/Report.json");
+        req.query = vec![
+            ("filter[size]".to_owned(), "big".to_owned()),
+            ("ids".to_owned(), "1,x".to_owned()),
+        ];
+
+        let report = validate_exchange(&spec, &req, &response(200, None));
+        assert_eq!(
+            locations(&report),
+            [
+                "request.path.name",
+                "request.query.filter",
+                "request.query.ids"
+            ]
+        );
+
+        let report = validate_exchange(
+            &spec,
+            &request(Method::GET, "/files/report.txt"),
+            &response(200, None),
+        );
+        assert_eq!(locations(&report), ["request.path"]);
+    }
+
+    #[test]
+    fn invalid_exchange() {
+        let spec = spec();
+
+        let mut req = request(Method::GET, "/pets/rex");
+        req.query = vec![("limit".to_owned(), "ten".to_owned())];
+
+        let report = validate_exchange(&spec, &req, &response(200, Some(json!({ "id": 1 }))));
+        assert_eq!(
+            locations(&report),
+            ["request.path.id", "request.query.limit", "response.body"]
+        );
+
+        let report =
+            validate_exchange(&spec, &request(Method::POST, "/pets"), &response(500, None));
+        assert_eq!(
+            locations(&report),
+            [
+                "request.header.X-Request-Id",
+                "request.body",
+                "response.status"
+            ]
+        );
+
+        let mut req = request(Method::POST, "/pets");
+        req.headers.insert("x-request-id", "abc".parse().unwrap());
+        req.headers
+            .insert(http::header::CONTENT_TYPE, "text/plain".parse().unwrap());
+        req.body = Some(json!("Rex"));
+
+        let report = validate_exchange(&spec, &req, &response(201, None));
+        assert_eq!(
+            locations(&report),
+            ["request.body", "response.header.Location"]
+        );
+        assert!(matches!(
+            report.findings[0].error,
+            Error::UndocumentedMediaType(_)
+        ));
+
+        let report = validate_exchange(
+            &spec,
+            &request(Method::DELETE, "/pets/1"),
+            &response(200, None),
+        );
+        assert_eq!(locations(&report), ["request.method"]);
+
+        let report = validate_exchange(
+            &spec,
+            &request(Method::GET, "/owners"),
+            &response(200, None),
+        );
+        assert_eq!(locations(&report), ["request.path"]);
+    }
+}
@@ -0,0 +1,593 @@
+use std::collections::BTreeMap;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::Value as JsonValue;
+
+use super::{Error, Path, Validate};
+
+/// String formats with validation behavior beyond their type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StringFormat {
+    /// Arbitrary binary content, e.g. file uploads. Any string is accepted.
+    Binary,
+
+    /// Base64 encoded characters (RFC 4648). Values must be decodable.
+    Byte,
+
+    /// An email address (RFC 5321).
+    Email,
+
+    /// An internationalized email address (RFC 6531).
+    IdnEmail,
+
+    /// An internet host name (RFC 1123).
+    Hostname,
+
+    /// An absolute URI (RFC 3986).
+    Uri,
+
+    /// An absolute internationalized URI (RFC 3987).
+    Iri,
+
+    /// A JSON pointer (RFC 6901).
+    JsonPointer,
+
+    /// A relative JSON pointer.
+    RelativeJsonPointer,
+
+    /// A full date (RFC 3339), e.g. `2024-02-29`.
+    Date,
+
+    /// A full time with time zone offset (RFC 3339), e.g. `23:59:59Z`.
+    Time,
+
+    /// A duration (ISO 8601, as in RFC 3339 appendix A), e.g. `PT1H30M`.
+    Duration,
+}
+
+impl StringFormat {
+    /// All formats that can be validated.
+    pub const ALL: &'static [StringFormat] = &[
+        Self::Binary,
+        Self::Byte,
+        Self::Email,
+        Self::IdnEmail,
+        Self::Hostname,
+        Self::Uri,
+        Self::Iri,
+        Self::JsonPointer,
+        Self::RelativeJsonPointer,
+        Self::Date,
+        Self::Time,
+        Self::Duration,
+    ];
+
+    /// Formats that are not enabled by [`FormatRegistry::standard`].
+    ///
+    /// Temporal formats are often used loosely in real specs, so they are only validated when
+    /// enabled explicitly.
+    pub const OPT_IN: &'static [StringFormat] = &[Self::Date, Self::Time, Self::Duration];
+
+    /// Looks up the validator for a schema's `format` name. Returns `None` for formats that are
+    /// treated as annotations only.
+    pub fn from_name(format: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|known| known.name() == format)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Binary => "binary",
+            Self::Byte => "byte",
+            Self::Email => "email",
+            Self::IdnEmail => "idn-email",
+            Self::Hostname => "hostname",
+            Self::Uri => "uri",
+            Self::Iri => "iri",
+            Self::JsonPointer => "json-pointer",
+            Self::RelativeJsonPointer => "relative-json-pointer",
+            Self::Date => "date",
+            Self::Time => "time",
+            Self::Duration => "duration",
+        }
+    }
+
+    /// Checks a string against this format.
+    pub fn is_valid(&self, val: &str, strictness: FormatStrictness) -> bool {
+        let strict = strictness == FormatStrictness::Strict;
+
+        match self {
+            Self::Binary => true,
+            Self::Byte => is_base64(val),
+            Self::Email => is_email(val, strict, false),
+            Self::IdnEmail => is_email(val, strict, true),
+            Self::Hostname => is_hostname(val, strict, false),
+            Self::Uri => is_uri(val, strict, false),
+            Self::Iri => is_uri(val, strict, true),
+            Self::JsonPointer => is_json_pointer(val),
+            Self::RelativeJsonPointer => is_relative_json_pointer(val),
+            Self::Date => is_date(val, strict),
+            Self::Time => is_time(val, strict),
+            Self::Duration => is_duration(val),
+        }
+    }
+}
+
+impl Validate for StringFormat {
+    /// Checks that string values conform to the format, leniently. Non-string values are left
+    /// for type validation to reject.
+    fn validate(&self, val: &JsonValue, path: Path) -> Result<(), Error> {
+        FormatValidator::new(*self, FormatStrictness::Lenient).validate(val, path)
+    }
+}
+
+/// How thoroughly a format is checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FormatStrictness {
+    /// Only rejects values that are clearly malformed, e.g. an email address without an `@`.
+    #[default]
+    Lenient,
+
+    /// Checks values against the full grammar of the format.
+    Strict,
+}
+
+/// Validates string values against a format with a given strictness.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatValidator {
+    pub format: StringFormat,
+    pub strictness: FormatStrictness,
+}
+
+impl FormatValidator {
+    pub fn new(format: StringFormat, strictness: FormatStrictness) -> Self {
+        Self { format, strictness }
+    }
+}
+
+impl Validate for FormatValidator {
+    /// Checks that string values conform to the format. Non-string values are left for type
+    /// validation to reject.
+    fn validate(&self, val: &JsonValue, path: Path) -> Result<(), Error> {
+        match val.as_str() {
+            Some(val) if !self.format.is_valid(val, self.strictness) => {
+                Err(Error::InvalidFormat(path, self.format.name()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The set of formats that are validated, along with their strictness.
+///
+/// Formats that are not enabled, including unknown formats, are treated as annotations only, as
+/// JSON Schema specifies by default. The default registry enables no formats; use
+/// [`FormatRegistry::standard`] or [`FormatRegistry::enable`] to validate them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FormatRegistry {
+    formats: BTreeMap<StringFormat, FormatStrictness>,
+}
+
+impl FormatRegistry {
+    /// Creates a registry with no formats enabled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry that enables all [known formats](StringFormat::ALL) leniently, except
+    /// for the [opt-in formats](StringFormat::OPT_IN).
+    pub fn standard() -> Self {
+        Self {
+            formats: StringFormat::ALL
+                .iter()
+                .filter(|format| !StringFormat::OPT_IN.contains(format))
+                .map(|format| (*format, FormatStrictness::Lenient))
+                .collect(),
+        }
+    }
+
+    /// Enables validation of `format` with the given strictness.
+    pub fn enable(mut self, format: StringFormat, strictness: FormatStrictness) -> Self {
+        self.formats.insert(format, strictness);
+        self
+    }
+
+    /// Disables validation of `format`, making it an annotation only.
+    pub fn disable(mut self, format: StringFormat) -> Self {
+        self.formats.remove(&format);
+        self
+    }
+
+    /// Returns the validator for a schema's `format` name, if that format is enabled.
+    pub fn validator(&self, format: &str) -> Option<FormatValidator> {
+        let format = StringFormat::from_name(format)?;
+        let strictness = *self.formats.get(&format)?;
+
+        Some(FormatValidator::new(format, strictness))
+    }
+}
+
+/// Checks that a string is padded, standard alphabet base64.
+fn is_base64(val: &str) -> bool {
+    // padded base64 is always made up of complete 4 character groups
+    if val.as_bytes().chunks(4).any(|group| group.len() != 4) {
+        return false;
+    }
+
+    let data = val.trim_end_matches('=');
+
+    val.len() - data.len() <= 2
+        && data
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'+' || b == b'/')
+}
+
+/// Checks for a `local@domain` address. Strict mode requires a dot-atom local part and a valid
+/// host name domain.
+fn is_email(val: &str, strict: bool, idn: bool) -> bool {
+    let (local, domain) = match val.rsplit_once('@') {
+        Some(parts) => parts,
+        None => return false,
+    };
+
+    if local.is_empty() || domain.is_empty() || val.chars().any(char::is_whitespace) {
+        return false;
+    }
+
+    if !strict {
+        return true;
+    }
+
+    let is_atom_char = |c: char| {
+        c.is_ascii_alphanumeric() || "!#$%&'*+-/=?^_`{|}~".contains(c) || (idn && !c.is_ascii())
+    };
+
+    local
+        .split('.')
+        .all(|atom| !atom.is_empty() && atom.chars().all(is_atom_char))
+        && is_hostname(domain, true, idn)
+}
+
+/// Checks for a host name. Strict mode requires dot separated labels of at most 63 letters,
+/// digits and hyphens, not starting or ending with a hyphen.
+fn is_hostname(val: &str, strict: bool, idn: bool) -> bool {
+    if val.is_empty() || val.len() > 253 || val.chars().any(char::is_whitespace) {
+        return false;
+    }
+
+    if !strict {
+        return true;
+    }
+
+    val.split('.').all(|label| {
+        !label.is_empty()
+            && label.len() <= 63
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || (idn && !c.is_ascii()))
+    })
+}
+
+/// Checks for an absolute URI. Lenient mode only requires a scheme.
+fn is_uri(val: &str, strict: bool, iri: bool) -> bool {
+    if val.chars().any(char::is_whitespace) || (!iri && !val.is_ascii()) {
+        return false;
+    }
+
+    if strict {
+        return url::Url::parse(val).is_ok();
+    }
+
+    match val.split_once(':') {
+        Some((scheme, _)) => {
+            matches!(scheme.chars().next(), Some(c) if c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+        }
+        None => false,
+    }
+}
+
+/// Checks for an empty string or `/` prefixed reference tokens with valid `~` escapes.
+fn is_json_pointer(val: &str) -> bool {
+    if !val.is_empty() && !val.starts_with('/') {
+        return false;
+    }
+
+    let mut chars = val.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '~' && !matches!(chars.next(), Some('0' | '1')) {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Checks for a non-negative integer followed by `#` or a JSON pointer.
+fn is_relative_json_pointer(val: &str) -> bool {
+    let digits = val.len() - val.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let (num, rest) = val.split_at(digits);
+
+    !num.is_empty()
+        && (num == "0" || !num.starts_with('0'))
+        && (rest == "#" || is_json_pointer(rest))
+}
+
+static RE_DATE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\d{4})-(\d{2})-(\d{2})$").unwrap());
+static RE_TIME: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\d{2}):(\d{2}):(\d{2})(\.\d+)?([Zz]|[+-](\d{2}):(\d{2}))$").unwrap()
+});
+static RE_DURATION: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^P(\d+W|(\d+Y)?(\d+M)?(\d+D)?(T(\d+H)?(\d+M)?(\d+S)?)?)$").unwrap());
+
+/// Checks for a `YYYY-MM-DD` date. Strict mode requires the day to exist in its month.
+fn is_date(val: &str, strict: bool) -> bool {
+    let caps = match RE_DATE.captures(val) {
+        Some(caps) => caps,
+        None => return false,
+    };
+
+    if !strict {
+        return true;
+    }
+
+    let num = |idx: usize| caps[idx].parse::<u32>().unwrap();
+    let (year, month, day) = (num(1), num(2), num(3));
+    let is_leap_year = matches!((year % 4, year % 100, year % 400), (0, 1.., _) | (_, _, 0));
+
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year => 29,
+        2 => 28,
+        _ => return false,
+    };
+
+    (1..=days_in_month).contains(&day)
+}
+
+/// Checks for an `HH:MM:SS` time with a `Z` or numeric offset. Strict mode requires each field
+/// to be in range, allowing leap seconds.
+fn is_time(val: &str, strict: bool) -> bool {
+    let caps = match RE_TIME.captures(val) {
+        Some(caps) => caps,
+        None => return false,
+    };
+
+    if !strict {
+        return true;
+    }
+
+    let num = |idx: usize| {
+        caps.get(idx)
+            .map_or(0, |m| m.as_str().parse::<u32>().unwrap())
+    };
+
+    num(1) < 24 && num(2) < 60 && num(3) <= 60 && num(6) < 24 && num(7) < 60
+}
+
+/// Checks for a `P`-prefixed duration with at least one component, and at least one time
+/// component after a `T`.
+fn is_duration(val: &str) -> bool {
+    RE_DURATION.is_match(val) && val != "P" && !val.ends_with('T')
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{super::tests::*, *};
+
+    #[test]
+    fn binary_validation() {
+        let v = StringFormat::Binary;
+
+        valid_vs_invalid!(v, &[&STRING, &json!(""), &json!("\u{0}\u{1}")], &[]);
+    }
+
+    #[test]
+    fn byte_validation() {
+        let v = StringFormat::Byte;
+
+        valid_vs_invalid!(
+            v,
+            &[
+                &json!(""),
+                &json!("c3RyaW5n"),
+                &json!("YQ=="),
+                &json!("YWI=")
+            ],
+            &[&STRING, &json!("YQ="), &json!("YQ==="), &json!("c3Ry-W5n")],
+        );
+    }
+
+    #[test]
+    fn email_validation() {
+        let v = StringFormat::Email;
+
+        valid_vs_invalid!(
+            v,
+            &[&json!("a@example.com"), &json!("a..b@-example")],
+            &[&STRING, &json!("@example.com"), &json!("a@")],
+        );
+
+        let v = FormatValidator::new(StringFormat::Email, FormatStrictness::Strict);
+
+        valid_vs_invalid!(
+            v,
+            &[&json!("first.last+tag@example.com")],
+            &[
+                &json!("a..b@example.com"),
+                &json!("a@-example"),
+                &json!("é@example.com")
+            ],
+        );
+
+        let v = FormatValidator::new(StringFormat::IdnEmail, FormatStrictness::Strict);
+
+        valid_vs_invalid!(v, &[&json!("é@exämple.com")], &[&json!("a@ex ample.com")]);
+    }
+
+    #[test]
+    fn hostname_validation() {
+        let v = FormatValidator::new(StringFormat::Hostname, FormatStrictness::Strict);
+
+        valid_vs_invalid!(
+            v,
+            &[&json!("example.com"), &json!("a-1.example")],
+            &[
+                &json!(""),
+                &json!("-a.com"),
+                &json!("a_b.com"),
+                &json!("a..com")
+            ],
+        );
+    }
+
+    #[test]
+    fn uri_validation() {
+        let v = StringFormat::Uri;
+
+        valid_vs_invalid!(
+            v,
+            &[&json!("https://example.com/a"), &json!("urn:isbn:123")],
+            &[
+                &json!("/relative"),
+                &json!("1http://a"),
+                &json!("https://ex ample.com")
+            ],
+        );
+
+        let v = FormatValidator::new(StringFormat::Uri, FormatStrictness::Strict);
+
+        valid_vs_invalid!(
+            v,
+            &[&json!("https://example.com/a?b#c")],
+            &[&json!("http://[::1"), &json!("https://example.com/ä")],
+        );
+
+        let v = StringFormat::Iri;
+
+        valid_vs_invalid!(v, &[&json!("https://example.com/ä")], &[&json!("ä")]);
+    }
+
+    #[test]
+    fn json_pointer_validation() {
+        let v = StringFormat::JsonPointer;
+
+        valid_vs_invalid!(
+            v,
+            &[&json!(""), &json!("/a/b"), &json!("/a~0b/~1")],
+            &[&json!("a/b"), &json!("/a~2"), &json!("/a~")],
+        );
+
+        let v = StringFormat::RelativeJsonPointer;
+
+        valid_vs_invalid!(
+            v,
+            &[&json!("0"), &json!("1/a"), &json!("10#")],
+            &[&json!(""), &json!("01/a"), &json!("/a"), &json!("1a")],
+        );
+    }
+
+    #[test]
+    fn temporal_validation() {
+        let v = StringFormat::Date;
+
+        valid_vs_invalid!(
+            v,
+            &[&json!("2024-02-29"), &json!("2023-02-30")],
+            &[&STRING, &json!("2024-2-29"), &json!("2024-02-29T00:00:00Z")],
+        );
+
+        let v = FormatValidator::new(StringFormat::Date, FormatStrictness::Strict);
+
+        valid_vs_invalid!(
+            v,
+            &[&json!("2024-02-29"), &json!("2000-02-29")],
+            &[
+                &json!("2023-02-29"),
+                &json!("1900-02-29"),
+                &json!("2024-13-01")
+            ],
+        );
+
+        let v = StringFormat::Time;
+
+        valid_vs_invalid!(
+            v,
+            &[&json!("23:59:59Z"), &json!("08:30:00.5+02:00")],
+            &[&STRING, &json!("23:59:59"), &json!("23:59Z")],
+        );
+
+        let v = FormatValidator::new(StringFormat::Time, FormatStrictness::Strict);
+
+        valid_vs_invalid!(
+            v,
+            &[&json!("23:59:60Z")],
+            &[&json!("24:00:00Z"), &json!("12:00:00+25:00")],
+        );
+
+        let v = StringFormat::Duration;
+
+        valid_vs_invalid!(
+            v,
+            &[
+                &json!("PT1H30M"),
+                &json!("P1Y2M3DT4H5M6S"),
+                &json!("P2W"),
+                &json!("P1D")
+            ],
+            &[
+                &STRING,
+                &json!("P"),
+                &json!("PT"),
+                &json!("P1DT"),
+                &json!("PT1H30"),
+                &json!("1H30M")
+            ],
+        );
+    }
+
+    #[test]
+    fn format_registry() {
+        assert_eq!(FormatRegistry::new().validator("byte"), None);
+
+        let registry = FormatRegistry::standard();
+        assert_eq!(
+            registry.validator("email"),
+            Some(FormatValidator::new(
+                StringFormat::Email,
+                FormatStrictness::Lenient
+            ))
+        );
+        assert_eq!(registry.validator("unknown"), None);
+
+        let registry = registry
+            .disable(StringFormat::Email)
+            .enable(StringFormat::Hostname, FormatStrictness::Strict);
+        assert_eq!(registry.validator("email"), None);
+        assert_eq!(
+            registry.validator("hostname").unwrap().strictness,
+            FormatStrictness::Strict
+        );
+
+        assert_eq!(FormatRegistry::standard().validator("duration"), None);
+        assert_eq!(
+            FormatRegistry::new()
+                .enable(StringFormat::Duration, FormatStrictness::Lenient)
+                .validator("duration")
+                .unwrap()
+                .format,
+            StringFormat::Duration
+        );
+    }
+}
@@ -8,19 +8,33 @@ use serde_json::Value as JsonValue;
 #[macro_use]
 mod test_macros;
 
+mod compiled;
 mod error;
+mod example;
+mod exchange;
+mod format;
+mod multiple_of;
 mod path;
+mod pattern;
 mod required;
 mod r#type;
+mod unevaluated;
 mod validator;
 
+pub use compiled::*;
 pub use error::*;
+pub use example::*;
+pub use exchange::*;
+pub use format::*;
+pub use multiple_of::*;
 pub use path::Path;
+pub use pattern::*;
 pub use r#type::*;
 pub use required::*;
+pub use unevaluated::*;
 pub use validator::*;
 
-pub trait Validate: Debug {
+pub trait Validate: Debug + Send + Sync {
     fn validate(&self, val: &JsonValue, path: Path) -> Result<(), Error>;
 }
 
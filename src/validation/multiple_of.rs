@@ -0,0 +1,81 @@
+use serde_json::{Number, Value as JsonValue};
+
+use super::{Error, Path, Validate};
+
+/// Validates that numbers are a multiple of a `multipleOf` divisor.
+#[derive(Debug, Clone)]
+pub struct MultipleOf {
+    divisor: Number,
+    divisor_f64: f64,
+}
+
+impl MultipleOf {
+    /// Returns `None` if `divisor` is not strictly positive, which `multipleOf` does not allow.
+    pub fn new(divisor: Number) -> Option<Self> {
+        let divisor_f64 = divisor.as_f64().filter(|divisor| *divisor > 0.0)?;
+
+        Some(Self {
+            divisor,
+            divisor_f64,
+        })
+    }
+}
+
+impl Validate for MultipleOf {
+    /// Checks that numbers divide evenly by the divisor, exactly if both are integers and
+    /// otherwise allowing for floating point error in the remainder. Non-number values are left
+    /// for type validation to reject.
+    fn validate(&self, val: &JsonValue, path: Path) -> Result<(), Error> {
+        let num = match val {
+            JsonValue::Number(num) => num,
+            _ => return Ok(()),
+        };
+
+        let is_multiple = match (as_i128(num), as_i128(&self.divisor)) {
+            (Some(val), Some(divisor)) => val % divisor == 0,
+            _ => {
+                let remainder = num.as_f64().unwrap_or_default().abs() % self.divisor_f64;
+                remainder.min(self.divisor_f64 - remainder) <= self.divisor_f64 * 1e-9
+            }
+        };
+
+        if is_multiple {
+            Ok(())
+        } else {
+            Err(Error::NotMultipleOf(path, self.divisor.clone()))
+        }
+    }
+}
+
+fn as_i128(num: &Number) -> Option<i128> {
+    num.as_i64()
+        .map(i128::from)
+        .or_else(|| num.as_u64().map(i128::from))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{super::tests::*, *};
+
+    #[test]
+    fn multiple_of_validation() {
+        let v = MultipleOf::new(Number::from_f64(0.1).unwrap()).unwrap();
+
+        valid_vs_invalid!(
+            v,
+            &[&INTEGER, &FLOAT, &json!(0.3), &STRING],
+            &[&json!(0.15)]
+        );
+
+        let v = MultipleOf::new(Number::from(2)).unwrap();
+        valid_vs_invalid!(
+            v,
+            &[&json!(4), &json!(-6.0), &json!(u64::MAX - 1)],
+            &[&INTEGER, &FLOAT, &json!(1000000001), &json!(3000000001_u64)]
+        );
+
+        assert!(MultipleOf::new(Number::from(0)).is_none());
+    }
+}
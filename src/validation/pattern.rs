@@ -0,0 +1,49 @@
+use regex::Regex;
+use serde_json::Value as JsonValue;
+
+use super::{Error, Path, Validate};
+
+/// Validates string values against a `pattern` regular expression, compiled once.
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    regex: Regex,
+}
+
+impl Pattern {
+    /// Compiles `pattern`, returning `None` if it is not a valid regular expression.
+    ///
+    /// Patterns are unanchored, as in JSON Schema. ECMA 262 features that the `regex` crate does
+    /// not support, such as lookaround, make a pattern invalid.
+    pub fn new(pattern: &str) -> Option<Self> {
+        Regex::new(pattern).ok().map(|regex| Self { regex })
+    }
+}
+
+impl Validate for Pattern {
+    /// Checks that string values match the pattern. Non-string values are left for type
+    /// validation to reject.
+    fn validate(&self, val: &JsonValue, path: Path) -> Result<(), Error> {
+        match val.as_str() {
+            Some(val) if !self.regex.is_match(val) => {
+                Err(Error::PatternMismatch(path, self.regex.as_str().to_owned()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{super::tests::*, *};
+
+    #[test]
+    fn pattern_validation() {
+        let v = Pattern::new("^im a").unwrap();
+
+        valid_vs_invalid!(v, &[&STRING, &INTEGER, &NULL], &[&json!("not a string")]);
+
+        assert!(Pattern::new("(?=a)").is_none());
+    }
+}
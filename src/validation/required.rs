@@ -6,19 +6,32 @@ use crate::spec::SchemaType;
 #[derive(Debug, Clone)]
 pub struct RequiredFields {
     fields: Vec<String>,
+    allow_non_object: bool,
 }
 
 impl RequiredFields {
     pub fn new(vs: Vec<String>) -> Self {
-        Self { fields: vs }
+        Self {
+            fields: vs,
+            allow_non_object: false,
+        }
+    }
+
+    /// Accept values that are not objects instead of reporting a type mismatch, as for schemas
+    /// that declare no `type`.
+    pub fn set_allow_non_object(mut self, allow_non_object: bool) -> Self {
+        self.allow_non_object = allow_non_object;
+        self
     }
 }
 
 impl Validate for RequiredFields {
     fn validate(&self, val: &JsonValue, path: Path) -> Result<(), Error> {
-        let obj = val
-            .as_object()
-            .ok_or_else(|| Error::TypeMismatch(path.clone(), SchemaType::Object))?;
+        let obj = match val.as_object() {
+            Some(obj) => obj,
+            None if self.allow_non_object => return Ok(()),
+            None => return Err(Error::TypeMismatch(path, SchemaType::Object)),
+        };
 
         for field in &self.fields {
             let path = path.extend(field);
@@ -46,4 +59,12 @@ mod tests {
             &[&NULL, &OBJ_EMPTY, &OBJ_NUMS],
         );
     }
+
+    #[test]
+    fn required_fields_any_type() {
+        let v = RequiredFields::new(vec!["name".to_owned(), "price".to_owned()])
+            .set_allow_non_object(true);
+
+        valid_vs_invalid!(v, &[&OBJ_MIXED, &NULL, &ARRAY_STRS], &[&OBJ_EMPTY]);
+    }
 }
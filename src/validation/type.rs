@@ -35,6 +35,9 @@ impl DataType {
     pub fn object() -> Self {
         Self::new(SchemaType::Object)
     }
+    pub fn null() -> Self {
+        Self::new(SchemaType::Null)
+    }
 
     pub fn set_nullable(self, nullable: bool) -> Self {
         Self { nullable, ..self }
@@ -68,7 +71,7 @@ impl Validate for DataType {
             JsonValue::Array(_) => SchemaType::Array,
             JsonValue::Object(_) => SchemaType::Object,
 
-            // already checked and returned
+            JsonValue::Null if self.r#type == SchemaType::Null => return Ok(()),
             JsonValue::Null => return Err(Error::InvalidNull(path)),
         };
 
@@ -153,6 +156,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn null_validation() {
+        let v = DataType::null();
+
+        valid_vs_invalid!(
+            v,
+            &[&NULL],
+            &[&TRUE, &INTEGER, &FLOAT, &STRING, &ARRAY_INTS, &OBJ_EMPTY],
+        );
+    }
+
     #[test]
     fn array_validation() {
         let v = DataType::array();
@@ -0,0 +1,47 @@
+use serde_json::Value as JsonValue;
+
+use super::{Error, Path, Validate};
+
+/// Rejects object properties that were not evaluated by any subschema.
+///
+/// Used to enforce `unevaluatedProperties: false` on composed schemas.
+#[derive(Debug, Clone)]
+pub struct UnevaluatedProperties {
+    evaluated: Vec<String>,
+}
+
+impl UnevaluatedProperties {
+    pub fn new(evaluated: Vec<String>) -> Self {
+        Self { evaluated }
+    }
+}
+
+impl Validate for UnevaluatedProperties {
+    fn validate(&self, val: &JsonValue, path: Path) -> Result<(), Error> {
+        let obj = match val.as_object() {
+            Some(obj) => obj,
+            None => return Ok(()),
+        };
+
+        match obj.keys().find(|prop| !self.evaluated.contains(prop)) {
+            Some(prop) => Err(Error::UndocumentedField(path.extend(prop).to_string())),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::tests::*, *};
+
+    #[test]
+    fn unevaluated_properties_validation() {
+        let v = UnevaluatedProperties::new(vec![s("name"), s("price")]);
+
+        valid_vs_invalid!(
+            v,
+            &[&OBJ_EMPTY, &OBJ_MIXED, &STRING],
+            &[&OBJ_NUMS, &OBJ_MIXED2],
+        );
+    }
+}
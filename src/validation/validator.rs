@@ -1,22 +1,48 @@
-use std::{collections::BTreeMap, fmt};
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    fmt,
+    sync::{Arc, Weak},
+};
 
-use super::{AggregateError, DataType, Error, Path, RequiredFields, Validate};
+use super::{
+    AggregateError, DataType, Error, FormatRegistry, MultipleOf, Path, Pattern, RequiredFields,
+    UnevaluatedProperties, Validate,
+};
 use crate::{
-    spec::{Error as SchemaError, SchemaType},
+    spec::{
+        BooleanOrSchema, Error as SchemaError, ObjectOrReference, SchemaDialect, SchemaType,
+        SchemaTypeSet,
+    },
     Schema, Spec,
 };
 
-use log::trace;
+use log::{trace, warn};
+use once_cell::sync::OnceCell;
 use serde_json::Value as JsonValue;
 
 #[derive(Debug)]
 pub enum ValidationBranch {
     Leaf,
     Array(Box<ValidationTree>),
+
+    /// Leading items validated by position, with the rest validated by the optional tree.
+    Tuple(Vec<ValidationTree>, Option<Box<ValidationTree>>),
     Object(BTreeMap<String, ValidationTree>),
+
+    /// Known properties validated by name, with all other properties validated by the
+    /// `additionalProperties` tree.
+    Map(BTreeMap<String, ValidationTree>, Box<ValidationTree>),
     AllOf(Vec<ValidationTree>),
     OneOf(Vec<ValidationTree>),
     AnyOf(Vec<ValidationTree>),
+
+    /// A referenced schema that refers back to itself, shared with the
+    /// [`Recursive`](Self::Recursive) trees within it.
+    Ref(Arc<OnceCell<ValidationTree>>),
+
+    /// A reference back to an enclosing [`Ref`](Self::Ref) tree, which owns it.
+    Recursive(Weak<OnceCell<ValidationTree>>),
 }
 
 pub struct ValidationTree {
@@ -25,7 +51,43 @@ pub struct ValidationTree {
 }
 
 impl ValidationTree {
+    /// Creates a validation tree, treating string formats as annotations only.
+    ///
+    /// Use [`ValidationTree::from_schema_with_formats`] to validate formats.
     pub fn from_schema(schema: &Schema, spec: &Spec) -> Result<ValidationTree, SchemaError> {
+        Self::from_schema_with_formats(schema, spec, &FormatRegistry::default())
+    }
+
+    /// Creates a validation tree, validating string formats as configured by `formats`.
+    ///
+    /// Keywords are interpreted according to the schema's dialect; see [`Schema::dialect`] and
+    /// [`Spec::schema_dialect`]. Each referenced schema is expanded where it is used, except
+    /// within itself, so recursive schemas result in finite trees.
+    pub fn from_schema_with_formats(
+        schema: &Schema,
+        spec: &Spec,
+        formats: &FormatRegistry,
+    ) -> Result<ValidationTree, SchemaError> {
+        let dialect = schema.dialect(spec.schema_dialect()?)?;
+
+        let ctx = Builder {
+            spec,
+            formats,
+            expanding: RefCell::new(vec![]),
+        };
+
+        Self::build(schema, &ctx, dialect)
+    }
+
+    fn build(
+        schema: &Schema,
+        ctx: &Builder<'_>,
+        dialect: SchemaDialect,
+    ) -> Result<ValidationTree, SchemaError> {
+        let subtree = |schema: &Schema| Self::build(schema, ctx, schema.dialect(dialect)?);
+        let member =
+            |schema_ref: &ObjectOrReference<Schema>| Self::member(schema_ref, ctx, dialect);
+
         trace!(
             "creating validation tree from schema: {}",
             &schema.title.as_deref().unwrap_or("_unnamed_")
@@ -36,20 +98,87 @@ impl ValidationTree {
             branch: ValidationBranch::Leaf,
         };
 
-        if let Some(type_) = schema.schema_type {
-            trace!("restricting data type: {:?}", type_);
+        // OpenAPI 3.0 `nullable` and a 3.1 type list containing `null` are equivalent
+        let nullable = schema.nullable.unwrap_or(false)
+            || matches!(schema.schema_type, Some(ref types) if types.contains(SchemaType::Null));
+
+        if let Some(types) = schema.schema_type.as_ref() {
+            trace!("restricting data type: {:?}", types);
+
+            let non_null = types
+                .types()
+                .iter()
+                .copied()
+                .filter(|type_| *type_ != SchemaType::Null)
+                .collect::<Vec<_>>();
+
+            // a type list accepts an instance of any of its types, and only the keywords applying
+            // to that type are checked, so each type is validated by its own subtree
+            if non_null.len() > 1 {
+                let vs = non_null
+                    .into_iter()
+                    .map(|type_| {
+                        let types = if nullable {
+                            SchemaTypeSet::Multiple(vec![type_, SchemaType::Null])
+                        } else {
+                            SchemaTypeSet::Single(type_)
+                        };
+
+                        let schema = Schema {
+                            schema_type: Some(types),
+                            ..schema.clone()
+                        };
+
+                        Self::build(&schema, ctx, dialect)
+                    })
+                    .collect::<Result<_, _>>()?;
 
-            let type_val = if let Some(nullable) = schema.nullable {
-                DataType::new(type_).set_nullable(nullable)
-            } else {
-                DataType::new(type_)
-            };
+                valtree.branch = ValidationBranch::AnyOf(vs);
+                return Ok(valtree);
+            }
 
-            valtree.validators.push(Box::new(type_val));
+            match types.single_non_null() {
+                Some(type_) => {
+                    let type_val = DataType::new(type_).set_nullable(nullable);
+                    valtree.validators.push(Box::new(type_val));
+                }
+                None if types.types().iter().all(|type_| *type_ == SchemaType::Null) => {
+                    valtree.validators.push(Box::new(DataType::null()));
+                }
+                None => {}
+            }
         }
 
-        match schema.schema_type {
-            Some(SchemaType::Object) => {
+        if let Some(format) = schema
+            .format
+            .as_deref()
+            .and_then(|format| ctx.formats.validator(format))
+        {
+            trace!("restricting string format: {}", format.format.name());
+
+            valtree.validators.push(Box::new(format));
+        }
+
+        if let Some(pattern) = schema.pattern.as_deref() {
+            match Pattern::new(pattern) {
+                Some(pattern) => valtree.validators.push(Box::new(pattern)),
+                None => warn!("ignoring unsupported pattern: {}", pattern),
+            }
+        }
+
+        if let Some(divisor) = schema.multiple_of.clone() {
+            match MultipleOf::new(divisor) {
+                Some(multiple_of) => valtree.validators.push(Box::new(multiple_of)),
+                None => warn!("ignoring non-positive `multipleOf`"),
+            }
+        }
+
+        match schema
+            .schema_type
+            .as_ref()
+            .map(|types| types.single_non_null())
+        {
+            Some(Some(SchemaType::Object)) => {
                 trace!(
                     "adding object validators: props {}",
                     schema
@@ -63,30 +192,52 @@ impl ValidationTree {
                 let vls = schema
                     .properties
                     .iter()
-                    .map(|(prop, schema)| {
-                        let sub_schema = schema.resolve(spec).unwrap();
-                        let valtree = ValidationTree::from_schema(&sub_schema, spec).unwrap();
-                        (prop.clone(), valtree)
-                    })
-                    .collect();
+                    .map(|(prop, schema_ref)| Ok((prop.clone(), member(schema_ref)?)))
+                    .collect::<Result<_, SchemaError>>()?;
 
-                valtree.branch = ValidationBranch::Object(vls);
+                valtree.branch = match schema.additional_properties.as_ref() {
+                    Some(BooleanOrSchema::Schema(schema_ref)) => {
+                        let additional = member(schema_ref)?;
+                        ValidationBranch::Map(vls, Box::new(additional))
+                    }
+                    Some(BooleanOrSchema::Boolean(true)) => {
+                        let additional = subtree(&Schema::default())?;
+                        ValidationBranch::Map(vls, Box::new(additional))
+                    }
+                    Some(BooleanOrSchema::Boolean(false)) | None => ValidationBranch::Object(vls),
+                };
 
                 if !schema.required.is_empty() {
                     trace!("required fields: {:?}", &schema.required);
 
-                    let req_fields = RequiredFields::new(schema.required.clone());
+                    // a null instance of a nullable object has no fields to require
+                    let req_fields =
+                        RequiredFields::new(schema.required.clone()).set_allow_non_object(nullable);
                     valtree.validators.push(Box::new(req_fields));
                 }
             }
 
-            Some(SchemaType::Array) => {
+            Some(Some(SchemaType::Array)) => {
                 trace!("adding array validators");
 
-                if let Some(schema_ref) = schema.items.as_ref() {
-                    let sub_schema = schema_ref.resolve(spec).unwrap();
-                    let vls = ValidationTree::from_schema(&sub_schema, spec).unwrap();
+                let items = match schema.items.as_ref() {
+                    Some(schema_ref) => Some(member(schema_ref)?),
+                    None => None,
+                };
 
+                // `prefixItems` is not a keyword before 2020-12
+                let prefix_items = match dialect {
+                    SchemaDialect::Draft2020_12 => schema
+                        .prefix_items
+                        .iter()
+                        .map(member)
+                        .collect::<Result<Vec<_>, _>>()?,
+                    SchemaDialect::Draft07 => vec![],
+                };
+
+                if !prefix_items.is_empty() {
+                    valtree.branch = ValidationBranch::Tuple(prefix_items, items.map(Box::new))
+                } else if let Some(vls) = items {
                     valtree.branch = ValidationBranch::Array(Box::new(vls))
                 }
             }
@@ -94,38 +245,50 @@ impl ValidationTree {
             Some(_) => {}
 
             None => {
+                // properties may be declared by composition members, so only presence is checked
+                if !schema.required.is_empty() {
+                    trace!("required fields: {:?}", &schema.required);
+
+                    let req_fields =
+                        RequiredFields::new(schema.required.clone()).set_allow_non_object(true);
+                    valtree.validators.push(Box::new(req_fields));
+                }
+
                 // allOf without a `type: object` declaration
                 if !schema.all_of.is_empty() {
-                    let vs = schema
+                    let members = schema
                         .all_of
                         .iter()
-                        .map(|schema_ref| schema_ref.resolve(spec).unwrap())
-                        .map(|schema| ValidationTree::from_schema(&schema, spec).unwrap())
-                        .collect();
+                        .map(|schema_ref| schema_ref.resolve(ctx.spec))
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    if schema.unevaluated_properties == Some(BooleanOrSchema::Boolean(false)) {
+                        let evaluated = members
+                            .iter()
+                            .flat_map(|member| member.properties.keys().cloned())
+                            .collect();
+
+                        trace!("disallowing unevaluated properties");
+
+                        let uneval = UnevaluatedProperties::new(evaluated);
+                        valtree.validators.push(Box::new(uneval));
+                    }
+
+                    let vs = schema.all_of.iter().map(member).collect::<Result<_, _>>()?;
 
                     valtree.branch = ValidationBranch::AllOf(vs)
                 }
 
                 // anyOf without a `type: object` declaration
                 if !schema.any_of.is_empty() {
-                    let vs = schema
-                        .any_of
-                        .iter()
-                        .map(|schema_ref| schema_ref.resolve(spec).unwrap())
-                        .map(|schema| ValidationTree::from_schema(&schema, spec).unwrap())
-                        .collect();
+                    let vs = schema.any_of.iter().map(member).collect::<Result<_, _>>()?;
 
                     valtree.branch = ValidationBranch::AnyOf(vs)
                 }
 
                 // oneOf without a `type: object` declaration
                 if !schema.one_of.is_empty() {
-                    let vs = schema
-                        .one_of
-                        .iter()
-                        .map(|schema_ref| schema_ref.resolve(spec).unwrap())
-                        .map(|schema| ValidationTree::from_schema(&schema, spec).unwrap())
-                        .collect();
+                    let vs = schema.one_of.iter().map(member).collect::<Result<_, _>>()?;
 
                     valtree.branch = ValidationBranch::OneOf(vs)
                 }
@@ -135,6 +298,62 @@ impl ValidationTree {
         Ok(valtree)
     }
 
+    /// Builds the tree of a subschema, expanding it if it is a reference.
+    ///
+    /// A reference to a schema that is already being expanded becomes a
+    /// [`ValidationBranch::Recursive`] tree, and the expanded schema is then wrapped in a
+    /// [`ValidationBranch::Ref`] tree owning it.
+    fn member(
+        schema_ref: &ObjectOrReference<Schema>,
+        ctx: &Builder<'_>,
+        dialect: SchemaDialect,
+    ) -> Result<ValidationTree, SchemaError> {
+        let ref_path = match schema_ref {
+            ObjectOrReference::Ref { ref_path, .. } => ref_path,
+            ObjectOrReference::Object(schema) => {
+                return Self::build(schema, ctx, schema.dialect(dialect)?)
+            }
+        };
+
+        let recursive = ctx
+            .expanding
+            .borrow()
+            .iter()
+            .find(|(expanding, _)| expanding == ref_path)
+            .map(|(_, target)| Arc::downgrade(target));
+
+        if let Some(target) = recursive {
+            trace!("recursive reference: {}", ref_path);
+
+            return Ok(ValidationTree {
+                validators: vec![],
+                branch: ValidationBranch::Recursive(target),
+            });
+        }
+
+        let schema = schema_ref.resolve(ctx.spec)?;
+        let target = Arc::new(OnceCell::new());
+
+        ctx.expanding
+            .borrow_mut()
+            .push((ref_path.clone(), Arc::clone(&target)));
+        let tree = Self::build(&schema, ctx, schema.dialect(dialect)?);
+        ctx.expanding.borrow_mut().pop();
+
+        let tree = tree?;
+
+        if Arc::weak_count(&target) == 0 {
+            return Ok(tree);
+        }
+
+        target.get_or_init(|| tree);
+
+        Ok(ValidationTree {
+            validators: vec![],
+            branch: ValidationBranch::Ref(target),
+        })
+    }
+
     #[allow(dead_code)]
     fn first_noncomposite_type_is_object(&self) -> bool {
         match &self.branch {
@@ -157,15 +376,55 @@ impl ValidationTree {
 
     /// top level validation entry-point
     pub fn validate(&self, val: &JsonValue) -> Result<(), Error> {
-        let path = Path::new('.');
-        self.validate_inner(val, path)
+        self.validate_with(val, &ValidationOptions::default())
+            .map_err(|mut errors| errors.errors.remove(0))
+    }
+
+    /// Validates `val`, returning the errors found as configured by `options`.
+    ///
+    /// The returned list is never empty. Unless [`ValidationOptions::collect_all`] is set, it
+    /// contains only the first error, exactly as returned by [`ValidationTree::validate`].
+    pub fn validate_with(
+        &self,
+        val: &JsonValue,
+        options: &ValidationOptions,
+    ) -> Result<(), ValidationErrors> {
+        let mut errors = Errors::new(options);
+        self.validate_inner(val, Path::new('.'), &mut errors);
+
+        if errors.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationErrors {
+                errors: errors.errors,
+                truncated: errors.truncated,
+            })
+        }
+    }
+
+    /// Runs this level's own validators, returning true if any of them failed.
+    fn validate_own(&self, val: &JsonValue, path: &Path, errors: &mut Errors) -> bool {
+        let before = errors.errors.len();
+
+        for v in &self.validators {
+            if let Err(err) = v.validate(val, path.clone()) {
+                errors.push(err);
+
+                if errors.is_done() {
+                    break;
+                }
+            }
+        }
+
+        errors.errors.len() > before
     }
 
     /// trigger sub-valtrees validation
-    fn validate_inner(&self, val: &JsonValue, path: Path) -> Result<(), Error> {
+    fn validate_inner(&self, val: &JsonValue, path: Path, errors: &mut Errors) {
         match &self.branch {
             ValidationBranch::AllOf(vs) => {
-                // TODO: error if any self validations
+                // validate own valtree level (e.g. unevaluated properties) and throw any errors
+                self.validate_own(val, &path, errors);
 
                 // it's arguable if this should be an error, it may be okay to have an
                 // allOf that's not an object, and even so the validation error will
@@ -178,6 +437,10 @@ impl ValidationTree {
                 //     .ok_or_else(|| Error::TypeMismatch(path.clone(), SchemaType::Object))?;
 
                 for v in vs {
+                    if errors.is_done() {
+                        return;
+                    }
+
                     // ~each sub-valtree must be object type~
                     // if !v.first_noncomposite_type_is_object() {
                     //     // TODO: error variant
@@ -187,14 +450,11 @@ impl ValidationTree {
                     // match this val against each sub-valtree ignoring extraneous
                     // field errors (TODO: this enables false positive cases)
 
-                    match v.validate_inner(val, path.clone()) {
-                        // TODO: in allOf schemas extraneous fields should be evaluated as a whole
-                        Ok(_) | Err(Error::UndocumentedField(_)) => continue,
-                        Err(err) => return Err(err),
-                    }
+                    // TODO: in allOf schemas extraneous fields should be evaluated as a whole
+                    let mut member = errors.member();
+                    v.validate_inner(val, path.clone(), &mut member);
+                    errors.extend_from(member);
                 }
-
-                Ok(())
             }
 
             // TODO: implement subtle differences in anyOf and oneOf
@@ -204,81 +464,298 @@ impl ValidationTree {
                 // match this val against sub-valtrees
                 // error if more than one match
 
-                let mut matched = false;
-                let mut errors = AggregateError::empty();
+                let mut failures = AggregateError::empty();
+                let mut failure_count = 0;
+                let mut truncated = false;
+                let budget = errors.remaining();
 
                 for v in vs {
-                    match v.validate_inner(val, path.clone()) {
-                        Ok(_) => {
-                            matched = true;
-                            break;
+                    let mut attempt = errors.child();
+                    v.validate_inner(val, path.clone(), &mut attempt);
+
+                    if attempt.errors.is_empty() {
+                        return;
+                    }
+
+                    truncated |= attempt.truncated;
+
+                    for err in attempt.errors {
+                        if failure_count < budget {
+                            failures.push(err);
+                            failure_count += 1;
+                        } else {
+                            truncated = true;
                         }
-                        Err(err) => errors.push(err),
                     }
                 }
 
-                if matched {
-                    Ok(())
-                } else {
-                    Err(Error::OneOfNoMatch(path, errors))
-                }
+                // a truncated branch only matters if no branch matched
+                errors.push(Error::OneOfNoMatch(path, failures));
+                errors.truncated |= truncated;
             }
 
             ValidationBranch::Array(v) => {
                 // validate own valtree level and throw any errors
-                for v in &self.validators {
-                    v.validate(val, path.clone())?
-                }
+                let own_failed = self.validate_own(val, &path, errors);
 
                 match val {
                     JsonValue::Array(items) => {
                         for (i, item) in items.iter().enumerate() {
+                            if errors.is_done() {
+                                return;
+                            }
+
                             let child_path = path.extend(format!("[{}]", i));
-                            v.validate_inner(item, child_path)?;
+                            v.validate_inner(item, child_path, errors);
                         }
                     }
-                    _ => return Err(Error::TypeMismatch(path, SchemaType::Array)),
+                    _ if own_failed => {}
+                    // the type validator accepted null, so the schema is nullable
+                    JsonValue::Null if !self.validators.is_empty() => {}
+                    _ => errors.push(Error::TypeMismatch(path, SchemaType::Array)),
                 }
+            }
 
-                Ok(())
+            ValidationBranch::Tuple(prefix, rest) => {
+                // validate own valtree level and throw any errors
+                let own_failed = self.validate_own(val, &path, errors);
+
+                match val {
+                    JsonValue::Array(items) => {
+                        for (i, item) in items.iter().enumerate() {
+                            if errors.is_done() {
+                                return;
+                            }
+
+                            let child_path = path.extend(format!("[{}]", i));
+
+                            if let Some(v) = prefix.get(i).or(rest.as_deref()) {
+                                v.validate_inner(item, child_path, errors);
+                            }
+                        }
+                    }
+                    _ if own_failed => {}
+                    // the type validator accepted null, so the schema is nullable
+                    JsonValue::Null if !self.validators.is_empty() => {}
+                    _ => errors.push(Error::TypeMismatch(path, SchemaType::Array)),
+                }
             }
 
             ValidationBranch::Object(validator_map) => {
                 // validate own valtree level and throw any errors
-                for v in &self.validators {
-                    v.validate(val, path.clone())?
-                }
+                let own_failed = self.validate_own(val, &path, errors);
 
                 match val {
                     JsonValue::Object(items) => {
                         for (prop, val) in items {
+                            if errors.is_done() {
+                                return;
+                            }
+
                             let child_path = path.extend(prop);
 
                             if let Some(validator) = validator_map.get(prop) {
-                                validator.validate_inner(val, child_path)?;
+                                validator.validate_inner(val, child_path, errors);
                             } else {
-                                return Err(Error::UndocumentedField(child_path.to_string()));
+                                errors.push(Error::UndocumentedField(child_path.to_string()));
                             }
                         }
                     }
-                    _ => return Err(Error::TypeMismatch(path, SchemaType::Object)),
+                    _ if own_failed => {}
+                    // the type validator accepted null, so the schema is nullable
+                    JsonValue::Null if !self.validators.is_empty() => {}
+                    _ => errors.push(Error::TypeMismatch(path, SchemaType::Object)),
                 }
+            }
+
+            ValidationBranch::Map(validator_map, additional) => {
+                // validate own valtree level and throw any errors
+                let own_failed = self.validate_own(val, &path, errors);
 
-                Ok(())
+                match val {
+                    JsonValue::Object(items) => {
+                        for (prop, val) in items {
+                            if errors.is_done() {
+                                return;
+                            }
+
+                            let child_path = path.extend(prop);
+
+                            validator_map
+                                .get(prop)
+                                .unwrap_or(additional)
+                                .validate_inner(val, child_path, errors);
+                        }
+                    }
+                    _ if own_failed => {}
+                    // the type validator accepted null, so the schema is nullable
+                    JsonValue::Null if !self.validators.is_empty() => {}
+                    _ => errors.push(Error::TypeMismatch(path, SchemaType::Object)),
+                }
             }
 
             ValidationBranch::Leaf => {
                 // validate own valtree level and throw any errors
-                for v in &self.validators {
-                    v.validate(val, path.clone())?
+                self.validate_own(val, &path, errors);
+            }
+
+            ValidationBranch::Ref(target) => {
+                if let Some(tree) = target.get() {
+                    tree.validate_inner(val, path, errors);
                 }
+            }
 
-                Ok(())
+            ValidationBranch::Recursive(target) => {
+                // the target is owned by an enclosing `Ref` tree, so it is alive unless this
+                // tree was moved out of it
+                if let Some(target) = target.upgrade() {
+                    if let Some(tree) = target.get() {
+                        tree.validate_inner(val, path, errors);
+                    }
+                }
             }
         }
     }
 }
 
+/// Builds validation trees, tracking the referenced schemas being expanded.
+struct Builder<'a> {
+    spec: &'a Spec,
+    formats: &'a FormatRegistry,
+    expanding: RefCell<Vec<(String, Arc<OnceCell<ValidationTree>>)>>,
+}
+
+/// Options controlling how values are validated.
+#[derive(Debug, Clone, Default)]
+pub struct ValidationOptions {
+    /// Whether to collect all errors instead of stopping at the first one.
+    ///
+    /// Both modes traverse the value in the same order. Collecting all errors gives more useful
+    /// feedback, e.g. when checking documented examples, but keeps validating after the outcome
+    /// is known, so stopping early is faster for high-throughput request validation.
+    pub collect_all: bool,
+
+    /// Maximum number of errors to collect when [`collect_all`](Self::collect_all) is set.
+    ///
+    /// Validation stops once an error beyond the limit is found, and the result is marked as
+    /// [`truncated`](ValidationErrors::truncated). This bounds the work and memory spent on
+    /// adversarial values. A limit of zero is treated as one. Has no effect without
+    /// `collect_all`, which already stops at the first error.
+    pub max_errors: Option<usize>,
+}
+
+/// Errors returned by [`ValidationTree::validate_with`].
+#[derive(Debug)]
+pub struct ValidationErrors {
+    /// Errors found, in traversal order. Never empty.
+    pub errors: Vec<Error>,
+
+    /// Whether more errors were found than [`ValidationOptions::max_errors`] allows.
+    pub truncated: bool,
+}
+
+/// Errors found so far during a validation.
+struct Errors {
+    errors: Vec<Error>,
+    collect_all: bool,
+    max_errors: usize,
+    truncated: bool,
+    ignore_undocumented: bool,
+}
+
+impl Errors {
+    fn new(options: &ValidationOptions) -> Self {
+        Self {
+            errors: vec![],
+            collect_all: options.collect_all,
+            max_errors: options.max_errors.unwrap_or(usize::MAX).max(1),
+            truncated: false,
+            ignore_undocumented: false,
+        }
+    }
+
+    /// Returns an empty collection with the same mode and the remaining error budget, for
+    /// validating alternatives.
+    fn child(&self) -> Self {
+        Self {
+            errors: vec![],
+            max_errors: self.remaining(),
+            truncated: false,
+            ignore_undocumented: false,
+            ..*self
+        }
+    }
+
+    /// Returns an empty collection like [`Errors::child`] for validating an `allOf` member,
+    /// which drops undocumented field errors without counting them against the budget.
+    fn member(&self) -> Self {
+        Self {
+            ignore_undocumented: true,
+            ..self.child()
+        }
+    }
+
+    /// Returns how many more errors can be collected, but at least one.
+    fn remaining(&self) -> usize {
+        self.max_errors.saturating_sub(self.errors.len()).max(1)
+    }
+
+    /// Adds the errors of a child collection.
+    fn extend_from(&mut self, child: Errors) {
+        self.truncated |= child.truncated;
+
+        for err in child.errors {
+            self.push(err);
+        }
+    }
+
+    fn push(&mut self, err: Error) {
+        if self.is_done()
+            || (self.ignore_undocumented && matches!(err, Error::UndocumentedField(_)))
+        {
+            return;
+        }
+
+        if self.errors.len() < self.max_errors {
+            self.errors.push(err)
+        } else {
+            self.truncated = true;
+        }
+    }
+
+    /// Returns true if validation can stop because no further errors are wanted.
+    fn is_done(&self) -> bool {
+        (!self.collect_all && !self.errors.is_empty()) || self.truncated
+    }
+}
+
+impl Schema {
+    /// Validates `val` against this schema, applying the keyword semantics of its JSON Schema
+    /// dialect.
+    ///
+    /// The dialect is given by the schema's `$schema`, then the spec's `jsonSchemaDialect`, and
+    /// defaults to 2020-12. Unsupported dialects result in an [`Error::Schema`].
+    pub fn validate_against_dialect(&self, spec: &Spec, val: &JsonValue) -> Result<(), Error> {
+        self.compile(spec)?.validate(val)
+    }
+
+    /// Validates `val` against this schema like [`Schema::validate_against_dialect`], returning
+    /// the errors found as configured by `options`.
+    pub fn validate_with(
+        &self,
+        spec: &Spec,
+        val: &JsonValue,
+        options: &ValidationOptions,
+    ) -> Result<(), ValidationErrors> {
+        ValidationTree::from_schema(self, spec)
+            .map_err(|err| ValidationErrors {
+                errors: vec![Error::Schema(err)],
+                truncated: false,
+            })?
+            .validate_with(val, options)
+    }
+}
+
 impl fmt::Debug for ValidationTree {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("ValidationTree")
@@ -297,7 +774,10 @@ mod tests {
     use serde_json::json;
 
     use super::{super::tests::*, *};
-    use crate::validation::RequiredFields;
+    use crate::{
+        spec::SchemaContext,
+        validation::{RequiredFields, StringFormat},
+    };
 
     fn get_schema(spec: &Spec, name: &str) -> Schema {
         spec.components
@@ -439,6 +919,46 @@ components:
         valtree.validate(&test).unwrap();
     }
 
+    #[test]
+    fn string_format_from_schema() {
+        let spec_str = r#"openapi: "3"
+paths: {}
+info:
+  title: Test API
+  version: "0.1"
+components:
+  schemas:
+    upload:
+      type: object
+      properties:
+        file: { type: string, format: binary }
+        checksum: { type: string, format: byte }
+"#;
+
+        let spec = crate::from_reader(spec_str.as_bytes()).unwrap();
+
+        let schema = get_schema(&spec, "upload");
+        let formats = FormatRegistry::standard();
+        let valtree = ValidationTree::from_schema_with_formats(&schema, &spec, &formats).unwrap();
+
+        let test = json!({ "file": "\u{89}PNG", "checksum": "c3RyaW5n" });
+        valtree.validate(&test).unwrap();
+
+        let test = json!({ "checksum": "not base64" });
+        assert!(matches!(
+            valtree.validate(&test),
+            Err(Error::InvalidFormat(_, "byte"))
+        ));
+
+        // formats are annotations only by default
+        let valtree = ValidationTree::from_schema(&schema, &spec).unwrap();
+        valtree.validate(&test).unwrap();
+
+        let formats = FormatRegistry::standard().disable(StringFormat::Byte);
+        let valtree = ValidationTree::from_schema_with_formats(&schema, &spec, &formats).unwrap();
+        valtree.validate(&test).unwrap();
+    }
+
     #[test]
     fn all_of_from_schema() {
         let spec_str = r#"openapi: "3"
@@ -476,6 +996,328 @@ components:
 
         let test = json!({ "meta": "meta", "size": 123 });
         valtree.validate(&test).unwrap();
+
+        // extraneous fields are ignored in allOf schemas by default
+        let test = json!({ "meta": "meta", "size": 123, "other": true });
+        valtree.validate(&test).unwrap();
+    }
+
+    #[test]
+    fn all_of_required_from_members() {
+        let spec_str = r#"openapi: "3.1.0"
+paths: {}
+info:
+  title: Test API
+  version: "0.1"
+components:
+  schemas:
+    data:
+      allOf:
+      - { $ref: '#/components/schemas/base' }
+      - properties:
+          meta: { type: string }
+      required: [id, meta]
+    base:
+      type: object
+      properties:
+        id: { type: integer }
+"#;
+
+        let spec = crate::from_reader(spec_str.as_bytes()).unwrap();
+
+        let schema = get_schema(&spec, "data");
+        assert!(schema.required_but_not_in_properties(&spec).is_empty());
+
+        let valtree = ValidationTree::from_schema(&schema, &spec).unwrap();
+        valtree
+            .validate(&json!({ "id": 1, "meta": "meta" }))
+            .unwrap();
+
+        assert!(matches!(
+            valtree.validate(&json!({ "meta": "meta" })),
+            Err(Error::RequiredFieldMissing(_))
+        ));
+    }
+
+    #[test]
+    fn null_handling() {
+        let spec_str = r#"openapi: "3.1.0"
+paths: {}
+info:
+  title: Test API
+  version: "0.1"
+components:
+  schemas:
+    string:
+      type: string
+    nullString:
+      type: [string, "null"]
+    null:
+      type: "null"
+    enumWithNull:
+      type: [string, "null"]
+      enum: [a, null]
+    enumWithNullNotNullable:
+      type: string
+      enum: [a, null]
+    nullableObject:
+      type: object
+      nullable: true
+      required: [id]
+      properties:
+        id: { type: integer }
+    nullableArray:
+      type: [array, "null"]
+      items: { type: integer }
+    object:
+      type: object
+      required: [id]
+      properties:
+        id: { type: integer }
+"#;
+
+        let spec = crate::from_reader(spec_str.as_bytes()).unwrap();
+        let valtree = |name: &str| {
+            let schema = get_schema(&spec, name);
+
+            // `nullable` schemas behave exactly like their 3.1 upconversions
+            let upconverted = ValidationTree::from_schema(&schema.to_3_1(), &spec).unwrap();
+            let valtree = ValidationTree::from_schema(&schema, &spec).unwrap();
+            assert_eq!(
+                valtree.validate(&NULL).is_ok(),
+                upconverted.validate(&NULL).is_ok()
+            );
+
+            valtree
+        };
+
+        assert!(matches!(
+            valtree("string").validate(&NULL),
+            Err(Error::InvalidNull(_))
+        ));
+        valtree("nullString").validate(&NULL).unwrap();
+        valtree("nullString").validate(&json!("a")).unwrap();
+        valtree("null").validate(&NULL).unwrap();
+        assert!(valtree("null").validate(&json!("a")).is_err());
+        valtree("enumWithNull").validate(&NULL).unwrap();
+        assert!(matches!(
+            valtree("enumWithNullNotNullable").validate(&NULL),
+            Err(Error::InvalidNull(_))
+        ));
+
+        let nullable_object = valtree("nullableObject");
+        nullable_object.validate(&NULL).unwrap();
+        nullable_object.validate(&json!({ "id": 1 })).unwrap();
+        assert!(matches!(
+            nullable_object.validate(&OBJ_EMPTY),
+            Err(Error::RequiredFieldMissing(_))
+        ));
+        assert!(nullable_object.validate(&json!("a")).is_err());
+
+        let nullable_array = valtree("nullableArray");
+        nullable_array.validate(&NULL).unwrap();
+        nullable_array.validate(&json!([1, 2])).unwrap();
+        assert!(nullable_array.validate(&json!([1, null])).is_err());
+
+        assert!(matches!(
+            valtree("object").validate(&NULL),
+            Err(Error::InvalidNull(_))
+        ));
+    }
+
+    #[test]
+    fn multiple_types() {
+        let spec_str = r#"openapi: "3.1.0"
+paths: {}
+info:
+  title: Test API
+  version: "0.1"
+components:
+  schemas:
+    stringOrInteger:
+      type: [string, integer]
+      pattern: "^[a-z]+$"
+      multipleOf: 2
+    stringOrIntegerOrNull:
+      type: [string, integer, "null"]
+    objectOrString:
+      type: [object, string]
+      required: [id]
+      properties:
+        id: { type: integer }
+    objectOrNull:
+      type: [object, "null"]
+      required: [id]
+      properties:
+        id: { type: integer }
+    arrayOrObject:
+      type: [array, object]
+      items: { type: integer }
+      properties:
+        id: { type: integer }
+    integerOrNumber:
+      type: [integer, number]
+      multipleOf: 0.5
+"#;
+
+        let spec = crate::from_reader(spec_str.as_bytes()).unwrap();
+        let valtree =
+            |name: &str| ValidationTree::from_schema(&get_schema(&spec, name), &spec).unwrap();
+
+        let string_or_integer = valtree("stringOrInteger");
+        string_or_integer.validate(&json!("abc")).unwrap();
+        string_or_integer.validate(&json!(4)).unwrap();
+        assert!(string_or_integer.validate(&json!("ABC")).is_err());
+        assert!(string_or_integer.validate(&json!(3)).is_err());
+        assert!(string_or_integer.validate(&json!(1.5)).is_err());
+        assert!(string_or_integer.validate(&json!(true)).is_err());
+        assert!(string_or_integer.validate(&NULL).is_err());
+
+        let string_or_integer_or_null = valtree("stringOrIntegerOrNull");
+        string_or_integer_or_null.validate(&NULL).unwrap();
+        string_or_integer_or_null.validate(&json!("a")).unwrap();
+        string_or_integer_or_null.validate(&json!(1)).unwrap();
+        assert!(string_or_integer_or_null.validate(&OBJ_EMPTY).is_err());
+
+        let object_or_string = valtree("objectOrString");
+        object_or_string.validate(&json!("a")).unwrap();
+        object_or_string.validate(&json!({ "id": 1 })).unwrap();
+        assert!(object_or_string.validate(&OBJ_EMPTY).is_err());
+        assert!(object_or_string.validate(&json!({ "id": "a" })).is_err());
+        assert!(object_or_string.validate(&json!(1)).is_err());
+
+        let object_or_null = valtree("objectOrNull");
+        object_or_null.validate(&NULL).unwrap();
+        object_or_null.validate(&json!({ "id": 1 })).unwrap();
+        assert!(object_or_null.validate(&OBJ_EMPTY).is_err());
+        assert!(object_or_null.validate(&json!("a")).is_err());
+
+        let array_or_object = valtree("arrayOrObject");
+        array_or_object.validate(&json!([1, 2])).unwrap();
+        array_or_object.validate(&json!({ "id": 1 })).unwrap();
+        assert!(array_or_object.validate(&json!(["a"])).is_err());
+        assert!(array_or_object.validate(&json!({ "id": "a" })).is_err());
+
+        let integer_or_number = valtree("integerOrNumber");
+        integer_or_number.validate(&json!(2)).unwrap();
+        integer_or_number.validate(&json!(2.5)).unwrap();
+        assert!(integer_or_number.validate(&json!(2.25)).is_err());
+    }
+
+    #[test]
+    fn boolean_additional_properties() {
+        let spec_str = r#"openapi: "3.1.0"
+paths: {}
+info:
+  title: Test API
+  version: "0.1"
+components:
+  schemas:
+    open:
+      type: object
+      properties:
+        id: { type: integer }
+      additionalProperties: true
+    closed:
+      type: object
+      properties:
+        id: { type: integer }
+      additionalProperties: false
+"#;
+
+        let spec = crate::from_reader(spec_str.as_bytes()).unwrap();
+        let test = json!({ "id": 1, "other": [true] });
+
+        let open = ValidationTree::from_schema(&get_schema(&spec, "open"), &spec).unwrap();
+        open.validate(&test).unwrap();
+        assert!(open.validate(&json!({ "id": "1" })).is_err());
+
+        let closed = ValidationTree::from_schema(&get_schema(&spec, "closed"), &spec).unwrap();
+        assert!(matches!(
+            closed.validate(&test),
+            Err(Error::UndocumentedField(_))
+        ));
+    }
+
+    #[test]
+    fn all_of_unevaluated_properties() {
+        let spec_str = r#"openapi: "3.1.0"
+paths: {}
+info:
+  title: Test API
+  version: "0.1"
+components:
+  schemas:
+    data:
+      allOf:
+      - { $ref: '#/components/schemas/size' }
+      - type: object
+        properties:
+          meta: { type: string }
+      unevaluatedProperties: false
+    size:
+      type: object
+      properties:
+        size: { type: integer }
+"#;
+
+        let spec = crate::from_reader(spec_str.as_bytes()).unwrap();
+
+        let schema = get_schema(&spec, "data");
+        let valtree = ValidationTree::from_schema(&schema, &spec).unwrap();
+        assert_eq!(valtree.validators.len(), 1);
+
+        let test = json!({ "meta": "meta", "size": 123 });
+        valtree.validate(&test).unwrap();
+
+        let test = json!({ "meta": "meta", "size": 123, "other": true });
+        assert!(matches!(
+            valtree.validate(&test),
+            Err(Error::UndocumentedField(_))
+        ));
+    }
+
+    #[test]
+    fn read_only_required_in_context() {
+        let spec_str = r#"openapi: "3.1.0"
+paths: {}
+info:
+  title: Test API
+  version: "0.1"
+components:
+  schemas:
+    pet:
+      type: object
+      required: [id, name, password]
+      properties:
+        id: { type: integer, readOnly: true }
+        name: { type: string }
+        password: { $ref: '#/components/schemas/password' }
+    password:
+      type: string
+      writeOnly: true
+"#;
+
+        let spec = crate::from_reader(spec_str.as_bytes()).unwrap();
+        let schema = get_schema(&spec, "pet");
+
+        let request = schema.for_context(&spec, SchemaContext::Request);
+        assert_eq!(request.required, ["name", "password"]);
+        assert!(!request.properties.contains_key("id"));
+
+        let valtree = ValidationTree::from_schema(&request, &spec).unwrap();
+        valtree
+            .validate(&json!({ "name": "Rex", "password": "secret" }))
+            .unwrap();
+
+        let response = schema.for_context(&spec, SchemaContext::Response);
+        assert_eq!(response.required, ["id", "name"]);
+
+        let valtree = ValidationTree::from_schema(&response, &spec).unwrap();
+        valtree
+            .validate(&json!({ "id": 1, "name": "Rex" }))
+            .unwrap();
+        assert!(valtree.validate(&json!({ "name": "Rex" })).is_err());
     }
 
     #[test]
@@ -526,4 +1368,329 @@ components:
         let test = json!([123, null, 789]);
         valtree.validate(&test).unwrap_err();
     }
+
+    #[test]
+    fn prefix_items_by_dialect() {
+        let spec_str = r#"openapi: "3.1.0"
+paths: {}
+info:
+  title: Test API
+  version: "0.1"
+components:
+  schemas:
+    pair:
+      type: array
+      prefixItems: [{ type: string }]
+      items: { type: integer }
+"#;
+
+        let mut spec = crate::from_reader(spec_str.as_bytes()).unwrap();
+        let schema = get_schema(&spec, "pair");
+
+        schema
+            .validate_against_dialect(&spec, &json!(["a", 1, 2]))
+            .unwrap();
+        schema
+            .validate_against_dialect(&spec, &json!(["a", "b"]))
+            .unwrap_err();
+
+        // in draft-07, `items` applies to all items
+        spec.json_schema_dialect = Some("http://json-schema.org/draft-07/schema#".to_owned());
+        schema
+            .validate_against_dialect(&spec, &json!([1, 2]))
+            .unwrap();
+        schema
+            .validate_against_dialect(&spec, &json!(["a", 1]))
+            .unwrap_err();
+
+        // a schema's own `$schema` takes precedence
+        let schema = Schema {
+            schema_dialect: Some("https://json-schema.org/draft/2020-12/schema".to_owned()),
+            ..schema
+        };
+        schema
+            .validate_against_dialect(&spec, &json!(["a", 1]))
+            .unwrap();
+
+        spec.json_schema_dialect = Some("http://json-schema.org/draft-04/schema#".to_owned());
+        let err = ValidationTree::from_schema(&get_schema(&spec, "pair"), &spec).unwrap_err();
+        assert!(matches!(err, SchemaError::UnsupportedDialect(_)));
+    }
+
+    #[test]
+    fn nested_refs() {
+        let spec_str = r##"openapi: "3.1.0"
+paths: {}
+info:
+  title: Test API
+  version: "0.1"
+components:
+  schemas:
+    Tag: { type: string }
+    Count: { type: integer }
+    Item:
+      type: object
+      properties:
+        tags: { type: array, items: { $ref: "#/components/schemas/Tag" } }
+    pair:
+      type: array
+      prefixItems: [{ $ref: "#/components/schemas/Tag" }]
+      items: { $ref: "#/components/schemas/Count" }
+    counts:
+      type: object
+      properties:
+        total: { $ref: "#/components/schemas/Count" }
+      additionalProperties: { $ref: "#/components/schemas/Count" }
+    items:
+      type: object
+      additionalProperties: { $ref: "#/components/schemas/Item" }
+"##;
+
+        let spec = crate::from_reader(spec_str.as_bytes()).unwrap();
+        let valtree =
+            |name: &str| ValidationTree::from_schema(&get_schema(&spec, name), &spec).unwrap();
+
+        let item = valtree("Item");
+        item.validate(&json!({ "tags": ["a"] })).unwrap();
+        item.validate(&json!({ "tags": [1] })).unwrap_err();
+
+        let pair = valtree("pair");
+        pair.validate(&json!(["a", 1, 2])).unwrap();
+        pair.validate(&json!([1, 2])).unwrap_err();
+        pair.validate(&json!(["a", "b"])).unwrap_err();
+
+        let counts = valtree("counts");
+        counts
+            .validate(&json!({ "total": 3, "cats": 1, "dogs": 2 }))
+            .unwrap();
+        counts
+            .validate(&json!({ "total": 3, "cats": "many" }))
+            .unwrap_err();
+
+        let items = valtree("items");
+        items.validate(&json!({ "a": { "tags": ["x"] } })).unwrap();
+        match items
+            .validate(&json!({ "a": { "tags": [1] } }))
+            .unwrap_err()
+        {
+            Error::TypeMismatch(path, SchemaType::String) => {
+                assert_eq!(path.to_string(), "a.tags.[0]")
+            }
+            err => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn recursive_refs() {
+        let spec_str = r##"openapi: "3.1.0"
+paths: {}
+info:
+  title: Test API
+  version: "0.1"
+components:
+  schemas:
+    Node:
+      type: object
+      properties:
+        value: { type: integer }
+        child: { $ref: "#/components/schemas/Node" }
+    Tree:
+      anyOf:
+        - { type: integer }
+        - { type: array, items: { $ref: "#/components/schemas/Forest" } }
+    Forest: { type: array, items: { $ref: "#/components/schemas/Tree" } }
+"##;
+
+        let spec = crate::from_reader(spec_str.as_bytes()).unwrap();
+        let valtree =
+            |name: &str| ValidationTree::from_schema(&get_schema(&spec, name), &spec).unwrap();
+
+        let node = valtree("Node");
+        node.validate(&json!({ "child": { "child": { "value": 1 } } }))
+            .unwrap();
+        match node
+            .validate(&json!({ "child": { "child": { "value": "one" } } }))
+            .unwrap_err()
+        {
+            Error::TypeMismatch(path, SchemaType::Integer) => {
+                assert_eq!(path.to_string(), "child.child.value")
+            }
+            err => panic!("unexpected error: {}", err),
+        }
+
+        let tree = valtree("Tree");
+        tree.validate(&json!([[1, [[2]]], []])).unwrap();
+        tree.validate(&json!([[1, [["two"]]]])).unwrap_err();
+    }
+
+    #[test]
+    fn unresolvable_nested_ref() {
+        let spec_str = r##"openapi: "3.1.0"
+paths: {}
+info:
+  title: Test API
+  version: "0.1"
+components:
+  schemas:
+    list:
+      type: array
+      items: { $ref: "#/components/schemas/Missing" }
+"##;
+
+        let spec = crate::from_reader(spec_str.as_bytes()).unwrap();
+        let err = ValidationTree::from_schema(&get_schema(&spec, "list"), &spec).unwrap_err();
+        assert!(matches!(err, SchemaError::Ref(_)));
+    }
+
+    #[test]
+    fn collect_all_errors() {
+        let spec_str = r#"openapi: "3.1.0"
+paths: {}
+info:
+  title: Test API
+  version: "0.1"
+components:
+  schemas:
+    pet:
+      type: object
+      required: [name]
+      properties:
+        name: { type: string }
+        age: { type: integer }
+        tags: { type: array, items: { type: string } }
+"#;
+
+        let spec = crate::from_reader(spec_str.as_bytes()).unwrap();
+        let schema = get_schema(&spec, "pet");
+        let valtree = ValidationTree::from_schema(&schema, &spec).unwrap();
+
+        let invalid = json!({ "age": "old", "tags": [1, "a", 2], "color": "red" });
+
+        let first = valtree
+            .validate_with(&invalid, &ValidationOptions::default())
+            .unwrap_err();
+        assert_eq!(first.errors.len(), 1);
+        assert_eq!(
+            first.errors[0].to_string(),
+            valtree.validate(&invalid).unwrap_err().to_string()
+        );
+
+        let options = ValidationOptions {
+            collect_all: true,
+            ..ValidationOptions::default()
+        };
+        let all = schema.validate_with(&spec, &invalid, &options).unwrap_err();
+        assert!(!all.truncated);
+        assert_eq!(
+            all.errors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            [
+                "Required field missing: name",
+                "age is not a Integer",
+                "Undocumented field: color",
+                "tags.[0] is not a String",
+                "tags.[2] is not a String",
+            ]
+        );
+
+        schema
+            .validate_with(&spec, &json!({ "name": "Rex" }), &options)
+            .unwrap();
+
+        let options = ValidationOptions {
+            collect_all: true,
+            max_errors: Some(2),
+        };
+        let capped = schema.validate_with(&spec, &invalid, &options).unwrap_err();
+        assert!(capped.truncated);
+        assert_eq!(capped.errors.len(), 2);
+
+        let options = ValidationOptions {
+            collect_all: true,
+            max_errors: Some(5),
+        };
+        let exact = schema.validate_with(&spec, &invalid, &options).unwrap_err();
+        assert!(!exact.truncated);
+        assert_eq!(exact.errors.len(), 5);
+    }
+
+    #[test]
+    fn max_errors_in_matching_any_of() {
+        let schema: Schema = serde_json::from_value(json!({
+            "type": "object",
+            "properties": {
+                "a": {
+                    "anyOf": [
+                        {
+                            "type": "object",
+                            "properties": {
+                                "p": { "type": "integer" },
+                                "q": { "type": "integer" }
+                            }
+                        },
+                        { "type": "object", "additionalProperties": true }
+                    ]
+                },
+                "b": { "type": "integer" }
+            }
+        }))
+        .unwrap();
+        let spec = Spec::minimal_valid("Test", "1");
+
+        let invalid = json!({ "a": { "p": "x", "q": "y" }, "b": "notint" });
+        let options = ValidationOptions {
+            collect_all: true,
+            max_errors: Some(1),
+        };
+
+        // the failed first branch exceeds the budget, but must not hide the error in `b`
+        let errors = schema.validate_with(&spec, &invalid, &options).unwrap_err();
+        assert_eq!(errors.errors.len(), 1);
+        assert_eq!(errors.errors[0].to_string(), "b is not a Integer");
+        assert!(!errors.truncated);
+
+        // a failing `anyOf` holds no more errors than the remaining budget
+        let invalid = json!({ "a": 1 });
+        let errors = schema.validate_with(&spec, &invalid, &options).unwrap_err();
+        match &errors.errors[..] {
+            [Error::OneOfNoMatch(_, failures)] => {
+                assert_eq!(failures.to_string().lines().count(), 1)
+            }
+            errs => panic!("unexpected errors: {:?}", errs),
+        }
+        assert!(errors.truncated);
+    }
+
+    #[test]
+    fn max_errors_in_all_of() {
+        let schema: Schema = serde_json::from_value(json!({
+            "allOf": [
+                {
+                    "type": "object",
+                    "properties": { "a": { "type": "string" } },
+                    "additionalProperties": false
+                },
+                {
+                    "type": "object",
+                    "properties": { "b": { "type": "integer" } }
+                }
+            ]
+        }))
+        .unwrap();
+        let spec = Spec::minimal_valid("Test", "1");
+
+        let invalid = json!({ "a": "x", "b": "y", "c": 1 });
+        let options = ValidationOptions {
+            collect_all: true,
+            max_errors: Some(1),
+        };
+
+        // the ignored undocumented fields of the first member must not use up the budget
+        let errors = schema.validate_with(&spec, &invalid, &options).unwrap_err();
+        assert_eq!(errors.errors.len(), 1);
+        assert_eq!(errors.errors[0].to_string(), "b is not a Integer");
+        assert!(!errors.truncated);
+    }
 }